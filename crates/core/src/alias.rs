@@ -0,0 +1,189 @@
+//! Vanity URLs such as `/c/rustaceans/general` map to aliases on our
+//! homeserver: `#rustaceans:example.org` for the space and
+//! `#rustaceans.general:example.org` for one of its boards. Slugs cannot
+//! contain dots, so the mapping goes both ways.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use matrix::ruma_common::{OwnedRoomAliasId, RoomAliasId, ServerName};
+
+use crate::error::{Error, Result};
+
+pub mod claim;
+pub mod resolve;
+
+/// Slugs that would clash with our own routes or impersonate staff.
+pub const RESERVED: &[&str] = &[
+    "about",
+    "admin",
+    "administrator",
+    "api",
+    "c",
+    "commune",
+    "help",
+    "login",
+    "logout",
+    "matrix",
+    "mod",
+    "moderator",
+    "register",
+    "settings",
+    "static",
+    "support",
+    "system",
+    "u",
+    "user",
+    "www",
+];
+
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+static CACHE: Mutex<BTreeMap<OwnedRoomAliasId, (resolve::Resolved, Instant)>> =
+    Mutex::new(BTreeMap::new());
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Slug {
+    pub space: String,
+
+    pub board: Option<String>,
+}
+
+impl Slug {
+    pub fn new(space: impl Into<String>, board: Option<String>) -> Result<Self> {
+        let space = space.into();
+
+        validate(&space)?;
+
+        if RESERVED.contains(&space.as_str()) {
+            return Err(Error::ReservedAlias);
+        }
+
+        if let Some(board) = board.as_deref() {
+            validate(board)?;
+        }
+
+        Ok(Self { space, board })
+    }
+
+    /// Parses `space` or `space/board`.
+    pub fn parse(slug: &str) -> Result<Self> {
+        match slug.split_once('/') {
+            Some((space, board)) => Self::new(space, Some(board.to_owned())),
+            None => Self::new(slug, None),
+        }
+    }
+
+    pub fn alias(&self, server_name: &ServerName) -> OwnedRoomAliasId {
+        let localpart = match &self.board {
+            Some(board) => format!("{}.{board}", self.space),
+            None => self.space.clone(),
+        };
+
+        RoomAliasId::parse(format!("#{localpart}:{server_name}"))
+            .expect("validated slugs should form valid aliases")
+    }
+
+    /// Aliases on other homeservers or not following our scheme have no slug.
+    pub fn from_alias(alias: &RoomAliasId, server_name: &ServerName) -> Option<Self> {
+        if alias.server_name() != server_name {
+            return None;
+        }
+
+        let slug = match alias.alias().split_once('.') {
+            Some((space, board)) => Self::new(space, Some(board.to_owned())),
+            None => Self::new(alias.alias(), None),
+        };
+
+        slug.ok()
+    }
+}
+
+impl fmt::Display for Slug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.board {
+            Some(board) => write!(f, "{}/{board}", self.space),
+            None => write!(f, "{}", self.space),
+        }
+    }
+}
+
+fn validate(part: &str) -> Result<()> {
+    match (1..=64).contains(&part.len())
+        && part
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        true => Ok(()),
+        false => Err(Error::InvalidSlug),
+    }
+}
+
+pub(crate) fn cached(alias: &RoomAliasId) -> Option<resolve::Resolved> {
+    let mut cache = CACHE.lock().unwrap();
+
+    match cache.get(alias) {
+        Some((resolved, at)) if at.elapsed() < CACHE_TTL => Some(resolved.clone()),
+        Some(_) => {
+            let _ = cache.remove(alias);
+
+            None
+        }
+        None => None,
+    }
+}
+
+pub(crate) fn cache(alias: OwnedRoomAliasId, resolved: resolve::Resolved) {
+    let _ = CACHE
+        .lock()
+        .unwrap()
+        .insert(alias, (resolved, Instant::now()));
+}
+
+pub(crate) fn invalidate(alias: &RoomAliasId) {
+    let _ = CACHE.lock().unwrap().remove(alias);
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::{room_alias_id, server_name};
+
+    use super::*;
+
+    #[test]
+    fn maps_slugs_to_aliases() {
+        let server_name = server_name!("example.org");
+
+        let slug = Slug::parse("rustaceans/general").unwrap();
+        assert_eq!(
+            slug.alias(server_name),
+            room_alias_id!("#rustaceans.general:example.org")
+        );
+        assert_eq!(
+            Slug::from_alias(&slug.alias(server_name), server_name),
+            Some(slug)
+        );
+
+        let slug = Slug::parse("rustaceans").unwrap();
+        assert_eq!(slug.to_string(), "rustaceans");
+
+        assert!(Slug::from_alias(room_alias_id!("#rustaceans:example.com"), server_name).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_slugs() {
+        assert!(matches!(Slug::parse("admin"), Err(Error::ReservedAlias)));
+        assert!(matches!(
+            Slug::parse("admin/general"),
+            Err(Error::ReservedAlias)
+        ));
+        assert!(matches!(Slug::parse("Rust"), Err(Error::InvalidSlug)));
+        assert!(matches!(Slug::parse("rust.lang"), Err(Error::InvalidSlug)));
+        assert!(matches!(Slug::parse("rust/a/b"), Err(Error::InvalidSlug)));
+        assert!(matches!(Slug::parse(""), Err(Error::InvalidSlug)));
+    }
+}
@@ -0,0 +1,119 @@
+use http::StatusCode;
+use matrix::{
+    client::{
+        alias::{get, set},
+        state,
+    },
+    ruma_client::Error::FromHttpResponse,
+    ruma_common::{
+        api::error::{FromHttpResponseError, MatrixError},
+        OwnedRoomId,
+    },
+    ruma_events::{room::canonical_alias::RoomCanonicalAliasEventContent, StateEventType},
+};
+
+use super::{resolve::Resolved, Slug};
+use crate::{
+    commune,
+    error::{Error, Result},
+    space,
+    util::auth,
+};
+
+/// Points an alias at a room and makes it canonical. The previous canonical
+/// alias is kept as an alternative, so old links redirect to the new one.
+///
+/// Board aliases live under the alias of their space, only those allowed to
+/// change the aliases of the space can claim them.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    slug: Slug,
+) -> Result<Resolved> {
+    let access_token = access_token.as_ref();
+    let server_name = &commune().config.matrix.server_name;
+
+    let user_id = auth::user_id(access_token).await?;
+    let mut owned = vec![room_id.clone()];
+
+    if slug.board.is_some() {
+        let space = super::resolve::service(Slug::new(slug.space.clone(), None)?).await?;
+
+        if !space::children(access_token, &space.room_id)
+            .await?
+            .contains(&room_id)
+        {
+            return Err(Error::Forbidden);
+        }
+
+        owned.push(space.room_id);
+    }
+
+    for room_id in &owned {
+        if !auth::power_levels(access_token, room_id)
+            .await?
+            .user_can_send_state(&user_id, StateEventType::RoomCanonicalAlias)
+        {
+            return Err(Error::Forbidden);
+        }
+    }
+
+    let alias = slug.alias(server_name);
+    let req = get::Request::new(alias.clone());
+
+    match commune().send_matrix_request(req, None).await {
+        Ok(get::Response { room_id: taken, .. }) if taken != room_id => {
+            return Err(Error::AliasTaken)
+        }
+        Ok(_) => {}
+        Err(FromHttpResponse(FromHttpResponseError::Server(MatrixError {
+            status_code: StatusCode::NOT_FOUND,
+            ..
+        }))) => {
+            let req = set::Request::new(alias.clone(), room_id.clone());
+
+            commune()
+                .send_matrix_request(req, Some(access_token))
+                .await?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    let req = state::get::Request::new(
+        room_id.clone(),
+        StateEventType::RoomCanonicalAlias,
+        String::new(),
+    );
+
+    let mut content = match commune().send_matrix_request(req, Some(access_token)).await {
+        Ok(state::get::Response { content, .. }) => {
+            content.deserialize_as::<RoomCanonicalAliasEventContent>()?
+        }
+        Err(_) => RoomCanonicalAliasEventContent::new(),
+    };
+
+    if let Some(previous) = content.alias.take().filter(|previous| *previous != alias) {
+        super::invalidate(&previous);
+
+        if !content.alt_aliases.contains(&previous) {
+            content.alt_aliases.push(previous);
+        }
+    }
+
+    content.alt_aliases.retain(|alt| *alt != alias);
+    content.alias = Some(alias.clone());
+
+    let req = state::send::Request::new(room_id.clone(), "", &content)?;
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    super::invalidate(&alias);
+
+    Ok(Resolved {
+        room_id,
+        alias,
+        canonical: None,
+    })
+}
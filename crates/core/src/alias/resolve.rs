@@ -0,0 +1,69 @@
+use http::StatusCode;
+use matrix::{
+    admin::room::get_room,
+    client::alias::get,
+    ruma_client::Error::FromHttpResponse,
+    ruma_common::{
+        api::error::{FromHttpResponseError, MatrixError},
+        OwnedRoomAliasId, OwnedRoomId,
+    },
+};
+use serde::Serialize;
+
+use super::Slug;
+use crate::{
+    commune,
+    error::{Error, Result},
+};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Resolved {
+    pub room_id: OwnedRoomId,
+
+    pub alias: OwnedRoomAliasId,
+
+    /// The slug of the canonical alias, set when it differs from the one
+    /// that was requested so clients can redirect to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical: Option<String>,
+}
+
+pub async fn service(slug: Slug) -> Result<Resolved> {
+    let server_name = &commune().config.matrix.server_name;
+    let alias = slug.alias(server_name);
+
+    if let Some(resolved) = super::cached(&alias) {
+        return Ok(resolved);
+    }
+
+    let req = get::Request::new(alias.clone());
+
+    let get::Response { room_id, .. } = match commune().send_matrix_request(req, None).await {
+        Ok(resp) => resp,
+        Err(FromHttpResponse(FromHttpResponseError::Server(MatrixError {
+            status_code: StatusCode::NOT_FOUND,
+            ..
+        }))) => return Err(Error::NotFound),
+        Err(e) => return Err(e.into()),
+    };
+
+    let req = get_room::Request::new(room_id.clone());
+    let get_room::Response { room, .. } = commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await?;
+
+    let canonical = room
+        .canonical_alias
+        .filter(|canonical| *canonical != alias)
+        .and_then(|canonical| Slug::from_alias(&canonical, server_name))
+        .map(|slug| slug.to_string());
+
+    let resolved = Resolved {
+        room_id,
+        alias: alias.clone(),
+        canonical,
+    };
+    super::cache(alias, resolved.clone());
+
+    Ok(resolved)
+}
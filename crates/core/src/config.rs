@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use matrix::ruma_common::OwnedServerName;
 use serde::Deserialize;
 use url::Url;
@@ -15,6 +17,12 @@ pub struct Config {
 
     pub matrix: Matrix,
     pub mail: SMTP,
+
+    #[serde(default)]
+    pub search: Search,
+
+    /// End-to-end encryption of direct messages, off unless configured.
+    pub crypto: Option<Crypto>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,3 +40,19 @@ pub struct Matrix {
     pub admin_token: Secret,
     pub shared_registration_secret: Secret,
 }
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Search {
+    /// Where the index of world-readable boards is kept, it only lives in
+    /// memory when unset.
+    pub index: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Crypto {
+    /// Where the keys of every device are kept.
+    pub store: PathBuf,
+
+    /// Encrypts the keys at rest, they cannot be read back without it.
+    pub pickle_key: Secret,
+}
@@ -0,0 +1,1039 @@
+//! End-to-end encryption of direct messages, for instances that enable it.
+//! Commune holds the Olm account of every device its users log in with,
+//! shares Megolm room keys with the devices of the participants over Olm and
+//! decrypts what the other side sends. The state of each device is pickled
+//! to disk under the configured key, and room keys can be backed up in the
+//! account data of the user under a passphrase.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#end-to-end-encryption
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{anyhow, bail, ensure};
+use matrix::{
+    client::keys::{DeviceKeys, Signatures, SignedKey},
+    ruma_common::{
+        canonical_json::to_canonical_value, serde::Raw, DeviceId, OwnedDeviceId, OwnedRoomId,
+        OwnedUserId, RoomId, UserId,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use vodozemac::{
+    megolm::{
+        GroupSession, InboundGroupSession, MegolmMessage, SessionConfig as MegolmConfig, SessionKey,
+    },
+    olm::{Account, InboundCreationResult, OlmMessage, Session, SessionConfig},
+    Curve25519PublicKey, Ed25519PublicKey, Ed25519Signature,
+};
+
+use self::homeserver::{Homeserver, Matrix, Synced, ToDeviceEvent};
+use crate::{commune, error::Result};
+
+pub mod backup;
+pub(crate) mod homeserver;
+pub mod restore;
+mod store;
+
+pub const OLM_ALGORITHM: &str = "m.olm.v1.curve25519-aes-sha2";
+pub const MEGOLM_ALGORITHM: &str = "m.megolm.v1.aes-sha2";
+
+/// Shown in place of messages that cannot be decrypted.
+pub const UNDECRYPTABLE: &str = "Unable to decrypt this message.";
+
+const ENCRYPTED_EVENT_TYPE: &str = "m.room.encrypted";
+const ONE_TIME_KEY_ALGORITHM: &str = "signed_curve25519";
+
+/// The amount of one-time keys kept on the homeserver for others to claim.
+const ONE_TIME_KEYS: u64 = 50;
+
+/// Room keys are replaced after this many messages.
+const ROTATION_PERIOD: u32 = 100;
+
+pub(crate) struct Device {
+    user_id: OwnedUserId,
+
+    device_id: OwnedDeviceId,
+
+    account: Account,
+
+    /// Whether the device keys were uploaded.
+    published: bool,
+
+    /// Unclaimed one-time keys on the homeserver, as of the last upload or
+    /// sync.
+    one_time_keys: u64,
+
+    /// Olm sessions by the identity key of the other device, newest last.
+    sessions: BTreeMap<String, Vec<Session>>,
+
+    /// The room key messages are sent with, by room.
+    outbound: BTreeMap<OwnedRoomId, Outbound>,
+
+    /// Room keys messages can be decrypted with, by session ID.
+    inbound: BTreeMap<String, Inbound>,
+
+    /// Where the next sync for to-device events starts.
+    since: Option<String>,
+}
+
+struct Outbound {
+    session: GroupSession,
+
+    /// The key as of the first message, so devices showing up later can read
+    /// the conversation from the start.
+    key: String,
+
+    shared_with: BTreeSet<(OwnedUserId, OwnedDeviceId)>,
+}
+
+struct Inbound {
+    room_id: OwnedRoomId,
+
+    /// The identity key of the device that created the session.
+    sender_key: String,
+
+    /// Who sent the key, the only user whose events it may decrypt.
+    sender: OwnedUserId,
+
+    sender_device: OwnedDeviceId,
+
+    session: InboundGroupSession,
+}
+
+/// A device of another user, whose keys are signed by itself.
+struct Remote {
+    user_id: OwnedUserId,
+
+    device_id: OwnedDeviceId,
+
+    curve25519: Curve25519PublicKey,
+
+    ed25519: Ed25519PublicKey,
+}
+
+impl Remote {
+    fn from_keys(keys: &DeviceKeys) -> Option<Self> {
+        let key = |algorithm: &str| keys.keys.get(&format!("{algorithm}:{}", keys.device_id));
+
+        let curve25519 = Curve25519PublicKey::from_base64(key("curve25519")?).ok()?;
+        let ed25519 = Ed25519PublicKey::from_base64(key("ed25519")?).ok()?;
+
+        verify(
+            keys,
+            &keys.signatures,
+            &keys.user_id,
+            &keys.device_id,
+            &ed25519,
+        )
+        .then(|| Self {
+            user_id: keys.user_id.clone(),
+            device_id: keys.device_id.clone(),
+            curve25519,
+            ed25519,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct OlmContent {
+    algorithm: String,
+
+    sender_key: String,
+
+    /// By the identity key of the recipient.
+    ciphertext: BTreeMap<String, OlmCiphertext>,
+}
+
+#[derive(Deserialize)]
+struct OlmCiphertext {
+    #[serde(rename = "type")]
+    message_type: usize,
+
+    body: String,
+}
+
+#[derive(Deserialize)]
+struct OlmPayload {
+    #[serde(rename = "type")]
+    kind: String,
+
+    content: Value,
+
+    sender: OwnedUserId,
+
+    sender_device: OwnedDeviceId,
+
+    /// The signing key of the sending device.
+    keys: Ed25519Keys,
+
+    recipient: OwnedUserId,
+
+    recipient_keys: Ed25519Keys,
+}
+
+#[derive(Deserialize)]
+struct Ed25519Keys {
+    ed25519: String,
+}
+
+#[derive(Deserialize)]
+struct RoomKey {
+    algorithm: String,
+
+    room_id: OwnedRoomId,
+
+    session_id: String,
+
+    session_key: String,
+}
+
+#[derive(Deserialize)]
+struct MegolmContent {
+    algorithm: String,
+
+    session_id: String,
+
+    ciphertext: String,
+
+    /// Deprecated, but checked against the room key when present.
+    sender_key: Option<String>,
+
+    device_id: Option<OwnedDeviceId>,
+}
+
+#[derive(Deserialize)]
+struct MegolmPayload {
+    #[serde(rename = "type")]
+    kind: String,
+
+    content: Value,
+
+    room_id: OwnedRoomId,
+}
+
+impl Device {
+    fn new(user_id: OwnedUserId, device_id: OwnedDeviceId) -> Self {
+        Self {
+            user_id,
+            device_id,
+            account: Account::new(),
+            published: false,
+            one_time_keys: 0,
+            sessions: BTreeMap::new(),
+            outbound: BTreeMap::new(),
+            inbound: BTreeMap::new(),
+            since: None,
+        }
+    }
+
+    fn curve25519_key(&self) -> String {
+        self.account.curve25519_key().to_base64()
+    }
+
+    fn ed25519_key(&self) -> String {
+        self.account.ed25519_key().to_base64()
+    }
+
+    fn sign(&self, value: &impl Serialize) -> Result<Signatures> {
+        let signature = self.account.sign(&canonical(value)?);
+
+        Ok(BTreeMap::from([(
+            self.user_id.clone(),
+            BTreeMap::from([(format!("ed25519:{}", self.device_id), signature.to_base64())]),
+        )]))
+    }
+
+    fn device_keys(&self) -> Result<DeviceKeys> {
+        let mut keys = DeviceKeys {
+            user_id: self.user_id.clone(),
+            device_id: self.device_id.clone(),
+            algorithms: vec![OLM_ALGORITHM.to_owned(), MEGOLM_ALGORITHM.to_owned()],
+            keys: BTreeMap::from([
+                (
+                    format!("curve25519:{}", self.device_id),
+                    self.curve25519_key(),
+                ),
+                (format!("ed25519:{}", self.device_id), self.ed25519_key()),
+            ]),
+            signatures: BTreeMap::new(),
+        };
+        keys.signatures = self.sign(&keys)?;
+
+        Ok(keys)
+    }
+
+    /// Uploads the device keys the first time, and tops up the one-time keys
+    /// once half of them were claimed.
+    async fn publish(&mut self, homeserver: &impl Homeserver) -> Result<()> {
+        if self.published && self.one_time_keys >= ONE_TIME_KEYS / 2 {
+            return Ok(());
+        }
+
+        let device_keys = match self.published {
+            true => None,
+            false => Some(self.device_keys()?),
+        };
+
+        let missing = ONE_TIME_KEYS.saturating_sub(self.one_time_keys);
+        let _ = self
+            .account
+            .generate_one_time_keys(usize::try_from(missing).unwrap_or_default());
+
+        let mut one_time_keys = BTreeMap::new();
+
+        for (key_id, key) in self.account.one_time_keys() {
+            let mut signed = SignedKey {
+                key: key.to_base64(),
+                fallback: false,
+                signatures: BTreeMap::new(),
+            };
+            signed.signatures = self.sign(&signed)?;
+
+            let _ = one_time_keys.insert(
+                format!("{ONE_TIME_KEY_ALGORITHM}:{}", key_id.to_base64()),
+                signed,
+            );
+        }
+
+        self.one_time_keys = homeserver.upload_keys(device_keys, one_time_keys).await?;
+        self.account.mark_keys_as_published();
+        self.published = true;
+
+        Ok(())
+    }
+
+    /// The devices of `users` besides this one. Devices whose keys are not
+    /// signed by themselves are left out.
+    async fn devices(
+        &self,
+        homeserver: &impl Homeserver,
+        users: &BTreeSet<OwnedUserId>,
+    ) -> Result<Vec<Remote>> {
+        let mut devices = Vec::new();
+
+        for (user_id, keys) in homeserver.query_keys(users).await? {
+            for (device_id, keys) in keys {
+                if user_id == self.user_id && device_id == self.device_id {
+                    continue;
+                }
+
+                match Remote::from_keys(&keys)
+                    .filter(|remote| remote.user_id == user_id && remote.device_id == device_id)
+                {
+                    Some(remote) => devices.push(remote),
+                    None => {
+                        tracing::debug!(%user_id, %device_id, "ignoring device with invalid keys")
+                    }
+                }
+            }
+        }
+
+        Ok(devices)
+    }
+
+    /// Encrypts an event for the room, sharing the room key with the devices
+    /// of `users` that do not have it yet.
+    async fn encrypt(
+        &mut self,
+        homeserver: &impl Homeserver,
+        room_id: &RoomId,
+        users: &BTreeSet<OwnedUserId>,
+        event_type: &str,
+        content: Value,
+    ) -> Result<Value> {
+        self.publish(homeserver).await?;
+
+        let mut outbound = match self.outbound.remove(room_id) {
+            Some(outbound) if outbound.session.message_index() < ROTATION_PERIOD => outbound,
+            _ => self.outbound_session(room_id),
+        };
+
+        if let Err(e) = self.share(homeserver, room_id, &mut outbound, users).await {
+            let _ = self.outbound.insert(room_id.to_owned(), outbound);
+
+            return Err(e);
+        }
+
+        let payload = json!({
+            "type": event_type,
+            "content": content,
+            "room_id": room_id,
+        });
+
+        let content = json!({
+            "algorithm": MEGOLM_ALGORITHM,
+            "sender_key": self.curve25519_key(),
+            "ciphertext": outbound.session.encrypt(serde_json::to_string(&payload)?).to_base64(),
+            "session_id": outbound.session.session_id(),
+            "device_id": self.device_id,
+        });
+
+        let _ = self.outbound.insert(room_id.to_owned(), outbound);
+
+        Ok(content)
+    }
+
+    /// Starts a room key, which this device can decrypt with as well.
+    fn outbound_session(&mut self, room_id: &RoomId) -> Outbound {
+        let session = GroupSession::new(MegolmConfig::version_1());
+        let key = session.session_key();
+
+        let _ = self.import(Inbound {
+            room_id: room_id.to_owned(),
+            sender_key: self.curve25519_key(),
+            sender: self.user_id.clone(),
+            sender_device: self.device_id.clone(),
+            session: InboundGroupSession::new(&key, MegolmConfig::version_1()),
+        });
+
+        Outbound {
+            session,
+            key: key.to_base64(),
+            shared_with: BTreeSet::new(),
+        }
+    }
+
+    async fn share(
+        &mut self,
+        homeserver: &impl Homeserver,
+        room_id: &RoomId,
+        outbound: &mut Outbound,
+        users: &BTreeSet<OwnedUserId>,
+    ) -> Result<()> {
+        let devices = self
+            .devices(homeserver, users)
+            .await?
+            .into_iter()
+            .filter(|device| {
+                !outbound
+                    .shared_with
+                    .contains(&(device.user_id.clone(), device.device_id.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        if devices.is_empty() {
+            return Ok(());
+        }
+
+        self.establish(homeserver, &devices).await?;
+
+        let room_key = json!({
+            "algorithm": MEGOLM_ALGORITHM,
+            "room_id": room_id,
+            "session_id": outbound.session.session_id(),
+            "session_key": outbound.key,
+        });
+
+        let mut messages = BTreeMap::<OwnedUserId, BTreeMap<String, Value>>::new();
+        let mut shared = Vec::new();
+
+        for device in &devices {
+            let Some(content) = self.olm_encrypt(device, "m.room_key", room_key.clone())? else {
+                tracing::debug!(
+                    user_id = %device.user_id,
+                    device_id = %device.device_id,
+                    "no olm session to share the room key over"
+                );
+
+                continue;
+            };
+
+            let _ = messages
+                .entry(device.user_id.clone())
+                .or_default()
+                .insert(device.device_id.to_string(), content);
+            shared.push((device.user_id.clone(), device.device_id.clone()));
+        }
+
+        if !messages.is_empty() {
+            homeserver
+                .send_to_device(ENCRYPTED_EVENT_TYPE, messages)
+                .await?;
+        }
+
+        outbound.shared_with.extend(shared);
+
+        Ok(())
+    }
+
+    /// Creates Olm sessions with the devices there are none with yet, from
+    /// one-time keys signed by the device.
+    async fn establish(&mut self, homeserver: &impl Homeserver, devices: &[Remote]) -> Result<()> {
+        let mut missing = BTreeMap::<OwnedUserId, BTreeMap<OwnedDeviceId, String>>::new();
+
+        for device in devices {
+            if !self.sessions.contains_key(&device.curve25519.to_base64()) {
+                let _ = missing
+                    .entry(device.user_id.clone())
+                    .or_default()
+                    .insert(device.device_id.clone(), ONE_TIME_KEY_ALGORITHM.to_owned());
+            }
+        }
+
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let claimed = homeserver.claim_keys(missing).await?;
+
+        for device in devices {
+            let Some(keys) = claimed
+                .get(&device.user_id)
+                .and_then(|keys| keys.get(&device.device_id))
+            else {
+                continue;
+            };
+
+            let one_time_key = keys.iter().find_map(|(key_id, key)| {
+                let valid = key_id.starts_with(&format!("{ONE_TIME_KEY_ALGORITHM}:"))
+                    && verify(
+                        key,
+                        &key.signatures,
+                        &device.user_id,
+                        &device.device_id,
+                        &device.ed25519,
+                    );
+
+                valid
+                    .then(|| Curve25519PublicKey::from_base64(&key.key).ok())
+                    .flatten()
+            });
+
+            let Some(one_time_key) = one_time_key else {
+                tracing::debug!(
+                    user_id = %device.user_id,
+                    device_id = %device.device_id,
+                    "no valid one-time key claimed"
+                );
+
+                continue;
+            };
+
+            let session = self.account.create_outbound_session(
+                SessionConfig::version_1(),
+                device.curve25519,
+                one_time_key,
+            );
+
+            self.sessions
+                .entry(device.curve25519.to_base64())
+                .or_default()
+                .push(session);
+        }
+
+        Ok(())
+    }
+
+    fn olm_encrypt(
+        &mut self,
+        device: &Remote,
+        event_type: &str,
+        content: Value,
+    ) -> Result<Option<Value>> {
+        let payload = serde_json::to_string(&json!({
+            "type": event_type,
+            "content": content,
+            "sender": self.user_id,
+            "sender_device": self.device_id,
+            "keys": { "ed25519": self.ed25519_key() },
+            "recipient": device.user_id,
+            "recipient_keys": { "ed25519": device.ed25519.to_base64() },
+        }))?;
+        let sender_key = self.curve25519_key();
+        let recipient_key = device.curve25519.to_base64();
+
+        let Some(session) = self
+            .sessions
+            .get_mut(&recipient_key)
+            .and_then(|sessions| sessions.last_mut())
+        else {
+            return Ok(None);
+        };
+
+        let (message_type, body) = session.encrypt(payload).to_parts();
+
+        Ok(Some(json!({
+            "algorithm": OLM_ALGORITHM,
+            "sender_key": sender_key,
+            "ciphertext": {
+                recipient_key: { "type": message_type, "body": body },
+            },
+        })))
+    }
+
+    /// Takes in the to-device events sent since the last call, which carry
+    /// the room keys of the other devices.
+    async fn receive(&mut self, homeserver: &impl Homeserver) -> Result<()> {
+        let Synced {
+            events,
+            next_batch,
+            one_time_keys,
+        } = homeserver.sync_to_device(self.since.clone()).await?;
+
+        for event in &events {
+            if let Err(e) = self.handle(homeserver, event).await {
+                tracing::debug!(?e, sender = %event.sender, "dropping to-device event");
+            }
+        }
+
+        self.since = Some(next_batch);
+        self.one_time_keys = one_time_keys;
+
+        Ok(())
+    }
+
+    async fn handle(
+        &mut self,
+        homeserver: &impl Homeserver,
+        event: &ToDeviceEvent,
+    ) -> anyhow::Result<()> {
+        if event.kind != ENCRYPTED_EVENT_TYPE {
+            return Ok(());
+        }
+
+        let content = serde_json::from_value::<OlmContent>(event.content.clone())?;
+        ensure!(
+            content.algorithm == OLM_ALGORITHM,
+            "unsupported algorithm {}",
+            content.algorithm
+        );
+
+        // the same event is sent to every device of the user
+        let Some(ciphertext) = content.ciphertext.get(&self.curve25519_key()) else {
+            return Ok(());
+        };
+
+        let message = OlmMessage::from_parts(ciphertext.message_type, &ciphertext.body)?;
+        let plaintext = self.olm_decrypt(&content.sender_key, &message)?;
+        let payload = serde_json::from_slice::<OlmPayload>(&plaintext)?;
+
+        ensure!(
+            payload.sender == event.sender
+                && payload.recipient == self.user_id
+                && payload.recipient_keys.ed25519 == self.ed25519_key(),
+            "olm payload is not meant for this device"
+        );
+
+        if payload.kind == "m.room_key" {
+            // the keys in the payload have to be the published ones of the
+            // device it claims to come from
+            let device = self
+                .devices(homeserver, &BTreeSet::from([payload.sender.clone()]))
+                .await?
+                .into_iter()
+                .find(|device| device.device_id == payload.sender_device)
+                .ok_or_else(|| anyhow!("unknown device {}", payload.sender_device))?;
+            ensure!(
+                device.curve25519.to_base64() == content.sender_key
+                    && device.ed25519.to_base64() == payload.keys.ed25519,
+                "room key does not come from the device it claims"
+            );
+
+            let room_key = serde_json::from_value::<RoomKey>(payload.content)?;
+            ensure!(
+                room_key.algorithm == MEGOLM_ALGORITHM,
+                "unsupported algorithm {}",
+                room_key.algorithm
+            );
+
+            let session = InboundGroupSession::new(
+                &SessionKey::from_base64(&room_key.session_key)?,
+                MegolmConfig::version_1(),
+            );
+            ensure!(
+                session.session_id() == room_key.session_id,
+                "room key does not match its session"
+            );
+
+            let _ = self.import(Inbound {
+                room_id: room_key.room_id,
+                sender_key: content.sender_key,
+                sender: payload.sender,
+                sender_device: payload.sender_device,
+                session,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn olm_decrypt(&mut self, sender_key: &str, message: &OlmMessage) -> anyhow::Result<Vec<u8>> {
+        if let Some(sessions) = self.sessions.get_mut(sender_key) {
+            for session in sessions.iter_mut().rev() {
+                if let Ok(plaintext) = session.decrypt(message) {
+                    return Ok(plaintext);
+                }
+            }
+        }
+
+        let OlmMessage::PreKey(message) = message else {
+            bail!("no olm session decrypts the message");
+        };
+
+        let InboundCreationResult { session, plaintext } = self
+            .account
+            .create_inbound_session(Curve25519PublicKey::from_base64(sender_key)?, message)?;
+
+        self.sessions
+            .entry(sender_key.to_owned())
+            .or_default()
+            .push(session);
+
+        Ok(plaintext)
+    }
+
+    /// Keeps whichever copy of the room key decrypts more messages, returning
+    /// whether it was this one.
+    fn import(&mut self, inbound: Inbound) -> bool {
+        let session_id = inbound.session.session_id();
+
+        match self.inbound.get(&session_id) {
+            Some(known)
+                if known.session.first_known_index() <= inbound.session.first_known_index() =>
+            {
+                false
+            }
+            _ => {
+                let _ = self.inbound.insert(session_id, inbound);
+
+                true
+            }
+        }
+    }
+
+    /// Replaces `m.room.encrypted` events of the room by the events they
+    /// hold, and by a notice for the ones that cannot be decrypted.
+    async fn decrypt_events<T>(
+        &mut self,
+        homeserver: &impl Homeserver,
+        room_id: &RoomId,
+        events: Vec<Raw<T>>,
+    ) -> Result<Vec<Raw<T>>> {
+        let mut received = false;
+        let mut decrypted = Vec::with_capacity(events.len());
+
+        for event in events {
+            if event.get_field::<String>("type").ok().flatten().as_deref()
+                != Some(ENCRYPTED_EVENT_TYPE)
+            {
+                decrypted.push(event);
+                continue;
+            }
+
+            let mut value = event.deserialize_as::<Value>()?;
+            let content = value.get("content").cloned().unwrap_or_default();
+            let sender = value
+                .get("sender")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+
+            // room keys of the other side arrive through to-device events
+            let session_id = content.get("session_id").and_then(Value::as_str);
+            if !received && session_id.is_some_and(|id| !self.inbound.contains_key(id)) {
+                self.receive(homeserver).await?;
+                received = true;
+            }
+
+            let (kind, content) = match self.decrypt(room_id, sender, content) {
+                Ok(decrypted) => decrypted,
+                Err(e) => {
+                    tracing::debug!(?e, %room_id, "failed to decrypt event");
+
+                    (
+                        "m.room.message".to_owned(),
+                        json!({ "msgtype": "m.notice", "body": UNDECRYPTABLE }),
+                    )
+                }
+            };
+
+            if let Some(object) = value.as_object_mut() {
+                let _ = object.insert("type".to_owned(), kind.into());
+                let _ = object.insert("content".to_owned(), content);
+            }
+
+            decrypted.push(Raw::from_json(serde_json::value::to_raw_value(&value)?));
+        }
+
+        Ok(decrypted)
+    }
+
+    /// Only the user who sent the room key can have sent events with it.
+    fn decrypt(
+        &mut self,
+        room_id: &RoomId,
+        sender: &str,
+        content: Value,
+    ) -> anyhow::Result<(String, Value)> {
+        let content = serde_json::from_value::<MegolmContent>(content)?;
+        ensure!(
+            content.algorithm == MEGOLM_ALGORITHM,
+            "unsupported algorithm {}",
+            content.algorithm
+        );
+
+        let inbound = self
+            .inbound
+            .get_mut(&content.session_id)
+            .ok_or_else(|| anyhow!("missing room key {}", content.session_id))?;
+        ensure!(
+            inbound.room_id == room_id,
+            "room key belongs to another room"
+        );
+        ensure!(
+            inbound.sender == sender
+                && content
+                    .sender_key
+                    .as_ref()
+                    .map_or(true, |key| *key == inbound.sender_key)
+                && content
+                    .device_id
+                    .as_ref()
+                    .map_or(true, |device_id| *device_id == inbound.sender_device),
+            "room key belongs to another sender"
+        );
+
+        let decrypted = inbound
+            .session
+            .decrypt(&MegolmMessage::from_base64(&content.ciphertext)?)?;
+        let payload = serde_json::from_slice::<MegolmPayload>(&decrypted.plaintext)?;
+        ensure!(payload.room_id == room_id, "event was sent to another room");
+
+        Ok((payload.kind, payload.content))
+    }
+}
+
+/// The canonical JSON signatures are made over, leaving the signatures out.
+fn canonical(value: &impl Serialize) -> Result<String> {
+    let mut value = serde_json::to_value(value)?;
+
+    if let Some(object) = value.as_object_mut() {
+        let _ = object.remove("signatures");
+        let _ = object.remove("unsigned");
+    }
+
+    Ok(to_canonical_value(value)
+        .map_err(anyhow::Error::from)?
+        .to_string())
+}
+
+fn verify(
+    value: &impl Serialize,
+    signatures: &Signatures,
+    user_id: &UserId,
+    device_id: &DeviceId,
+    key: &Ed25519PublicKey,
+) -> bool {
+    let Some(signature) = signatures
+        .get(user_id)
+        .and_then(|signatures| signatures.get(&format!("ed25519:{device_id}")))
+        .and_then(|signature| Ed25519Signature::from_base64(signature).ok())
+    else {
+        return false;
+    };
+
+    canonical(value).is_ok_and(|message| key.verify(message.as_bytes(), &signature).is_ok())
+}
+
+/// Whether the instance manages encryption for its users.
+pub(crate) fn enabled() -> bool {
+    commune().config.crypto.is_some()
+}
+
+/// Makes sure the device of the access token can be sent room keys.
+pub(crate) async fn publish(access_token: &str) -> Result<()> {
+    let device = store::device(access_token).await?;
+    let mut device = device.lock().await;
+    let homeserver = Matrix::new(access_token, device.user_id.clone());
+
+    device.publish(&homeserver).await?;
+
+    store::save(&device).await
+}
+
+/// Encrypts an event for the devices of `users` in the room, which becomes
+/// the content of an `m.room.encrypted` event.
+pub(crate) async fn encrypt(
+    access_token: &str,
+    room_id: &RoomId,
+    users: &BTreeSet<OwnedUserId>,
+    event_type: &str,
+    content: Value,
+) -> Result<Value> {
+    let device = store::device(access_token).await?;
+    let mut device = device.lock().await;
+    let homeserver = Matrix::new(access_token, device.user_id.clone());
+
+    let result = device
+        .encrypt(&homeserver, room_id, users, event_type, content)
+        .await;
+
+    // sessions may have been created before a failure
+    store::save(&device).await?;
+
+    result
+}
+
+/// Replaces the encrypted events of the room by the events they hold.
+pub(crate) async fn decrypt<T>(
+    access_token: &str,
+    room_id: &RoomId,
+    events: Vec<Raw<T>>,
+) -> Result<Vec<Raw<T>>> {
+    let device = store::device(access_token).await?;
+    let mut device = device.lock().await;
+    let homeserver = Matrix::new(access_token, device.user_id.clone());
+
+    device.publish(&homeserver).await?;
+    let events = device.decrypt_events(&homeserver, room_id, events).await?;
+
+    store::save(&device).await?;
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{
+        ruma_common::{device_id, room_id, user_id},
+        ruma_events::AnyTimelineEvent,
+    };
+
+    use super::{homeserver::StandIn, *};
+
+    fn event(sender: &UserId, content: &Value) -> Raw<AnyTimelineEvent> {
+        Raw::new(&json!({
+            "type": ENCRYPTED_EVENT_TYPE,
+            "event_id": "$message",
+            "sender": sender,
+            "room_id": "!direct:example.com",
+            "origin_server_ts": 1,
+            "content": content,
+        }))
+        .unwrap()
+        .cast()
+    }
+
+    async fn body(
+        device: &mut Device,
+        homeserver: &impl Homeserver,
+        event: Raw<AnyTimelineEvent>,
+    ) -> Value {
+        let events = device
+            .decrypt_events(homeserver, room_id!("!direct:example.com"), vec![event])
+            .await
+            .unwrap();
+
+        events[0].get_field::<Value>("content").unwrap().unwrap()["body"].clone()
+    }
+
+    fn text(body: &str) -> Value {
+        json!({ "msgtype": "m.text", "body": body })
+    }
+
+    #[tokio::test]
+    async fn exchanges_encrypted_messages() {
+        let server = StandIn::default();
+        let alice_id = user_id!("@alice:example.com");
+        let bob_id = user_id!("@bob:example.com");
+        let users = BTreeSet::from([alice_id.to_owned(), bob_id.to_owned()]);
+        let room_id = room_id!("!direct:example.com");
+
+        let alice_hs = server.login(alice_id, device_id!("ALICE"));
+        let bob_hs = server.login(bob_id, device_id!("BOB"));
+        let mut alice = Device::new(alice_id.to_owned(), device_id!("ALICE").to_owned());
+        let mut bob = Device::new(bob_id.to_owned(), device_id!("BOB").to_owned());
+
+        bob.publish(&bob_hs).await.unwrap();
+
+        let content = alice
+            .encrypt(
+                &alice_hs,
+                room_id,
+                &users,
+                "m.room.message",
+                text("hello bob"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(content["algorithm"], MEGOLM_ALGORITHM);
+        assert!(!content.to_string().contains("hello bob"));
+
+        let message = event(alice_id, &content);
+        assert_eq!(body(&mut bob, &bob_hs, message.clone()).await, "hello bob");
+        assert_eq!(body(&mut alice, &alice_hs, message).await, "hello bob");
+
+        // sent again by someone else, it does not pass for their message
+        let replayed = event(user_id!("@mallory:example.com"), &content);
+        assert_eq!(body(&mut bob, &bob_hs, replayed).await, UNDECRYPTABLE);
+
+        let content = bob
+            .encrypt(
+                &bob_hs,
+                room_id,
+                &users,
+                "m.room.message",
+                text("hello alice"),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            body(&mut alice, &alice_hs, event(bob_id, &content)).await,
+            "hello alice"
+        );
+
+        // the key of one room does not open the events of another
+        let events = bob
+            .decrypt_events(
+                &bob_hs,
+                room_id!("!other:example.com"),
+                vec![event(alice_id, &content)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            events[0].get_field::<Value>("content").unwrap().unwrap()["body"],
+            UNDECRYPTABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn ignores_devices_with_forged_keys() {
+        let server = StandIn::default();
+        let alice_id = user_id!("@alice:example.com");
+        let mallory_id = user_id!("@mallory:example.com");
+        let room_id = room_id!("!direct:example.com");
+
+        let alice_hs = server.login(alice_id, device_id!("ALICE"));
+        let mallory_hs = server.login(mallory_id, device_id!("MALLORY"));
+        let mut alice = Device::new(alice_id.to_owned(), device_id!("ALICE").to_owned());
+        let mallory = Device::new(mallory_id.to_owned(), device_id!("MALLORY").to_owned());
+
+        let mut keys = mallory.device_keys().unwrap();
+        let _ = keys
+            .keys
+            .insert("curve25519:MALLORY".to_owned(), alice.curve25519_key());
+        mallory_hs
+            .upload_keys(Some(keys), BTreeMap::new())
+            .await
+            .unwrap();
+
+        let devices = alice
+            .devices(&alice_hs, &BTreeSet::from([mallory_id.to_owned()]))
+            .await
+            .unwrap();
+        assert!(devices.is_empty());
+
+        alice
+            .encrypt(
+                &alice_hs,
+                room_id,
+                &BTreeSet::from([mallory_id.to_owned()]),
+                "m.room.message",
+                text("secret"),
+            )
+            .await
+            .unwrap();
+        assert!(alice.outbound[room_id].shared_with.is_empty());
+    }
+}
@@ -0,0 +1,250 @@
+//! Backs up the room keys of the device in the account data of the user,
+//! pickled under a key derived from a passphrase, so conversations stay
+//! readable from devices that log in later.
+
+use pbkdf2::pbkdf2_hmac;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{homeserver::Homeserver, store, Device, Matrix};
+use crate::error::{Error, Result};
+
+pub const BACKUP_EVENT_TYPE: &str = "sh.commune.key_backup";
+
+const ITERATIONS: u32 = 100_000;
+
+/// Backups are read back from account data, which the user can write
+/// anything into, so the work a restore asks for is bounded.
+const MAX_ITERATIONS: u32 = 10 * ITERATIONS;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct Backup {
+    salt: String,
+
+    iterations: u32,
+
+    /// Hash of the derived key, telling a wrong passphrase apart from a
+    /// corrupted backup.
+    check: String,
+
+    sessions: Vec<store::InboundPickle>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BackedUp {
+    /// The amount of room keys in the backup.
+    pub sessions: usize,
+}
+
+/// Runs on the blocking pool, as it is meant to take a while.
+async fn derive(passphrase: &str, salt: Vec<u8>, iterations: u32) -> Result<[u8; 32]> {
+    if iterations > MAX_ITERATIONS {
+        return Err(anyhow::anyhow!("the key backup asks for too many iterations").into());
+    }
+
+    let passphrase = passphrase.to_owned();
+
+    let key = tokio::task::spawn_blocking(move || {
+        let mut key = [0; 32];
+        pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), &salt, iterations, &mut key);
+
+        key
+    })
+    .await
+    .map_err(anyhow::Error::from)?;
+
+    Ok(key)
+}
+
+fn check(key: &[u8; 32]) -> String {
+    vodozemac::base64_encode(Sha256::digest(key))
+}
+
+impl Device {
+    async fn backup(&self, passphrase: &str) -> Result<Backup> {
+        let salt = rand::thread_rng().gen::<[u8; 16]>();
+        let key = derive(passphrase, salt.to_vec(), ITERATIONS).await?;
+
+        Ok(Backup {
+            salt: vodozemac::base64_encode(salt),
+            iterations: ITERATIONS,
+            check: check(&key),
+            sessions: self
+                .inbound
+                .values()
+                .map(|inbound| inbound.pickle(&key))
+                .collect(),
+        })
+    }
+
+    /// Imports the room keys of the backup, returning how many were new.
+    pub(super) async fn restore(&mut self, passphrase: &str, backup: Backup) -> Result<usize> {
+        let salt = vodozemac::base64_decode(&backup.salt).map_err(anyhow::Error::from)?;
+        let key = derive(passphrase, salt, backup.iterations).await?;
+
+        if check(&key) != backup.check {
+            return Err(Error::WrongPassphrase);
+        }
+
+        let mut imported = 0;
+
+        for pickle in backup.sessions {
+            match super::Inbound::from_pickle(pickle, &key) {
+                Ok(inbound) => imported += usize::from(self.import(inbound)),
+                Err(e) => tracing::debug!(?e, "skipping room key of the backup"),
+            }
+        }
+
+        Ok(imported)
+    }
+
+    /// Replaces the backup with one of every room key this device has. An
+    /// existing backup has to open with the same passphrase, its keys are
+    /// carried over.
+    pub(super) async fn back_up(
+        &mut self,
+        homeserver: &impl Homeserver,
+        passphrase: &str,
+    ) -> Result<BackedUp> {
+        if let Some(backup) = homeserver.account_data(BACKUP_EVENT_TYPE).await? {
+            let _ = self
+                .restore(passphrase, serde_json::from_value(backup)?)
+                .await?;
+        }
+
+        let backup = self.backup(passphrase).await?;
+        let sessions = backup.sessions.len();
+
+        homeserver
+            .set_account_data(BACKUP_EVENT_TYPE, serde_json::to_value(backup)?)
+            .await?;
+
+        Ok(BackedUp { sessions })
+    }
+}
+
+pub async fn service(
+    access_token: impl AsRef<str>,
+    passphrase: impl AsRef<str>,
+) -> Result<BackedUp> {
+    let access_token = access_token.as_ref();
+
+    let device = store::device(access_token).await?;
+    let mut device = device.lock().await;
+    let homeserver = Matrix::new(access_token, device.user_id.clone());
+
+    let backed_up = device.back_up(&homeserver, passphrase.as_ref()).await?;
+
+    store::save(&device).await?;
+
+    Ok(backed_up)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use matrix::ruma_common::{device_id, room_id, serde::Raw, user_id};
+    use serde_json::{json, Value};
+
+    use super::{
+        super::{homeserver::StandIn, UNDECRYPTABLE},
+        *,
+    };
+
+    async fn backup(homeserver: &StandIn) -> Backup {
+        let backup = homeserver.account_data(BACKUP_EVENT_TYPE).await.unwrap();
+
+        serde_json::from_value(backup.unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn restores_backups_with_the_passphrase() {
+        let server = StandIn::default();
+        let alice_id = user_id!("@alice:example.com");
+        let bob_id = user_id!("@bob:example.com");
+        let users = BTreeSet::from([alice_id.to_owned(), bob_id.to_owned()]);
+        let room_id = room_id!("!direct:example.com");
+
+        let alice_hs = server.login(alice_id, device_id!("ALICE"));
+        let bob_hs = server.login(bob_id, device_id!("BOB"));
+        let mut alice = Device::new(alice_id.to_owned(), device_id!("ALICE").to_owned());
+        let mut bob = Device::new(bob_id.to_owned(), device_id!("BOB").to_owned());
+
+        bob.publish(&bob_hs).await.unwrap();
+
+        let content = alice
+            .encrypt(
+                &alice_hs,
+                room_id,
+                &users,
+                "m.room.message",
+                json!({ "body": "hi" }),
+            )
+            .await
+            .unwrap();
+        let events = || {
+            vec![Raw::<Value>::new(&json!({
+                "type": "m.room.encrypted",
+                "sender": alice_id,
+                "content": content,
+            }))
+            .unwrap()]
+        };
+
+        let _ = bob
+            .decrypt_events(&bob_hs, room_id, events())
+            .await
+            .unwrap();
+        let backed_up = bob.back_up(&bob_hs, "correct horse").await.unwrap();
+        assert_eq!(backed_up.sessions, 1);
+
+        // a device logging in later has no way to the key but the backup
+        let laptop_hs = server.login(bob_id, device_id!("LAPTOP"));
+        let mut laptop = Device::new(bob_id.to_owned(), device_id!("LAPTOP").to_owned());
+
+        let decrypted = laptop
+            .decrypt_events(&laptop_hs, room_id, events())
+            .await
+            .unwrap();
+        assert_eq!(
+            decrypted[0].deserialize().unwrap()["content"]["body"],
+            UNDECRYPTABLE
+        );
+
+        assert!(matches!(
+            laptop
+                .restore("battery staple", backup(&laptop_hs).await)
+                .await,
+            Err(Error::WrongPassphrase)
+        ));
+        assert_eq!(
+            laptop
+                .restore("correct horse", backup(&laptop_hs).await)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            laptop
+                .restore("correct horse", backup(&laptop_hs).await)
+                .await
+                .unwrap(),
+            0
+        );
+
+        let decrypted = laptop
+            .decrypt_events(&laptop_hs, room_id, events())
+            .await
+            .unwrap();
+        assert_eq!(decrypted[0].deserialize().unwrap()["content"]["body"], "hi");
+
+        let mut greedy = backup(&laptop_hs).await;
+        greedy.iterations = u32::MAX;
+        assert!(matches!(
+            laptop.restore("correct horse", greedy).await,
+            Err(Error::Unknown(_))
+        ));
+    }
+}
@@ -0,0 +1,434 @@
+//! What encryption needs from the homeserver, kept behind a trait so the
+//! exchange between devices can be tested without one.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use http::StatusCode;
+use matrix::{
+    client::{
+        account_data,
+        keys::{claim, query, upload, DeviceKeys, SignedKey},
+        sync::v3,
+        to_device,
+    },
+    ruma_client::Error::FromHttpResponse,
+    ruma_common::{
+        api::error::{FromHttpResponseError, MatrixError},
+        serde::Raw,
+        OwnedDeviceId, OwnedUserId, TransactionId,
+    },
+    ruma_events::GlobalAccountDataEventType,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::ONE_TIME_KEY_ALGORITHM;
+use crate::{commune, error::Result};
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ToDeviceEvent {
+    pub(crate) sender: OwnedUserId,
+
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+
+    pub(crate) content: Value,
+}
+
+pub(crate) struct Synced {
+    pub(crate) events: Vec<ToDeviceEvent>,
+
+    pub(crate) next_batch: String,
+
+    /// Unclaimed one-time keys of the device.
+    pub(crate) one_time_keys: u64,
+}
+
+type Claimed = BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, BTreeMap<String, SignedKey>>>;
+
+pub(crate) trait Homeserver {
+    /// Returns the amount of unclaimed one-time keys.
+    async fn upload_keys(
+        &self,
+        device_keys: Option<DeviceKeys>,
+        one_time_keys: BTreeMap<String, SignedKey>,
+    ) -> Result<u64>;
+
+    async fn query_keys(
+        &self,
+        users: &BTreeSet<OwnedUserId>,
+    ) -> Result<BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, DeviceKeys>>>;
+
+    async fn claim_keys(
+        &self,
+        devices: BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, String>>,
+    ) -> Result<Claimed>;
+
+    async fn send_to_device(
+        &self,
+        event_type: &str,
+        messages: BTreeMap<OwnedUserId, BTreeMap<String, Value>>,
+    ) -> Result<()>;
+
+    async fn sync_to_device(&self, since: Option<String>) -> Result<Synced>;
+
+    async fn account_data(&self, event_type: &str) -> Result<Option<Value>>;
+
+    async fn set_account_data(&self, event_type: &str, content: Value) -> Result<()>;
+}
+
+/// The homeserver, on behalf of the device of an access token.
+pub(crate) struct Matrix<'a> {
+    access_token: &'a str,
+
+    user_id: OwnedUserId,
+}
+
+impl<'a> Matrix<'a> {
+    pub(crate) fn new(access_token: &'a str, user_id: OwnedUserId) -> Self {
+        Self {
+            access_token,
+            user_id,
+        }
+    }
+}
+
+impl Homeserver for Matrix<'_> {
+    async fn upload_keys(
+        &self,
+        device_keys: Option<DeviceKeys>,
+        one_time_keys: BTreeMap<String, SignedKey>,
+    ) -> Result<u64> {
+        let mut req = upload::Request::new();
+        req.device_keys = device_keys;
+        req.one_time_keys = one_time_keys;
+
+        let upload::Response {
+            one_time_key_counts,
+            ..
+        } = commune()
+            .send_matrix_request(req, Some(self.access_token))
+            .await?;
+
+        Ok(one_time_key_counts
+            .get(ONE_TIME_KEY_ALGORITHM)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn query_keys(
+        &self,
+        users: &BTreeSet<OwnedUserId>,
+    ) -> Result<BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, DeviceKeys>>> {
+        let req = query::Request::new(
+            users
+                .iter()
+                .map(|user_id| (user_id.clone(), Vec::new()))
+                .collect(),
+        );
+
+        let query::Response { device_keys, .. } = commune()
+            .send_matrix_request(req, Some(self.access_token))
+            .await?;
+
+        Ok(device_keys)
+    }
+
+    async fn claim_keys(
+        &self,
+        devices: BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, String>>,
+    ) -> Result<Claimed> {
+        let req = claim::Request::new(devices);
+
+        let claim::Response { one_time_keys, .. } = commune()
+            .send_matrix_request(req, Some(self.access_token))
+            .await?;
+
+        Ok(one_time_keys)
+    }
+
+    async fn send_to_device(
+        &self,
+        event_type: &str,
+        messages: BTreeMap<OwnedUserId, BTreeMap<String, Value>>,
+    ) -> Result<()> {
+        let req = to_device::Request::new(event_type.to_owned(), TransactionId::new(), messages);
+
+        commune()
+            .send_matrix_request(req, Some(self.access_token))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn sync_to_device(&self, since: Option<String>) -> Result<Synced> {
+        let mut req = v3::Request::new();
+        req.since = since;
+        req.filter = json!({
+            "presence": { "types": [] },
+            "account_data": { "types": [] },
+            "room": { "rooms": [] },
+        })
+        .to_string();
+
+        let v3::Response {
+            next_batch,
+            to_device,
+            device_one_time_keys_count,
+            ..
+        } = commune()
+            .send_matrix_request(req, Some(self.access_token))
+            .await?;
+
+        Ok(Synced {
+            events: to_device
+                .events
+                .iter()
+                .filter_map(|event| event.deserialize_as::<ToDeviceEvent>().ok())
+                .collect(),
+            next_batch,
+            one_time_keys: device_one_time_keys_count
+                .get(ONE_TIME_KEY_ALGORITHM)
+                .copied()
+                .unwrap_or_default(),
+        })
+    }
+
+    async fn account_data(&self, event_type: &str) -> Result<Option<Value>> {
+        let req = account_data::get::Request::new(
+            self.user_id.clone(),
+            GlobalAccountDataEventType::from(event_type),
+        );
+
+        match commune()
+            .send_matrix_request(req, Some(self.access_token))
+            .await
+        {
+            Ok(account_data::get::Response { account_data, .. }) => {
+                Ok(Some(account_data.deserialize_as::<Value>()?))
+            }
+            Err(FromHttpResponse(FromHttpResponseError::Server(MatrixError {
+                status_code: StatusCode::NOT_FOUND,
+                ..
+            }))) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn set_account_data(&self, event_type: &str, content: Value) -> Result<()> {
+        let req = account_data::set::Request::new(
+            self.user_id.clone(),
+            GlobalAccountDataEventType::from(event_type),
+            Raw::new(&content)?.cast(),
+        );
+
+        commune()
+            .send_matrix_request(req, Some(self.access_token))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) use self::stand_in::StandIn;
+
+#[cfg(test)]
+mod stand_in {
+    use std::sync::{Arc, Mutex};
+
+    use matrix::ruma_common::{DeviceId, UserId};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Server {
+        device_keys: BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, DeviceKeys>>,
+
+        one_time_keys: BTreeMap<(OwnedUserId, OwnedDeviceId), BTreeMap<String, SignedKey>>,
+
+        inbox: BTreeMap<(OwnedUserId, OwnedDeviceId), Vec<ToDeviceEvent>>,
+
+        account_data: BTreeMap<(OwnedUserId, String), Value>,
+    }
+
+    /// A homeserver in memory, shared by the devices logged in through it.
+    #[derive(Clone, Default)]
+    pub(crate) struct StandIn {
+        server: Arc<Mutex<Server>>,
+
+        login: Option<(OwnedUserId, OwnedDeviceId)>,
+    }
+
+    impl StandIn {
+        pub(crate) fn login(&self, user_id: &UserId, device_id: &DeviceId) -> Self {
+            Self {
+                server: self.server.clone(),
+                login: Some((user_id.to_owned(), device_id.to_owned())),
+            }
+        }
+
+        fn device(&self) -> (OwnedUserId, OwnedDeviceId) {
+            self.login.clone().expect("stand-in should be logged in")
+        }
+
+        fn count(server: &Server, device: &(OwnedUserId, OwnedDeviceId)) -> u64 {
+            server.one_time_keys.get(device).map_or(0, |keys| {
+                keys.keys()
+                    .filter(|key_id| key_id.starts_with(ONE_TIME_KEY_ALGORITHM))
+                    .count() as u64
+            })
+        }
+    }
+
+    impl Homeserver for StandIn {
+        async fn upload_keys(
+            &self,
+            device_keys: Option<DeviceKeys>,
+            one_time_keys: BTreeMap<String, SignedKey>,
+        ) -> Result<u64> {
+            let (user_id, device_id) = self.device();
+            let mut server = self.server.lock().unwrap();
+
+            if let Some(keys) = device_keys {
+                let _ = server
+                    .device_keys
+                    .entry(user_id.clone())
+                    .or_default()
+                    .insert(device_id.clone(), keys);
+            }
+
+            let device = (user_id, device_id);
+            server
+                .one_time_keys
+                .entry(device.clone())
+                .or_default()
+                .extend(one_time_keys);
+
+            Ok(Self::count(&server, &device))
+        }
+
+        async fn query_keys(
+            &self,
+            users: &BTreeSet<OwnedUserId>,
+        ) -> Result<BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, DeviceKeys>>> {
+            let server = self.server.lock().unwrap();
+
+            Ok(users
+                .iter()
+                .filter_map(|user_id| {
+                    let devices = server.device_keys.get(user_id)?;
+
+                    Some((user_id.clone(), devices.clone()))
+                })
+                .collect())
+        }
+
+        async fn claim_keys(
+            &self,
+            devices: BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, String>>,
+        ) -> Result<Claimed> {
+            let mut server = self.server.lock().unwrap();
+            let mut claimed = Claimed::new();
+
+            for (user_id, devices) in devices {
+                for (device_id, algorithm) in devices {
+                    let Some(keys) = server
+                        .one_time_keys
+                        .get_mut(&(user_id.clone(), device_id.clone()))
+                    else {
+                        continue;
+                    };
+
+                    let Some(key_id) = keys
+                        .keys()
+                        .find(|key_id| key_id.starts_with(&format!("{algorithm}:")))
+                        .cloned()
+                    else {
+                        continue;
+                    };
+
+                    if let Some(key) = keys.remove(&key_id) {
+                        let _ = claimed
+                            .entry(user_id.clone())
+                            .or_default()
+                            .insert(device_id, BTreeMap::from([(key_id, key)]));
+                    }
+                }
+            }
+
+            Ok(claimed)
+        }
+
+        async fn send_to_device(
+            &self,
+            event_type: &str,
+            messages: BTreeMap<OwnedUserId, BTreeMap<String, Value>>,
+        ) -> Result<()> {
+            let (sender, _) = self.device();
+            let mut server = self.server.lock().unwrap();
+
+            for (user_id, devices) in messages {
+                for (device_id, content) in devices {
+                    let recipients = match device_id.as_str() {
+                        "*" => server
+                            .device_keys
+                            .get(&user_id)
+                            .map(|devices| devices.keys().cloned().collect())
+                            .unwrap_or_default(),
+                        _ => vec![OwnedDeviceId::from(device_id)],
+                    };
+
+                    for device_id in recipients {
+                        server
+                            .inbox
+                            .entry((user_id.clone(), device_id))
+                            .or_default()
+                            .push(ToDeviceEvent {
+                                sender: sender.clone(),
+                                kind: event_type.to_owned(),
+                                content: content.clone(),
+                            });
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        async fn sync_to_device(&self, since: Option<String>) -> Result<Synced> {
+            let device = self.device();
+            let mut server = self.server.lock().unwrap();
+
+            let events = server.inbox.remove(&device).unwrap_or_default();
+            let batch = since.map_or(0, |since| since.parse::<usize>().unwrap_or_default());
+
+            Ok(Synced {
+                next_batch: (batch + events.len()).to_string(),
+                events,
+                one_time_keys: Self::count(&server, &device),
+            })
+        }
+
+        async fn account_data(&self, event_type: &str) -> Result<Option<Value>> {
+            let (user_id, _) = self.device();
+            let server = self.server.lock().unwrap();
+
+            Ok(server
+                .account_data
+                .get(&(user_id, event_type.to_owned()))
+                .cloned())
+        }
+
+        async fn set_account_data(&self, event_type: &str, content: Value) -> Result<()> {
+            let (user_id, _) = self.device();
+            let mut server = self.server.lock().unwrap();
+
+            let _ = server
+                .account_data
+                .insert((user_id, event_type.to_owned()), content);
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+use super::{
+    backup::{Backup, BACKUP_EVENT_TYPE},
+    homeserver::Homeserver,
+    store, Matrix,
+};
+use crate::error::{Error, Result};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Restored {
+    /// Room keys the device did not have before.
+    pub imported: usize,
+}
+
+/// Imports the room keys of the backup into the device of the access token.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    passphrase: impl AsRef<str>,
+) -> Result<Restored> {
+    let access_token = access_token.as_ref();
+
+    let device = store::device(access_token).await?;
+    let mut device = device.lock().await;
+    let homeserver = Matrix::new(access_token, device.user_id.clone());
+
+    let Some(backup) = homeserver.account_data(BACKUP_EVENT_TYPE).await? else {
+        return Err(Error::NotFound);
+    };
+
+    let imported = device
+        .restore(
+            passphrase.as_ref(),
+            serde_json::from_value::<Backup>(backup)?,
+        )
+        .await?;
+
+    store::save(&device).await?;
+
+    Ok(Restored { imported })
+}
@@ -0,0 +1,359 @@
+//! Device state on disk, one file per device, with every secret pickled
+//! under a key derived from the configured one.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use matrix::{
+    client::account::whoami,
+    ruma_common::{DeviceId, OwnedDeviceId, OwnedRoomId, OwnedUserId, UserId},
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use vodozemac::{
+    megolm::{GroupSession, GroupSessionPickle, InboundGroupSession, InboundGroupSessionPickle},
+    olm::{Account, AccountPickle, Session, SessionPickle},
+    PickleError,
+};
+
+use super::{Device, Inbound, Outbound};
+use crate::{
+    commune,
+    error::{Error, Result},
+};
+
+type Devices = BTreeMap<(OwnedUserId, OwnedDeviceId), Arc<tokio::sync::Mutex<Device>>>;
+
+static DEVICES: Mutex<Devices> = Mutex::new(BTreeMap::new());
+
+#[derive(Deserialize, Serialize)]
+struct Pickle {
+    user_id: OwnedUserId,
+
+    device_id: OwnedDeviceId,
+
+    account: String,
+
+    published: bool,
+
+    one_time_keys: u64,
+
+    sessions: BTreeMap<String, Vec<String>>,
+
+    outbound: BTreeMap<OwnedRoomId, OutboundPickle>,
+
+    inbound: BTreeMap<String, InboundPickle>,
+
+    since: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct OutboundPickle {
+    session: String,
+
+    key: String,
+
+    shared_with: BTreeSet<(OwnedUserId, OwnedDeviceId)>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(super) struct InboundPickle {
+    room_id: OwnedRoomId,
+
+    sender_key: String,
+
+    /// Missing from keys stored before senders were recorded, which are
+    /// dropped as nothing can be decrypted with them.
+    #[serde(default)]
+    sender: Option<OwnedUserId>,
+
+    #[serde(default)]
+    sender_device: Option<OwnedDeviceId>,
+
+    session: String,
+}
+
+impl Inbound {
+    pub(super) fn pickle(&self, key: &[u8; 32]) -> InboundPickle {
+        InboundPickle {
+            room_id: self.room_id.clone(),
+            sender_key: self.sender_key.clone(),
+            sender: Some(self.sender.clone()),
+            sender_device: Some(self.sender_device.clone()),
+            session: self.session.pickle().encrypt(key),
+        }
+    }
+
+    pub(super) fn from_pickle(pickle: InboundPickle, key: &[u8; 32]) -> anyhow::Result<Self> {
+        let (Some(sender), Some(sender_device)) = (pickle.sender, pickle.sender_device) else {
+            anyhow::bail!("room key without its sender");
+        };
+
+        Ok(Self {
+            room_id: pickle.room_id,
+            sender_key: pickle.sender_key,
+            sender,
+            sender_device,
+            session: InboundGroupSession::from_pickle(InboundGroupSessionPickle::from_encrypted(
+                &pickle.session,
+                key,
+            )?),
+        })
+    }
+}
+
+impl Device {
+    fn pickle(&self, key: &[u8; 32]) -> Pickle {
+        Pickle {
+            user_id: self.user_id.clone(),
+            device_id: self.device_id.clone(),
+            account: self.account.pickle().encrypt(key),
+            published: self.published,
+            one_time_keys: self.one_time_keys,
+            sessions: self
+                .sessions
+                .iter()
+                .map(|(sender_key, sessions)| {
+                    (
+                        sender_key.clone(),
+                        sessions
+                            .iter()
+                            .map(|session| session.pickle().encrypt(key))
+                            .collect(),
+                    )
+                })
+                .collect(),
+            outbound: self
+                .outbound
+                .iter()
+                .map(|(room_id, outbound)| {
+                    (
+                        room_id.clone(),
+                        OutboundPickle {
+                            session: outbound.session.pickle().encrypt(key),
+                            key: outbound.key.clone(),
+                            shared_with: outbound.shared_with.clone(),
+                        },
+                    )
+                })
+                .collect(),
+            inbound: self
+                .inbound
+                .iter()
+                .map(|(session_id, inbound)| (session_id.clone(), inbound.pickle(key)))
+                .collect(),
+            since: self.since.clone(),
+        }
+    }
+
+    fn from_pickle(pickle: Pickle, key: &[u8; 32]) -> std::result::Result<Self, PickleError> {
+        let mut sessions = BTreeMap::new();
+
+        for (sender_key, pickles) in pickle.sessions {
+            let pickles = pickles
+                .iter()
+                .map(|session| {
+                    SessionPickle::from_encrypted(session, key).map(Session::from_pickle)
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+
+            let _ = sessions.insert(sender_key, pickles);
+        }
+
+        let mut outbound = BTreeMap::new();
+
+        for (room_id, session) in pickle.outbound {
+            let _ = outbound.insert(
+                room_id,
+                Outbound {
+                    session: GroupSession::from_pickle(GroupSessionPickle::from_encrypted(
+                        &session.session,
+                        key,
+                    )?),
+                    key: session.key,
+                    shared_with: session.shared_with,
+                },
+            );
+        }
+
+        let mut inbound = BTreeMap::new();
+
+        for (session_id, session) in pickle.inbound {
+            match Inbound::from_pickle(session, key) {
+                Ok(session) => {
+                    let _ = inbound.insert(session_id, session);
+                }
+                Err(e) => tracing::debug!(?e, %session_id, "dropping stored room key"),
+            }
+        }
+
+        Ok(Self {
+            user_id: pickle.user_id,
+            device_id: pickle.device_id,
+            account: Account::from_pickle(AccountPickle::from_encrypted(&pickle.account, key)?),
+            published: pickle.published,
+            one_time_keys: pickle.one_time_keys,
+            sessions,
+            outbound,
+            inbound,
+            since: pickle.since,
+        })
+    }
+}
+
+/// The key pickles are encrypted with, whatever the length of the secret.
+fn pickle_key(secret: &str) -> [u8; 32] {
+    Sha256::digest(secret.as_bytes()).into()
+}
+
+/// One file per device, named after the hex encoding of the IDs since both
+/// may contain characters that are not allowed in file names.
+fn path(store: &Path, user_id: &UserId, device_id: &DeviceId) -> PathBuf {
+    let name = format!("{user_id}|{device_id}")
+        .bytes()
+        .fold(String::new(), |mut name, byte| {
+            let _ = write!(name, "{byte:02x}");
+            name
+        });
+
+    store.join(name).with_extension("json")
+}
+
+/// The state of the device the access token belongs to, loaded from disk
+/// the first time and created if there is none yet.
+pub(super) async fn device(access_token: &str) -> Result<Arc<tokio::sync::Mutex<Device>>> {
+    let Some(config) = &commune().config.crypto else {
+        return Err(Error::CryptoDisabled);
+    };
+
+    let req = whoami::Request::new();
+
+    let whoami::Response {
+        user_id, device_id, ..
+    } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let key = (user_id, device_id);
+
+    if let Some(device) = DEVICES.lock().unwrap().get(&key) {
+        return Ok(device.clone());
+    }
+
+    let device = match tokio::fs::read(path(&config.store, &key.0, &key.1)).await {
+        Ok(bytes) => Device::from_pickle(
+            serde_json::from_slice(&bytes)?,
+            &pickle_key(&config.pickle_key.inner()),
+        )
+        .map_err(anyhow::Error::from)?,
+        Err(e) if e.kind() == ErrorKind::NotFound => Device::new(key.0.clone(), key.1.clone()),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(DEVICES
+        .lock()
+        .unwrap()
+        .entry(key)
+        .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(device)))
+        .clone())
+}
+
+pub(super) async fn save(device: &Device) -> Result<()> {
+    let Some(config) = &commune().config.crypto else {
+        return Err(Error::CryptoDisabled);
+    };
+
+    let pickle = device.pickle(&pickle_key(&config.pickle_key.inner()));
+    let path = path(&config.store, &device.user_id, &device.device_id);
+
+    tokio::fs::create_dir_all(&config.store).await?;
+
+    // written aside first, so a crash cannot leave half the keys behind
+    let partial = path.with_extension("partial");
+    tokio::fs::write(&partial, serde_json::to_vec(&pickle)?).await?;
+    tokio::fs::rename(partial, path).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::{device_id, room_id, serde::Raw, user_id, RoomId};
+    use serde_json::{json, Value};
+
+    use super::{super::homeserver::StandIn, *};
+
+    async fn send(
+        device: &mut Device,
+        homeserver: &StandIn,
+        room_id: &RoomId,
+        users: &BTreeSet<OwnedUserId>,
+        body: &str,
+    ) -> Vec<Raw<Value>> {
+        let content = device
+            .encrypt(
+                homeserver,
+                room_id,
+                users,
+                "m.room.message",
+                json!({ "body": body }),
+            )
+            .await
+            .unwrap();
+
+        vec![Raw::new(&json!({
+            "type": "m.room.encrypted",
+            "sender": device.user_id,
+            "content": content,
+        }))
+        .unwrap()]
+    }
+
+    #[tokio::test]
+    async fn restores_pickled_devices() {
+        let server = StandIn::default();
+        let alice_id = user_id!("@alice:example.com");
+        let bob_id = user_id!("@bob:example.com");
+        let users = BTreeSet::from([alice_id.to_owned(), bob_id.to_owned()]);
+        let room_id = room_id!("!direct:example.com");
+
+        let alice_hs = server.login(alice_id, device_id!("ALICE"));
+        let bob_hs = server.login(bob_id, device_id!("BOB"));
+        let mut alice = Device::new(alice_id.to_owned(), device_id!("ALICE").to_owned());
+        let mut bob = Device::new(bob_id.to_owned(), device_id!("BOB").to_owned());
+
+        bob.publish(&bob_hs).await.unwrap();
+
+        let events = send(&mut alice, &alice_hs, room_id, &users, "first").await;
+        let events = bob.decrypt_events(&bob_hs, room_id, events).await.unwrap();
+        assert_eq!(events[0].deserialize().unwrap()["content"]["body"], "first");
+
+        let key = pickle_key("pickle key");
+        let pickle = serde_json::to_vec(&bob.pickle(&key)).unwrap();
+
+        assert!(Device::from_pickle(
+            serde_json::from_slice(&pickle).unwrap(),
+            &pickle_key("another key")
+        )
+        .is_err());
+
+        let mut bob = Device::from_pickle(serde_json::from_slice(&pickle).unwrap(), &key).unwrap();
+        assert_eq!(
+            bob.curve25519_key(),
+            bob.device_keys().unwrap().keys["curve25519:BOB"]
+        );
+
+        // the room key and the position of the sync came along
+        let events = send(&mut alice, &alice_hs, room_id, &users, "second").await;
+        let events = bob.decrypt_events(&bob_hs, room_id, events).await.unwrap();
+        assert_eq!(
+            events[0].deserialize().unwrap()["content"]["body"],
+            "second"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Direct messages between two users. Each conversation is a private room
+//! marked with a state event naming both participants, and recorded in the
+//! `m.direct` account data like other clients do, so existing rooms are
+//! reused and show up as direct chats elsewhere. Rooms in `m.direct` without
+//! the marker were not started through Commune and are left alone.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#direct-messaging
+
+use std::collections::BTreeSet;
+
+use http::StatusCode;
+use matrix::{
+    client::{account_data, state},
+    ruma_client::Error::FromHttpResponse,
+    ruma_common::{
+        api::error::{FromHttpResponseError, MatrixError},
+        serde::Raw,
+        MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedUserId, RoomId, UserId,
+    },
+    ruma_events::{direct::DirectEventContent, GlobalAccountDataEventType, StateEventType},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commune,
+    error::{Error, Result},
+    membership::refused,
+    post::Author,
+    util::auth,
+};
+
+pub mod accept;
+pub mod invites;
+pub mod list;
+pub mod messages;
+pub mod send;
+pub mod start;
+
+/// State event marking a room as a conversation between its two users.
+pub const DIRECT_EVENT_TYPE: &str = "sh.commune.direct";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct Direct {
+    pub(crate) users: BTreeSet<OwnedUserId>,
+
+    /// Whether messages are encrypted end to end, which is decided when the
+    /// conversation starts.
+    #[serde(default)]
+    pub(crate) encrypted: bool,
+}
+
+impl Direct {
+    /// The participant that is not `user_id`.
+    pub(crate) fn other(&self, user_id: &UserId) -> Option<&UserId> {
+        self.users
+            .iter()
+            .find(|participant| *participant != user_id)
+            .map(|participant| participant.as_ref())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Message {
+    pub event_id: OwnedEventId,
+
+    pub sender: Author,
+
+    pub body: String,
+
+    /// Sanitized HTML rendering of the body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_body: Option<String>,
+
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+}
+
+/// Reads the marker of a conversation, which only participants can.
+pub(crate) async fn direct(access_token: &str, room_id: &RoomId) -> Result<Direct> {
+    let req = state::get::Request::new(
+        room_id.to_owned(),
+        StateEventType::from(DIRECT_EVENT_TYPE),
+        String::new(),
+    );
+
+    let state::get::Response { content, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .map_err(refused)?;
+
+    Ok(content.deserialize_as::<Direct>()?)
+}
+
+/// Ensures the caller takes part in the conversation, returning its marker.
+pub(crate) async fn participant(access_token: &str, room_id: &RoomId) -> Result<Direct> {
+    let user_id = auth::user_id(access_token).await?;
+    let direct = direct(access_token, room_id).await?;
+
+    match direct.users.contains(&user_id) {
+        true => Ok(direct),
+        false => Err(Error::NotFound),
+    }
+}
+
+/// The `m.direct` account data of the user, absent meaning no conversations.
+pub(crate) async fn rooms(access_token: &str, user_id: &UserId) -> Result<DirectEventContent> {
+    let req =
+        account_data::get::Request::new(user_id.to_owned(), GlobalAccountDataEventType::Direct);
+
+    match commune().send_matrix_request(req, Some(access_token)).await {
+        Ok(account_data::get::Response { account_data, .. }) => {
+            Ok(account_data.deserialize_as::<DirectEventContent>()?)
+        }
+        Err(FromHttpResponse(FromHttpResponseError::Server(MatrixError {
+            status_code: StatusCode::NOT_FOUND,
+            ..
+        }))) => Ok(DirectEventContent::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Records the room as a conversation with `other` in `m.direct`.
+pub(crate) async fn remember(
+    access_token: &str,
+    user_id: &UserId,
+    mut rooms: DirectEventContent,
+    other: &UserId,
+    room_id: &RoomId,
+) -> Result<()> {
+    let entry = rooms.entry(other.to_owned()).or_default();
+
+    if entry.iter().any(|known| known == room_id) {
+        return Ok(());
+    }
+
+    entry.push(room_id.to_owned());
+
+    let req = account_data::set::Request::new(
+        user_id.to_owned(),
+        GlobalAccountDataEventType::Direct,
+        Raw::new(&rooms)?.cast(),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::user_id;
+
+    use super::*;
+
+    #[test]
+    fn finds_the_other_participant() {
+        let alice = user_id!("@alice:example.com");
+        let bob = user_id!("@bob:example.com");
+
+        let direct = Direct {
+            users: [alice.to_owned(), bob.to_owned()].into(),
+            encrypted: false,
+        };
+
+        assert_eq!(direct.other(alice), Some(bob));
+        assert_eq!(direct.other(bob), Some(alice));
+    }
+}
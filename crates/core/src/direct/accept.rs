@@ -0,0 +1,64 @@
+use matrix::{
+    admin::room::get_state,
+    client::membership::join,
+    ruma_common::OwnedRoomId,
+    ruma_events::room::member::{MembershipState, RoomMemberEventContent},
+};
+
+use super::{Direct, DIRECT_EVENT_TYPE};
+use crate::{
+    commune,
+    error::{Error, Result},
+    membership::refused,
+    util::auth,
+};
+
+/// Joins a conversation the user was invited into, provided it is one
+/// between them and the inviter. The administrator account checks that,
+/// since the state is not readable before joining.
+pub async fn service(access_token: impl AsRef<str>, room_id: OwnedRoomId) -> Result<()> {
+    let access_token = access_token.as_ref();
+    let user_id = auth::user_id(access_token).await?;
+
+    let req = get_state::Request::new(room_id.clone());
+
+    let get_state::Response { state, .. } = commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await
+        .map_err(refused)?;
+
+    let inviter = state
+        .iter()
+        .filter(|event| event.kind == "m.room.member" && event.state_key == user_id.as_str())
+        .find(|event| {
+            event
+                .content
+                .deserialize_as::<RoomMemberEventContent>()
+                .is_ok_and(|content| {
+                    content.membership == MembershipState::Invite && content.is_direct == Some(true)
+                })
+        })
+        .map(|event| event.sender.clone())
+        .ok_or(Error::NotInvited)?;
+
+    let participants = state
+        .iter()
+        .find(|event| event.kind == DIRECT_EVENT_TYPE && event.state_key.is_empty())
+        .and_then(|event| event.content.deserialize_as::<Direct>().ok())
+        .map(|direct| direct.users);
+
+    if participants != Some([user_id.clone(), inviter.clone()].into()) {
+        return Err(Error::NotInvited);
+    }
+
+    let req = join::Request::new(room_id.clone().into());
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .map_err(refused)?;
+
+    let rooms = super::rooms(access_token, &user_id).await?;
+
+    super::remember(access_token, &user_id, rooms, &inviter, &room_id).await
+}
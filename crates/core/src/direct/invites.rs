@@ -0,0 +1,100 @@
+use matrix::{
+    client::sync::v3,
+    ruma_common::{serde::Raw, OwnedRoomId, OwnedUserId, UserId},
+    ruma_events::{
+        room::member::{MembershipState, RoomMemberEventContent},
+        AnyStrippedStateEvent, StateEventType,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    commune,
+    error::Result,
+    post::{self, Author},
+    util::auth,
+};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Invite {
+    pub room_id: OwnedRoomId,
+
+    /// The user who started the conversation.
+    pub user: Author,
+}
+
+/// Conversations others started with the user, which stay pending until
+/// accepted.
+pub async fn service(access_token: impl AsRef<str>) -> Result<Vec<Invite>> {
+    let access_token = access_token.as_ref();
+    let user_id = auth::user_id(access_token).await?;
+
+    // invites come with their stripped state whatever the filter, the rest
+    // of the rooms is left empty
+    let mut req = v3::Request::new();
+    req.timeout = None;
+    req.filter = json!({
+        "presence": { "types": [] },
+        "account_data": { "types": [] },
+        "room": {
+            "timeline": { "limit": 0, "types": [] },
+            "state": { "types": [] },
+            "ephemeral": { "types": [] },
+            "account_data": { "types": [] },
+        },
+    })
+    .to_string();
+
+    let v3::Response { rooms, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let mut invites = Vec::new();
+
+    for (room_id, room) in rooms.invite {
+        let Some(inviter) = room
+            .invite_state
+            .events
+            .iter()
+            .find_map(|event| inviter(event, &user_id))
+        else {
+            continue;
+        };
+
+        invites.push(Invite {
+            room_id,
+            user: post::author(inviter).await,
+        });
+    }
+
+    Ok(invites)
+}
+
+/// The inviter, if the stripped event is a direct invite of the user.
+fn inviter(event: &Raw<AnyStrippedStateEvent>, user_id: &UserId) -> Option<OwnedUserId> {
+    #[derive(Deserialize)]
+    struct Stripped {
+        #[serde(rename = "type")]
+        kind: StateEventType,
+
+        state_key: OwnedUserId,
+
+        sender: OwnedUserId,
+
+        content: RoomMemberEventContent,
+    }
+
+    let Stripped {
+        kind,
+        state_key,
+        sender,
+        content,
+    } = event.deserialize_as().ok()?;
+
+    (kind == StateEventType::RoomMember
+        && state_key == user_id
+        && content.membership == MembershipState::Invite
+        && content.is_direct == Some(true))
+    .then_some(sender)
+}
@@ -0,0 +1,124 @@
+use matrix::{client::sync::v3, ruma_common::OwnedRoomId};
+use serde::Serialize;
+use serde_json::json;
+
+use super::{messages::MessageEvent, Direct, Message, DIRECT_EVENT_TYPE};
+use crate::{
+    commune, crypto,
+    error::Result,
+    post::{self, Author},
+    util::auth,
+};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Conversation {
+    pub room_id: OwnedRoomId,
+
+    /// The other participant.
+    pub user: Author,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_message: Option<Message>,
+
+    /// Counted from the read receipt of the caller.
+    pub unread_count: usize,
+}
+
+/// Lists the conversations of the user, most recent first. Pending invites
+/// are listed apart, with [`super::invites`].
+pub async fn service(access_token: impl AsRef<str>) -> Result<Vec<Conversation>> {
+    let access_token = access_token.as_ref();
+    let user_id = auth::user_id(access_token).await?;
+
+    // others can only start encrypted conversations with published devices
+    if crypto::enabled() {
+        crypto::publish(access_token).await?;
+    }
+
+    let room_ids: Vec<_> = super::rooms(access_token, &user_id)
+        .await?
+        .values()
+        .flatten()
+        .cloned()
+        .collect();
+
+    if room_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // only the conversations and their latest message are needed
+    let mut req = v3::Request::new();
+    req.timeout = None;
+    req.filter = json!({
+        "presence": { "types": [] },
+        "account_data": { "types": [] },
+        "room": {
+            "rooms": room_ids,
+            "timeline": { "limit": 1, "types": ["m.room.message", "m.room.encrypted"] },
+            "state": { "types": [DIRECT_EVENT_TYPE] },
+            "ephemeral": { "types": [] },
+            "account_data": { "types": [] },
+        },
+    })
+    .to_string();
+
+    let v3::Response { rooms, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let mut conversations = Vec::new();
+
+    for (room_id, room) in rooms.join {
+        let Some(direct) = room
+            .state
+            .events
+            .iter()
+            .filter(|event| {
+                event.get_field::<String>("type").ok().flatten().as_deref()
+                    == Some(DIRECT_EVENT_TYPE)
+            })
+            .find_map(|event| event.get_field::<Direct>("content").ok().flatten())
+        else {
+            continue;
+        };
+
+        let Some(other) = direct.other(&user_id).map(ToOwned::to_owned) else {
+            continue;
+        };
+
+        let mut last_message = None;
+        let mut events = room.timeline.events.last().cloned().into_iter().collect();
+
+        if direct.encrypted && crypto::enabled() {
+            events = crypto::decrypt(access_token, &room_id, events).await?;
+        }
+
+        if let Some(event) = events.first().and_then(MessageEvent::from_raw) {
+            let sender = post::author(event.sender.clone()).await;
+            last_message = Some(event.into_message(sender));
+        }
+
+        conversations.push(Conversation {
+            room_id,
+            user: post::author(other).await,
+            last_message,
+            unread_count: room
+                .unread_notifications
+                .notification_count
+                .unwrap_or_default(),
+        });
+    }
+
+    conversations.sort_by(|a, b| {
+        let ts = |conversation: &Conversation| {
+            conversation
+                .last_message
+                .as_ref()
+                .map(|message| message.origin_server_ts)
+        };
+
+        ts(b).cmp(&ts(a))
+    });
+
+    Ok(conversations)
+}
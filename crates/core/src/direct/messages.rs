@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use matrix::{
+    client::messages,
+    ruma_common::{
+        api::Direction, serde::Raw, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId,
+        OwnedUserId,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::Message;
+use crate::{
+    commune, crypto,
+    error::Result,
+    post::{self, format::Formatted, Author},
+};
+
+pub const DEFAULT_LIMIT: u64 = 50;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Messages {
+    /// Newest first.
+    pub messages: Vec<Message>,
+
+    /// Passed as `from` for older messages, absent once there are none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_batch: Option<String>,
+}
+
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    from: Option<String>,
+    limit: Option<u64>,
+) -> Result<Messages> {
+    let access_token = access_token.as_ref();
+
+    let direct = super::participant(access_token, &room_id).await?;
+
+    let mut req = messages::Request::new(room_id.clone(), Direction::Backward);
+    req.from = from;
+    req.limit = Some(limit.unwrap_or(DEFAULT_LIMIT));
+    req.filter = json!({ "types": ["m.room.message", "m.room.encrypted"] }).to_string();
+
+    let messages::Response { chunk, end, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let chunk = match direct.encrypted {
+        true => crypto::decrypt(access_token, &room_id, chunk).await?,
+        false => chunk,
+    };
+
+    let mut authors = BTreeMap::<OwnedUserId, Author>::new();
+    let mut messages = Vec::with_capacity(chunk.len());
+
+    for event in &chunk {
+        let Some(message) = MessageEvent::from_raw(event) else {
+            continue;
+        };
+
+        let sender = match authors.get(&message.sender) {
+            Some(author) => author.clone(),
+            None => {
+                let author = post::author(message.sender.clone()).await;
+                let _ = authors.insert(message.sender.clone(), author.clone());
+
+                author
+            }
+        };
+
+        messages.push(message.into_message(sender));
+    }
+
+    Ok(Messages {
+        messages,
+        next_batch: end.filter(|_| !chunk.is_empty()),
+    })
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MessageEvent {
+    pub(crate) event_id: OwnedEventId,
+
+    pub(crate) sender: OwnedUserId,
+
+    pub(crate) origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    pub(crate) content: MessageContent,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MessageContent {
+    #[serde(default)]
+    body: String,
+
+    #[serde(flatten)]
+    formatted: Formatted,
+}
+
+impl MessageEvent {
+    /// Removed messages have no body left and are skipped.
+    pub(crate) fn from_raw<T>(event: &Raw<T>) -> Option<Self> {
+        event
+            .deserialize_as::<Self>()
+            .ok()
+            .filter(|event| !event.content.body.is_empty())
+    }
+
+    pub(crate) fn into_message(self, sender: Author) -> Message {
+        Message {
+            event_id: self.event_id,
+            sender,
+            formatted_body: self.content.formatted.sanitized(),
+            body: self.content.body,
+            origin_server_ts: self.origin_server_ts,
+        }
+    }
+}
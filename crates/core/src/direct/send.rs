@@ -0,0 +1,49 @@
+use matrix::{
+    client::event::send::*,
+    ruma_common::{serde::Raw, OwnedRoomId, OwnedTransactionId, TransactionId},
+    ruma_events::{room::message::RoomMessageEventContent, MessageLikeEventType},
+};
+
+use crate::{commune, crypto, error::Result, post::format};
+
+/// Retrying with the same `txn_id` does not send the message twice.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    txn_id: Option<OwnedTransactionId>,
+    body: impl Into<String>,
+) -> Result<Response> {
+    let access_token = access_token.as_ref();
+
+    let direct = super::participant(access_token, &room_id).await?;
+
+    let content =
+        serde_json::to_value(RoomMessageEventContent::new(format::markdown(body.into())))?;
+
+    let (event_type, content) = match direct.encrypted {
+        true => (
+            MessageLikeEventType::RoomEncrypted,
+            crypto::encrypt(
+                access_token,
+                &room_id,
+                &direct.users,
+                "m.room.message",
+                content,
+            )
+            .await?,
+        ),
+        false => (MessageLikeEventType::RoomMessage, content),
+    };
+
+    let req = Request::new_raw(
+        room_id,
+        event_type,
+        txn_id.unwrap_or_else(TransactionId::new),
+        Raw::new(&content)?.cast(),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .map_err(Into::into)
+}
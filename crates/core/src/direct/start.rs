@@ -0,0 +1,164 @@
+use matrix::{
+    client::{
+        create_room::{self, RoomPreset},
+        state::{get, send},
+    },
+    ruma_common::{serde::Raw, OwnedRoomId, OwnedUserId, RoomId, UserId},
+    ruma_events::{room::member::MembershipState, StateEventType},
+};
+use serde::Serialize;
+use serde_json::{json, value::to_raw_value, Value};
+
+use super::{Direct, DIRECT_EVENT_TYPE};
+use crate::{
+    commune, crypto,
+    error::{Error, Result},
+    membership::{membership, refused},
+    util::auth,
+};
+
+/// Power both participants are left with, below what inviting and editing
+/// the state of the conversation require.
+const PARTICIPANT_LEVEL: i64 = 50;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Started {
+    pub room_id: OwnedRoomId,
+
+    /// Whether a new room was created rather than an existing one reused.
+    pub created: bool,
+}
+
+/// Reuses the latest conversation with the user both are still part of,
+/// otherwise creates one and invites them.
+pub async fn service(access_token: impl AsRef<str>, user_id: OwnedUserId) -> Result<Started> {
+    let access_token = access_token.as_ref();
+    let own_id = auth::user_id(access_token).await?;
+
+    if own_id == user_id {
+        return Err(Error::SelfDirect);
+    }
+
+    let rooms = super::rooms(access_token, &own_id).await?;
+
+    for room_id in rooms.get(&user_id).into_iter().flatten().rev() {
+        let Ok(direct) = super::direct(access_token, room_id).await else {
+            continue;
+        };
+
+        if direct.other(&own_id) != Some(&user_id) {
+            continue;
+        }
+
+        let own = membership(room_id, &own_id).await?;
+        let theirs = membership(room_id, &user_id).await?;
+
+        if own == Some(MembershipState::Join)
+            && matches!(
+                theirs,
+                Some(MembershipState::Join | MembershipState::Invite)
+            )
+        {
+            return Ok(Started {
+                room_id: room_id.clone(),
+                created: false,
+            });
+        }
+    }
+
+    let encrypted = crypto::enabled();
+
+    // the other side needs the keys of this device to reply
+    if encrypted {
+        crypto::publish(access_token).await?;
+    }
+
+    let direct = Direct {
+        users: [own_id.clone(), user_id.clone()].into(),
+        encrypted,
+    };
+
+    // Rooms that require an invite keep everyone else out, and Commune does
+    // not invite anyone into a conversation.
+    let mut req = create_room::Request::new();
+    req.preset = Some(RoomPreset::TrustedPrivateChat);
+    req.is_direct = true;
+    req.invite = vec![user_id.clone()];
+    // The preset makes both participants administrators, which would let
+    // either invite others or rewrite the marker. The creator keeps the power
+    // to send the invite and steps down once the room is set up.
+    req.power_level_content_override = Some(Raw::from_json(to_raw_value(&json!({
+        "users": {
+            own_id.as_str(): 100,
+            user_id.as_str(): PARTICIPANT_LEVEL,
+        },
+        "invite": 100,
+        "state_default": 100,
+        "events": {
+            DIRECT_EVENT_TYPE: 100,
+            "m.room.power_levels": 100,
+        },
+    }))?));
+    req.initial_state = vec![Raw::new(&json!({
+        "type": DIRECT_EVENT_TYPE,
+        "state_key": "",
+        "content": direct,
+    }))?
+    .cast()];
+
+    if encrypted {
+        req.initial_state.push(
+            Raw::new(&json!({
+                "type": "m.room.encryption",
+                "state_key": "",
+                "content": { "algorithm": crypto::MEGOLM_ALGORITHM },
+            }))?
+            .cast(),
+        );
+    }
+
+    let create_room::Response { room_id, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    step_down(access_token, &room_id, &own_id).await?;
+
+    super::remember(access_token, &own_id, rooms, &user_id, &room_id).await?;
+
+    Ok(Started {
+        room_id,
+        created: true,
+    })
+}
+
+/// Lowers the creator to the level of the other participant.
+async fn step_down(access_token: &str, room_id: &RoomId, own_id: &UserId) -> Result<()> {
+    let req = get::Request::new(
+        room_id.to_owned(),
+        StateEventType::RoomPowerLevels,
+        String::new(),
+    );
+
+    let get::Response { content, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .map_err(refused)?;
+
+    // edited as JSON, so fields unknown to us are preserved
+    let mut power_levels: Value = content.deserialize_as()?;
+    power_levels["users"][own_id.as_str()] = json!(PARTICIPANT_LEVEL);
+
+    let req = send::Request::new_raw(
+        room_id.to_owned(),
+        StateEventType::RoomPowerLevels,
+        "",
+        Raw::new(&power_levels)?.cast(),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .map_err(refused)?;
+
+    Ok(())
+}
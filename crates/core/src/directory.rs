@@ -0,0 +1,86 @@
+//! The directory lists the public spaces of this homeserver, administrators
+//! can feature or hide entries which is stored in their account data.
+
+use std::collections::BTreeSet;
+
+use http::StatusCode;
+use matrix::{
+    client::account_data::get,
+    ruma_client::Error::FromHttpResponse,
+    ruma_common::{
+        api::error::{FromHttpResponseError, MatrixError},
+        OwnedMxcUri, OwnedRoomAliasId, OwnedRoomId, OwnedUserId,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{commune, error::Result, util::auth};
+
+pub mod curate;
+pub mod list;
+
+pub const CURATION_EVENT_TYPE: &str = "sh.commune.directory";
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Curation {
+    #[serde(default)]
+    pub featured: BTreeSet<OwnedRoomId>,
+
+    #[serde(default)]
+    pub hidden: BTreeSet<OwnedRoomId>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Entry {
+    pub room_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_alias: Option<OwnedRoomAliasId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<OwnedMxcUri>,
+
+    pub joined_members: u64,
+
+    pub featured: bool,
+}
+
+impl From<matrix::admin::room::Room> for Entry {
+    fn from(room: matrix::admin::room::Room) -> Self {
+        Self {
+            room_id: room.room_id,
+            canonical_alias: room.canonical_alias,
+            name: room.name,
+            topic: room.details.and_then(|details| details.topic),
+            avatar: room.avatar,
+            joined_members: room.joined_members,
+            featured: false,
+        }
+    }
+}
+
+/// Retrieves the curation from the account data of the administrator account
+/// that Commune uses, an absent entry means nothing has been curated yet.
+pub(crate) async fn curation() -> Result<(Curation, OwnedUserId)> {
+    let admin_token = commune().config.matrix.admin_token.inner();
+    let admin_id = auth::admin_id().await?;
+
+    let req = get::Request::new(admin_id.clone(), CURATION_EVENT_TYPE.into());
+
+    match commune().send_matrix_request(req, Some(&admin_token)).await {
+        Ok(get::Response { account_data, .. }) => {
+            Ok((account_data.deserialize_as::<Curation>()?, admin_id))
+        }
+        Err(FromHttpResponse(FromHttpResponseError::Server(MatrixError {
+            status_code: StatusCode::NOT_FOUND,
+            ..
+        }))) => Ok((Curation::default(), admin_id)),
+        Err(e) => Err(e.into()),
+    }
+}
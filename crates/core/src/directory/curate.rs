@@ -0,0 +1,40 @@
+use matrix::{
+    client::account_data::set,
+    ruma_common::{serde::Raw, OwnedRoomId},
+};
+
+use super::Curation;
+use crate::{commune, error::Result, util::auth};
+
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    featured: bool,
+    hidden: bool,
+) -> Result<Curation> {
+    let user_id = auth::user_id(access_token).await?;
+    auth::ensure_admin(&user_id).await?;
+
+    let (mut curation, admin_id) = super::curation().await?;
+
+    let _ = match featured {
+        true => curation.featured.insert(room_id.clone()),
+        false => curation.featured.remove(&room_id),
+    };
+    let _ = match hidden {
+        true => curation.hidden.insert(room_id),
+        false => curation.hidden.remove(&room_id),
+    };
+
+    let req = set::Request::new(
+        admin_id,
+        super::CURATION_EVENT_TYPE.into(),
+        Raw::new(&curation)?.cast(),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await?;
+
+    Ok(curation)
+}
@@ -0,0 +1,130 @@
+use matrix::{
+    admin::room::{get_room, get_rooms, Room},
+    ruma_common::{api::Direction, room::RoomType},
+};
+use serde::{Deserialize, Serialize};
+
+use super::Entry;
+use crate::{commune, error::Result};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sort {
+    #[default]
+    Members,
+
+    /// Synapse does not keep track of the last activity in a room, spaces
+    /// only contain state so we approximate it by the amount of state events.
+    Activity,
+
+    Name,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Directory {
+    /// Featured spaces are only returned along with the first page.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub featured: Vec<Entry>,
+
+    pub spaces: Vec<Entry>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_batch: Option<u64>,
+}
+
+pub async fn service(
+    search_term: Option<String>,
+    sort: Sort,
+    from: Option<u64>,
+    limit: u64,
+) -> Result<Directory> {
+    let admin_token = commune().config.matrix.admin_token.inner();
+    let (curation, _) = super::curation().await?;
+
+    let (order_by, direction) = match sort {
+        Sort::Members => (get_rooms::OrderBy::JoinedMembers, Direction::Backward),
+        Sort::Activity => (get_rooms::OrderBy::StateEvents, Direction::Backward),
+        Sort::Name => (get_rooms::OrderBy::Name, Direction::Forward),
+    };
+
+    let is_listed = |room: &Room| {
+        room.public
+            && room.room_type == Some(RoomType::Space)
+            && !curation.hidden.contains(&room.room_id)
+    };
+
+    let mut spaces = Vec::new();
+    let mut offset = from.unwrap_or_default();
+    let mut next_batch = None;
+
+    // the admin API does not filter by room type, so we keep requesting pages
+    // until we have enough spaces to fill ours
+    loop {
+        let mut req = get_rooms::Request::new(order_by.clone(), direction);
+        req.from = offset;
+        req.limit = Some(limit);
+        req.search_term = search_term.clone().unwrap_or_default();
+
+        let get_rooms::Response { rooms, total, .. } = commune()
+            .send_matrix_request(req, Some(&admin_token))
+            .await?;
+
+        let fetched = rooms.len() as u64;
+
+        for room in rooms {
+            offset += 1;
+
+            if is_listed(&room) {
+                spaces.push(Entry::from(room));
+            }
+
+            if spaces.len() as u64 == limit {
+                break;
+            }
+        }
+
+        if spaces.len() as u64 == limit {
+            next_batch = Some(offset).filter(|offset| *offset < total);
+            break;
+        }
+
+        if offset >= total || fetched == 0 {
+            break;
+        }
+    }
+
+    let mut featured = Vec::new();
+
+    if from.is_none() && search_term.is_none() {
+        for room_id in &curation.featured {
+            let req = get_room::Request::new(room_id.clone());
+
+            // a featured room that was deleted since is left out
+            let room = match commune().send_matrix_request(req, Some(&admin_token)).await {
+                Ok(get_room::Response { room, .. }) => room,
+                Err(e) => {
+                    tracing::debug!(?e, %room_id, "skipping unavailable featured room");
+
+                    continue;
+                }
+            };
+
+            if is_listed(&room) {
+                featured.push(Entry {
+                    featured: true,
+                    ..Entry::from(room)
+                });
+            }
+        }
+    }
+
+    for entry in &mut spaces {
+        entry.featured = curation.featured.contains(&entry.room_id);
+    }
+
+    Ok(Directory {
+        featured,
+        spaces,
+        next_batch,
+    })
+}
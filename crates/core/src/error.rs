@@ -15,6 +15,45 @@ pub enum Error {
     #[error("failed to validate identifier: {0}")]
     InvalidIdentifier(#[from] matrix::ruma_identifiers_validation::Error),
 
+    #[error("failed to (de)serialize Matrix event: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("you are not allowed to access this resource")]
+    Forbidden,
+
+    #[error("the requested resource could not be found")]
+    NotFound,
+
+    #[error("you are banned from this room")]
+    Banned,
+
+    #[error("this room requires an invite to join")]
+    NotInvited,
+
+    #[error("the provided pagination cursor is invalid")]
+    InvalidCursor,
+
+    #[error("slugs must consist of 1 to 64 lowercase letters, digits or dashes")]
+    InvalidSlug,
+
+    #[error("this alias is reserved")]
+    ReservedAlias,
+
+    #[error("this alias is already taken")]
+    AliasTaken,
+
+    #[error("direct messages need another user")]
+    SelfDirect,
+
+    #[error("end-to-end encryption is not enabled on this instance")]
+    CryptoDisabled,
+
+    #[error("the passphrase does not open the key backup")]
+    WrongPassphrase,
+
+    #[error("space child order must consist of at most 50 printable ASCII characters")]
+    InvalidOrder,
+
     #[error("an IO operation failed: {0}")]
     IO(#[from] std::io::Error),
 
@@ -27,6 +66,15 @@ pub enum Error {
 
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
-        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+        let status = match self {
+            Error::Forbidden | Error::Banned | Error::NotInvited | Error::WrongPassphrase => {
+                StatusCode::FORBIDDEN
+            }
+            Error::NotFound => StatusCode::NOT_FOUND,
+            Error::AliasTaken => StatusCode::CONFLICT,
+            _ => StatusCode::BAD_REQUEST,
+        };
+
+        (status, self.to_string()).into_response()
     }
 }
@@ -0,0 +1,10 @@
+//! Forward extremities are the latest events of a room that nothing points to
+//! yet. Rooms gather many of them when federation or a crash interrupts the
+//! homeserver, and every event sent after has to resolve the state between
+//! all of them, so the room gets slow. Administrators can find such rooms and
+//! have the homeserver drop all but the most recent extremities.
+//!
+//! reference: https://matrix-org.github.io/synapse/latest/admin_api/rooms.html#forward-extremities-admin-api
+
+pub mod clear;
+pub mod list;
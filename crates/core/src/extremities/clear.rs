@@ -0,0 +1,25 @@
+use matrix::{admin::room::forward_extremities::delete, ruma_common::OwnedRoomId};
+use serde::Serialize;
+
+use crate::{commune, error::Result, util::auth};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Cleared {
+    /// The amount of extremities dropped.
+    pub deleted: u64,
+}
+
+/// Drops all but the most recent forward extremities of a room. Only
+/// administrators of the homeserver may do so.
+pub async fn service(access_token: impl AsRef<str>, room_id: OwnedRoomId) -> Result<Cleared> {
+    let user_id = auth::user_id(access_token).await?;
+    auth::ensure_admin(&user_id).await?;
+
+    let req = delete::Request::new(room_id);
+
+    let delete::Response { deleted, .. } = commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await?;
+
+    Ok(Cleared { deleted })
+}
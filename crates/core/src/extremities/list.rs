@@ -0,0 +1,83 @@
+use matrix::{
+    admin::room::{forward_extremities::get, get_rooms},
+    ruma_common::{api::Direction, OwnedRoomId, RoomVersionId},
+};
+use serde::Serialize;
+
+use crate::{commune, error::Result, util::auth};
+
+/// Rooms with fewer extremities than this are left out by default.
+pub const DEFAULT_MIN: u64 = 10;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Room {
+    pub room_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    pub version: RoomVersionId,
+
+    pub joined_members: u64,
+
+    /// The amount of forward extremities.
+    pub extremities: u64,
+}
+
+/// Lists the rooms of the homeserver with at least `min` forward extremities,
+/// the worst first. Only administrators of the homeserver may look.
+pub async fn service(access_token: impl AsRef<str>, min: u64) -> Result<Vec<Room>> {
+    let user_id = auth::user_id(access_token).await?;
+    auth::ensure_admin(&user_id).await?;
+
+    let admin_token = commune().config.matrix.admin_token.inner();
+
+    let mut rooms = Vec::new();
+    let mut from = 0;
+
+    loop {
+        let mut req = get_rooms::Request::new(get_rooms::OrderBy::Name, Direction::Forward);
+        req.from = from;
+        req.limit = Some(500);
+
+        let get_rooms::Response {
+            rooms: page,
+            next_batch,
+            ..
+        } = commune()
+            .send_matrix_request(req, Some(&admin_token))
+            .await?;
+
+        for room in page {
+            let req = get::Request::new(room.room_id.clone());
+
+            let count = match commune().send_matrix_request(req, Some(&admin_token)).await {
+                Ok(get::Response { count, .. }) => count,
+                Err(e) => {
+                    tracing::debug!(?e, room_id = %room.room_id, "failed to count extremities");
+
+                    continue;
+                }
+            };
+
+            if count >= min {
+                rooms.push(Room {
+                    room_id: room.room_id,
+                    name: room.name,
+                    version: room.version,
+                    joined_members: room.joined_members,
+                    extremities: count,
+                });
+            }
+        }
+
+        match next_batch.and_then(|next_batch| next_batch.parse().ok()) {
+            Some(next_batch) => from = next_batch,
+            None => break,
+        }
+    }
+
+    rooms.sort_by(|a, b| b.extremities.cmp(&a.extremities));
+
+    Ok(rooms)
+}
@@ -6,7 +6,22 @@ pub mod error;
 pub mod util;
 
 pub mod account;
+pub mod alias;
+pub mod crypto;
+pub mod direct;
+pub mod directory;
+pub mod extremities;
+pub mod membership;
+pub mod moderation;
+pub mod notification;
+pub mod post;
 pub mod profile;
+pub mod read;
+pub mod search;
+pub mod settings;
+pub mod space;
+pub mod stream;
+pub mod sync;
 
 use std::sync::RwLock;
 
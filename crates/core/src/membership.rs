@@ -0,0 +1,132 @@
+//! Membership changes of the caller in boards and spaces. Refusals by the
+//! homeserver are mapped to errors that explain why.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#room-membership
+
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::StatusCode;
+use matrix::{
+    admin::room::get_state,
+    client::membership::joined_rooms,
+    ruma_client::Error::FromHttpResponse,
+    ruma_common::{
+        api::error::{FromHttpResponseError, MatrixError},
+        OwnedRoomId, RoomId, UserId,
+    },
+    ruma_events::room::member::MembershipState,
+    HandleError,
+};
+
+use crate::{
+    commune,
+    error::{Error, Result},
+};
+
+pub mod invite;
+pub mod join;
+pub mod knock;
+pub mod leave;
+
+/// How long the rooms the administrator account joined are cached, which
+/// only changes when a board starts being streamed.
+const ADMIN_ROOMS_TTL: Duration = Duration::from_secs(60);
+
+type AdminRooms = Option<(Arc<BTreeSet<OwnedRoomId>>, Instant)>;
+
+static ADMIN_ROOMS: Mutex<AdminRooms> = Mutex::new(None);
+
+/// Maps the status codes shared by all membership endpoints.
+pub(crate) fn refused(e: HandleError) -> Error {
+    match e {
+        FromHttpResponse(FromHttpResponseError::Server(MatrixError {
+            status_code: StatusCode::NOT_FOUND,
+            ..
+        })) => Error::NotFound,
+        FromHttpResponse(FromHttpResponseError::Server(MatrixError {
+            status_code: StatusCode::FORBIDDEN,
+            ..
+        })) => Error::Forbidden,
+        e => e.into(),
+    }
+}
+
+/// The homeserver refuses joins and knocks with the same status code, the
+/// membership of the user tells us whether they are banned.
+pub(crate) async fn refused_entry(e: HandleError, room_id: &RoomId, user_id: &UserId) -> Error {
+    match refused(e) {
+        Error::Forbidden => match membership(room_id, user_id).await {
+            Ok(Some(MembershipState::Ban)) => Error::Banned,
+            Ok(_) => Error::NotInvited,
+            Err(e) => e,
+        },
+        e => e,
+    }
+}
+
+/// Looks up the membership of a user through the administrator account,
+/// since users that are not part of a room cannot read its state.
+pub(crate) async fn membership(
+    room_id: &RoomId,
+    user_id: &UserId,
+) -> Result<Option<MembershipState>> {
+    let req = get_state::Request::new(room_id.to_owned());
+
+    let get_state::Response { state, .. } = commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await
+        .map_err(refused)?;
+
+    Ok(state
+        .iter()
+        .find(|event| event.kind == "m.room.member" && event.state_key == user_id.as_str())
+        .and_then(|event| {
+            event
+                .content
+                .get_field::<MembershipState>("membership")
+                .ok()
+                .flatten()
+        }))
+}
+
+/// The joined members of a room as shown to users, leaving out the
+/// administrator account that joins boards to stream them.
+pub(crate) async fn joined_members(room_id: &RoomId, joined_members: u64) -> u64 {
+    match admin_rooms().await {
+        Ok(rooms) if rooms.contains(room_id) => joined_members.saturating_sub(1),
+        Ok(_) => joined_members,
+        Err(e) => {
+            tracing::debug!(?e, "failed to list the rooms of the admin account");
+
+            joined_members
+        }
+    }
+}
+
+/// Drops the cached rooms of the administrator account, after it joined one.
+pub(crate) fn forget_admin_rooms() {
+    *ADMIN_ROOMS.lock().unwrap() = None;
+}
+
+async fn admin_rooms() -> Result<Arc<BTreeSet<OwnedRoomId>>> {
+    if let Some((rooms, fetched)) = &*ADMIN_ROOMS.lock().unwrap() {
+        if fetched.elapsed() < ADMIN_ROOMS_TTL {
+            return Ok(rooms.clone());
+        }
+    }
+
+    let req = joined_rooms::Request::new();
+
+    let joined_rooms::Response { joined_rooms, .. } = commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await?;
+
+    let rooms = Arc::new(joined_rooms.into_iter().collect::<BTreeSet<_>>());
+    *ADMIN_ROOMS.lock().unwrap() = Some((rooms.clone(), Instant::now()));
+
+    Ok(rooms)
+}
@@ -0,0 +1,33 @@
+use matrix::{
+    client::membership::invite::*,
+    ruma_common::{OwnedRoomId, OwnedUserId},
+};
+
+use crate::{
+    commune,
+    error::{Error, Result},
+};
+
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    user_id: OwnedUserId,
+    reason: Option<String>,
+) -> Result<()> {
+    // conversations stay between the two users that started them
+    if crate::direct::direct(access_token.as_ref(), &room_id)
+        .await
+        .is_ok()
+    {
+        return Err(Error::Forbidden);
+    }
+
+    let req = Request::new(room_id, user_id, reason);
+
+    commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await
+        .map_err(super::refused)?;
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use matrix::{
+    client::{membership::join::*, space::hierarchy},
+    ruma_common::{room::RoomType, OwnedRoomId},
+};
+use serde::Serialize;
+
+use crate::{
+    commune,
+    error::Result,
+    util::{auth, upgrade},
+};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Joined {
+    pub room_id: OwnedRoomId,
+
+    /// The default boards that were joined along with a space.
+    pub boards: Vec<OwnedRoomId>,
+}
+
+/// Joining a space also joins its default boards, the ones marked as
+/// suggested. Boards that cannot be joined are skipped.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    reason: Option<String>,
+) -> Result<Joined> {
+    let access_token = access_token.as_ref();
+    let user_id = auth::user_id(access_token).await?;
+
+    let mut req = Request::new(room_id.clone().into());
+    req.server_name = vec![commune().config.matrix.server_name.clone()];
+    req.reason = reason;
+
+    let Response { room_id, .. } =
+        match commune().send_matrix_request(req, Some(access_token)).await {
+            Ok(resp) => resp,
+            Err(e) => return Err(super::refused_entry(e, &room_id, &user_id).await),
+        };
+
+    let mut req = hierarchy::Request::new(room_id.clone());
+    req.max_depth = Some(1);
+    req.suggested_only = true;
+
+    let hierarchy::Response { rooms, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let mut boards = Vec::new();
+
+    match rooms.first() {
+        Some(space) if space.room_type == Some(RoomType::Space) => {}
+        _ => return Ok(Joined { room_id, boards }),
+    }
+
+    for board in rooms
+        .into_iter()
+        .skip(1)
+        .filter(|board| board.room_type.is_none())
+    {
+        let board_id = match upgrade::latest(&board.room_id).await {
+            Ok(board_id) => board_id,
+            Err(e) => {
+                tracing::debug!(?e, room_id = %board.room_id, "skipping default board");
+
+                continue;
+            }
+        };
+
+        let mut req = Request::new(board_id.into());
+        req.server_name = vec![commune().config.matrix.server_name.clone()];
+
+        match commune().send_matrix_request(req, Some(access_token)).await {
+            Ok(Response { room_id, .. }) => boards.push(room_id),
+            Err(e) => tracing::debug!(?e, room_id = %board.room_id, "skipping default board"),
+        }
+    }
+
+    Ok(Joined { room_id, boards })
+}
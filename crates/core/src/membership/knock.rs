@@ -0,0 +1,23 @@
+use matrix::{client::membership::knock::*, ruma_common::OwnedRoomId};
+
+use crate::{commune, error::Result, util::auth};
+
+/// Asks to join a room with the `knock` join rule, its moderators can then
+/// invite the user.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    reason: Option<String>,
+) -> Result<Response> {
+    let access_token = access_token.as_ref();
+    let user_id = auth::user_id(access_token).await?;
+
+    let mut req = Request::new(room_id.clone().into());
+    req.server_name = vec![commune().config.matrix.server_name.clone()];
+    req.reason = reason;
+
+    match commune().send_matrix_request(req, Some(access_token)).await {
+        Ok(resp) => Ok(resp),
+        Err(e) => Err(super::refused_entry(e, &room_id, &user_id).await),
+    }
+}
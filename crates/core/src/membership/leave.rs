@@ -0,0 +1,18 @@
+use matrix::{client::membership::leave::*, ruma_common::OwnedRoomId};
+
+use crate::{commune, error::Result};
+
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    reason: Option<String>,
+) -> Result<()> {
+    let req = Request::new(room_id, reason);
+
+    commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await
+        .map_err(super::refused)?;
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! Moderation actions taken on a board, or on a space in which case they
+//! cascade to each of its boards. Every action requires a reason, which ends
+//! up in the membership or power level event and in our logs.
+
+use matrix::{
+    client::membership,
+    ruma_common::{OwnedRoomId, OwnedUserId, RoomId},
+};
+
+use crate::{commune, error::Result, membership::refused, space, util::auth};
+
+pub mod mute;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Action {
+    Kick,
+    Ban,
+    Unban,
+}
+
+/// The room itself followed by its boards, if it is a space.
+pub(crate) async fn scope(access_token: &str, room_id: &RoomId) -> Result<Vec<OwnedRoomId>> {
+    let mut rooms = vec![room_id.to_owned()];
+    rooms.extend(space::children(access_token, room_id).await?);
+
+    Ok(rooms)
+}
+
+/// Fails when the action fails in the room itself, boards in which it fails,
+/// for instance because the user never joined them, are skipped.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    user_id: OwnedUserId,
+    action: Action,
+    reason: String,
+) -> Result<()> {
+    let access_token = access_token.as_ref();
+    let moderator = auth::user_id(access_token).await?;
+
+    for (i, room_id) in scope(access_token, &room_id).await?.into_iter().enumerate() {
+        let user_id = user_id.clone();
+        let reason = Some(reason.clone());
+
+        let resp = match action {
+            Action::Kick => {
+                let req = membership::kick::Request::new(room_id.clone(), user_id, reason);
+
+                commune()
+                    .send_matrix_request(req, Some(access_token))
+                    .await
+                    .map(drop)
+            }
+            Action::Ban => {
+                let req = membership::ban::Request::new(room_id.clone(), user_id, reason);
+
+                commune()
+                    .send_matrix_request(req, Some(access_token))
+                    .await
+                    .map(drop)
+            }
+            Action::Unban => {
+                let req = membership::unban::Request::new(room_id.clone(), user_id, reason);
+
+                commune()
+                    .send_matrix_request(req, Some(access_token))
+                    .await
+                    .map(drop)
+            }
+        };
+
+        match resp {
+            Ok(()) => {}
+            Err(e) if i == 0 => return Err(refused(e)),
+            Err(e) => tracing::debug!(?e, %room_id, "skipping board"),
+        }
+    }
+
+    tracing::info!(%moderator, %user_id, %room_id, ?action, %reason, "moderation action");
+
+    Ok(())
+}
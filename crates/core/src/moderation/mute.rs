@@ -0,0 +1,374 @@
+//! Muted users have their power level lowered below `events_default`. The
+//! level they had before is kept in a state event, so it can be restored.
+//!
+//! Expiries are lifted by background tasks acting as the moderator who
+//! recorded the mute, since their own session may have ended by then, so
+//! the homeserver still checks the change against their power. The tasks
+//! are scheduled again from the state of the boards when the server starts.
+//! A level is never restored above the one of whoever lifts the mute, as the
+//! record is room state its sender could have written anything into.
+
+use std::time::Duration;
+
+use matrix::{
+    admin::{room::get_state, user::login_as},
+    client::state::{get, send},
+    ruma_common::{
+        serde::Raw, MilliSecondsSinceUnixEpoch, OwnedRoomId, OwnedUserId, RoomId, UserId,
+    },
+    ruma_events::StateEventType,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    commune,
+    error::{Error, Result},
+    membership::refused,
+    space,
+    util::auth,
+};
+
+/// How long the token acting as the moderator stays valid.
+const ACT_AS_VALIDITY: Duration = Duration::from_secs(60);
+
+/// State event sent in the room, keyed by the ID of the muted user.
+pub const MUTED_EVENT_TYPE: &str = "sh.commune.muted";
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Muted {
+    previous: i64,
+
+    reason: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires: Option<MilliSecondsSinceUnixEpoch>,
+}
+
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    user_id: OwnedUserId,
+    reason: String,
+    duration: Option<Duration>,
+) -> Result<()> {
+    let access_token = access_token.as_ref().to_owned();
+    let moderator = auth::user_id(&access_token).await?;
+
+    let expires = duration
+        .and_then(|duration| {
+            let now = u64::from(MilliSecondsSinceUnixEpoch::now().get());
+            (now + duration.as_millis() as u64).try_into().ok()
+        })
+        .map(MilliSecondsSinceUnixEpoch);
+
+    let rooms = super::scope(&access_token, &room_id).await?;
+
+    for (i, room_id) in rooms.iter().enumerate() {
+        match mute(&access_token, room_id, &user_id, &reason, expires).await {
+            Ok(()) => {}
+            Err(e) if i == 0 => return Err(e),
+            Err(e) => tracing::debug!(?e, %room_id, "skipping board"),
+        }
+    }
+
+    tracing::info!(%moderator, %user_id, %room_id, ?duration, %reason, "muted user");
+
+    if let Some(expires) = expires {
+        for room_id in rooms {
+            schedule(room_id, user_id.clone(), expires);
+        }
+    }
+
+    Ok(())
+}
+
+/// Schedules the expiry of the mutes persisted in the boards of the spaces.
+pub async fn start() {
+    tokio::spawn(async {
+        if let Err(e) = reschedule().await {
+            tracing::warn!(?e, "failed to schedule expiring mutes");
+        }
+    });
+}
+
+async fn reschedule() -> Result<()> {
+    let admin_token = commune().config.matrix.admin_token.inner();
+
+    for room in space::boards().await? {
+        let req = get_state::Request::new(room.room_id.clone());
+
+        let state = match commune().send_matrix_request(req, Some(&admin_token)).await {
+            Ok(get_state::Response { state, .. }) => state,
+            Err(e) => {
+                tracing::debug!(?e, room_id = %room.room_id, "failed to read mutes");
+
+                continue;
+            }
+        };
+
+        for event in state.iter().filter(|event| event.kind == MUTED_EVENT_TYPE) {
+            // lifted mutes have their content emptied
+            let Ok(Muted {
+                expires: Some(expires),
+                ..
+            }) = event.content.deserialize_as::<Muted>()
+            else {
+                continue;
+            };
+
+            if let Ok(user_id) = OwnedUserId::try_from(event.state_key.as_str()) {
+                schedule(room.room_id.clone(), user_id, expires);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn schedule(room_id: OwnedRoomId, user_id: OwnedUserId, expires: MilliSecondsSinceUnixEpoch) {
+    let now = u64::from(MilliSecondsSinceUnixEpoch::now().get());
+    let delay = Duration::from_millis(u64::from(expires.get()).saturating_sub(now));
+
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+
+        match expire(&room_id, &user_id, expires).await {
+            Ok(()) => tracing::info!(%user_id, %room_id, "mute expired"),
+            Err(e) => tracing::warn!(?e, %room_id, %user_id, "failed to lift expired mute"),
+        }
+    });
+}
+
+/// Lifts an expired mute as the user who recorded it, provided they may
+/// still change power levels.
+async fn expire(
+    room_id: &RoomId,
+    user_id: &UserId,
+    expires: MilliSecondsSinceUnixEpoch,
+) -> Result<()> {
+    let admin_token = commune().config.matrix.admin_token.inner();
+
+    let req = get_state::Request::new(room_id.to_owned());
+
+    let get_state::Response { state, .. } = commune()
+        .send_matrix_request(req, Some(&admin_token))
+        .await?;
+
+    let Some(sender) = state
+        .into_iter()
+        .find(|event| event.kind == MUTED_EVENT_TYPE && event.state_key == user_id.as_str())
+        .map(|event| event.sender)
+    else {
+        return Ok(());
+    };
+
+    let power_levels = auth::admin_power_levels(room_id).await?;
+
+    if !power_levels.user_can_send_state(&sender, StateEventType::RoomPowerLevels) {
+        tracing::warn!(%sender, %user_id, %room_id, "mute recorded without power over levels");
+
+        return Err(Error::Forbidden);
+    }
+
+    let now = u64::from(MilliSecondsSinceUnixEpoch::now().get());
+
+    let mut req = login_as::Request::new(sender.clone());
+    req.valid_until_ms = (now + ACT_AS_VALIDITY.as_millis() as u64)
+        .try_into()
+        .ok()
+        .map(MilliSecondsSinceUnixEpoch);
+
+    let login_as::Response { access_token, .. } = commune()
+        .send_matrix_request(req, Some(&admin_token))
+        .await?;
+
+    unmute(&access_token, room_id, user_id, &sender, Some(expires)).await
+}
+
+/// Lifts a mute before it expires, or one without expiry.
+pub async fn lift(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    user_id: OwnedUserId,
+    reason: String,
+) -> Result<()> {
+    let access_token = access_token.as_ref();
+    let moderator = auth::user_id(access_token).await?;
+
+    for (i, room_id) in super::scope(access_token, &room_id)
+        .await?
+        .iter()
+        .enumerate()
+    {
+        match unmute(access_token, room_id, &user_id, &moderator, None).await {
+            Ok(()) => {}
+            Err(e) if i == 0 => return Err(e),
+            Err(e) => tracing::debug!(?e, %room_id, "skipping board"),
+        }
+    }
+
+    tracing::info!(%moderator, %user_id, %room_id, %reason, "unmuted user");
+
+    Ok(())
+}
+
+/// Power levels are edited as JSON, so fields unknown to us are preserved.
+async fn power_levels(access_token: &str, room_id: &RoomId) -> Result<Value> {
+    let req = get::Request::new(
+        room_id.to_owned(),
+        StateEventType::RoomPowerLevels,
+        String::new(),
+    );
+
+    let get::Response { content, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .map_err(refused)?;
+
+    Ok(content.deserialize_as()?)
+}
+
+async fn send_state(
+    access_token: &str,
+    room_id: &RoomId,
+    event_type: StateEventType,
+    state_key: &str,
+    content: &Value,
+) -> Result<()> {
+    let req = send::Request::new_raw(
+        room_id.to_owned(),
+        event_type,
+        state_key,
+        Raw::new(content)?.cast(),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .map_err(refused)?;
+
+    Ok(())
+}
+
+fn level(power_levels: &Value, user_id: &UserId) -> i64 {
+    power_levels["users"][user_id.as_str()]
+        .as_i64()
+        .or_else(|| power_levels["users_default"].as_i64())
+        .unwrap_or_default()
+}
+
+async fn mute(
+    access_token: &str,
+    room_id: &RoomId,
+    user_id: &UserId,
+    reason: &str,
+    expires: Option<MilliSecondsSinceUnixEpoch>,
+) -> Result<()> {
+    let mut power_levels = power_levels(access_token, room_id).await?;
+
+    let current = level(&power_levels, user_id);
+    let events_default = power_levels["events_default"].as_i64().unwrap_or_default();
+
+    // muting again only updates the reason and expiry
+    let previous = muted(access_token, room_id, user_id)
+        .await
+        .map_or(current, |muted| muted.previous);
+
+    // record the previous level first, so a failure leaves nothing to revert
+    let muted = Muted {
+        previous,
+        reason: reason.to_owned(),
+        expires,
+    };
+    send_state(
+        access_token,
+        room_id,
+        MUTED_EVENT_TYPE.into(),
+        user_id.as_str(),
+        &serde_json::to_value(muted)?,
+    )
+    .await?;
+
+    if current >= events_default {
+        power_levels["users"][user_id.as_str()] = json!(events_default - 1);
+
+        send_state(
+            access_token,
+            room_id,
+            StateEventType::RoomPowerLevels,
+            "",
+            &power_levels,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Lifted mutes have their content emptied.
+async fn muted(access_token: &str, room_id: &RoomId, user_id: &UserId) -> Option<Muted> {
+    let req = get::Request::new(
+        room_id.to_owned(),
+        MUTED_EVENT_TYPE.into(),
+        user_id.to_string(),
+    );
+
+    let get::Response { content, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .ok()?;
+
+    content.deserialize_as::<Muted>().ok()
+}
+
+/// Expired mutes are only lifted if they were not renewed in the meantime.
+/// `moderator` is the user `access_token` belongs to, whose level caps the
+/// one restored.
+async fn unmute(
+    access_token: &str,
+    room_id: &RoomId,
+    user_id: &UserId,
+    moderator: &UserId,
+    expired: Option<MilliSecondsSinceUnixEpoch>,
+) -> Result<()> {
+    let Some(Muted {
+        previous, expires, ..
+    }) = muted(access_token, room_id, user_id).await
+    else {
+        return Ok(());
+    };
+
+    if expired.is_some() && expired != expires {
+        return Ok(());
+    }
+
+    let mut power_levels = power_levels(access_token, room_id).await?;
+    let users_default = power_levels["users_default"].as_i64().unwrap_or_default();
+    let previous = previous.min(level(&power_levels, moderator));
+
+    match power_levels["users"].as_object_mut() {
+        Some(users) if previous == users_default => {
+            let _ = users.remove(user_id.as_str());
+        }
+        _ => power_levels["users"][user_id.as_str()] = json!(previous),
+    }
+
+    send_state(
+        access_token,
+        room_id,
+        StateEventType::RoomPowerLevels,
+        "",
+        &power_levels,
+    )
+    .await?;
+
+    send_state(
+        access_token,
+        room_id,
+        MUTED_EVENT_TYPE.into(),
+        user_id.as_str(),
+        &json!({}),
+    )
+    .await
+}
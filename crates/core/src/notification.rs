@@ -0,0 +1,53 @@
+//! Replies to the posts and comments of the user and mentions of them,
+//! picked from what the homeserver notified them of according to their push
+//! rules. Each comes with the post and board it belongs to.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#listing-notifications
+
+use matrix::ruma_common::{MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId};
+use serde::Serialize;
+
+use crate::post::Author;
+
+pub mod list;
+pub mod read;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// A comment directly under a post of the user.
+    PostReply,
+
+    /// A reply to a comment of the user.
+    CommentReply,
+
+    /// A post or comment mentioning the user.
+    Mention,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Notification {
+    pub kind: Kind,
+
+    pub board_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub board: Option<String>,
+
+    /// The post itself, or the post of the thread for comments.
+    pub post_id: OwnedEventId,
+
+    pub event_id: OwnedEventId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    pub author: Author,
+
+    pub snippet: String,
+
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    /// Whether the read receipt of the user is past the event.
+    pub read: bool,
+}
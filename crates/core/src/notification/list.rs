@@ -0,0 +1,355 @@
+use std::collections::BTreeMap;
+
+use matrix::{
+    admin::room::get_room,
+    client::{event::get, notifications},
+    ruma_common::{serde::Raw, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId, UserId},
+    ruma_events::{
+        relation::{InReplyTo, RelationType},
+        AnyTimelineEvent, TimelineEventType,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use super::{Kind, Notification};
+use crate::{
+    commune,
+    error::Result,
+    post::{self, Author, PostEvent},
+    search::snippet,
+    util::auth,
+};
+
+pub const DEFAULT_LIMIT: u64 = 20;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Notifications {
+    pub notifications: Vec<Notification>,
+
+    /// Passed as `from` for the next page, absent on the last one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_batch: Option<String>,
+}
+
+/// Notifications that are neither replies to the user nor mentions of them
+/// are left out, so pages can be shorter than `limit`.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    from: Option<String>,
+    limit: Option<u64>,
+) -> Result<Notifications> {
+    let access_token = access_token.as_ref();
+    let user_id = auth::user_id(access_token).await?;
+
+    let mut req = notifications::Request::new();
+    req.from = from;
+    req.limit = Some(limit.unwrap_or(DEFAULT_LIMIT));
+
+    let notifications::Response {
+        next_token,
+        notifications,
+        ..
+    } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let mut context = Context {
+        access_token,
+        user_id: &user_id,
+        events: BTreeMap::new(),
+        boards: BTreeMap::new(),
+        authors: BTreeMap::new(),
+    };
+
+    let mut list = Vec::with_capacity(notifications.len());
+
+    for notification in notifications {
+        if let Some(notification) = context.notification(notification).await {
+            list.push(notification);
+        }
+    }
+
+    Ok(Notifications {
+        notifications: list,
+        next_batch: next_token,
+    })
+}
+
+#[derive(Deserialize)]
+struct Message {
+    event_id: OwnedEventId,
+
+    sender: OwnedUserId,
+
+    content: Content,
+}
+
+#[derive(Default, Deserialize)]
+struct Content {
+    #[serde(default)]
+    body: String,
+
+    #[serde(rename = "m.relates_to")]
+    relates_to: Option<Relation>,
+
+    #[serde(rename = "m.mentions", default)]
+    mentions: Mentions,
+}
+
+#[derive(Deserialize)]
+struct Relation {
+    rel_type: Option<RelationType>,
+
+    event_id: Option<OwnedEventId>,
+
+    #[serde(rename = "m.in_reply_to")]
+    in_reply_to: Option<InReplyTo>,
+
+    #[serde(default)]
+    is_falling_back: bool,
+}
+
+#[derive(Default, Deserialize)]
+struct Mentions {
+    #[serde(default)]
+    user_ids: Vec<OwnedUserId>,
+}
+
+impl Content {
+    /// Clients without intentional mentions only put the user ID in the body.
+    fn mentions(&self, user_id: &UserId) -> bool {
+        self.mentions
+            .user_ids
+            .iter()
+            .any(|mentioned| mentioned == user_id)
+            || mentioned_in(&self.body, user_id)
+    }
+}
+
+/// Whether the body names the user ID on its own, rather than as the start of
+/// a longer one such as `@ferris:example.community` for `@ferris:example.com`.
+fn mentioned_in(body: &str, user_id: &UserId) -> bool {
+    // characters of localparts and server names, punctuation ending a sentence
+    // is only part of the ID when more of it follows
+    let continues = |c: char| c.is_ascii_alphanumeric() || "_=-/+".contains(c);
+
+    body.match_indices(user_id.as_str()).any(|(start, id)| {
+        let before = body[..start].chars().next_back();
+        let mut after = body[start + id.len()..].chars();
+
+        let starts = before.map_or(true, |c| !continues(c) && c != '.' && c != ':');
+        let ends = match after.next() {
+            Some('.' | ':') => !after.next().is_some_and(continues),
+            Some(c) => !continues(c),
+            None => true,
+        };
+
+        starts && ends
+    })
+}
+
+/// An event a comment replies to.
+#[derive(Clone)]
+struct Target {
+    sender: OwnedUserId,
+
+    /// Only set for posts.
+    title: Option<String>,
+}
+
+/// Caches what several notifications tend to share.
+struct Context<'a> {
+    access_token: &'a str,
+
+    user_id: &'a UserId,
+
+    events: BTreeMap<OwnedEventId, Option<Target>>,
+
+    boards: BTreeMap<OwnedRoomId, Option<String>>,
+
+    authors: BTreeMap<OwnedUserId, Author>,
+}
+
+impl Context<'_> {
+    async fn notification(
+        &mut self,
+        notification: notifications::Notification,
+    ) -> Option<Notification> {
+        let notifications::Notification {
+            event,
+            read,
+            room_id,
+            ts,
+            ..
+        } = notification;
+
+        if event.get_field::<TimelineEventType>("type").ok()?? != TimelineEventType::RoomMessage {
+            return None;
+        }
+
+        let Message {
+            event_id,
+            sender,
+            content,
+        } = event.deserialize_as().ok()?;
+
+        if sender == self.user_id {
+            return None;
+        }
+
+        let mentioned = content.mentions(self.user_id);
+
+        let (kind, post_id, title) = match PostEvent::from_raw(&event) {
+            Some(post) => (
+                mentioned.then_some(Kind::Mention)?,
+                post.event_id,
+                Some(post.content.post.title),
+            ),
+            None => {
+                let Relation {
+                    rel_type: Some(RelationType::Thread),
+                    event_id: Some(post_id),
+                    in_reply_to,
+                    is_falling_back,
+                } = content.relates_to.as_ref()?
+                else {
+                    return None;
+                };
+
+                // fallbacks point at the latest comment rather than a parent
+                let parent = in_reply_to
+                    .as_ref()
+                    .filter(|_| !is_falling_back)
+                    .map_or(post_id, |in_reply_to| &in_reply_to.event_id);
+
+                let post = self.target(&room_id, post_id).await;
+                let replied_to = match parent == post_id {
+                    true => post.clone(),
+                    false => self.target(&room_id, parent).await,
+                }
+                .is_some_and(|target| target.sender == self.user_id);
+
+                let kind = match (replied_to, parent == post_id) {
+                    (true, true) => Kind::PostReply,
+                    (true, false) => Kind::CommentReply,
+                    (false, _) => mentioned.then_some(Kind::Mention)?,
+                };
+
+                (kind, post_id.clone(), post.and_then(|post| post.title))
+            }
+        };
+
+        let highlights = match kind {
+            Kind::Mention => vec![self.user_id.to_string()],
+            _ => Vec::new(),
+        };
+
+        Some(Notification {
+            kind,
+            board: self.board(&room_id).await,
+            board_id: room_id,
+            post_id,
+            event_id,
+            title,
+            author: self.author(sender).await,
+            snippet: snippet(&content.body, &highlights),
+            origin_server_ts: ts,
+            read,
+        })
+    }
+
+    async fn target(&mut self, room_id: &RoomId, event_id: &OwnedEventId) -> Option<Target> {
+        if let Some(target) = self.events.get(event_id) {
+            return target.clone();
+        }
+
+        let req = get::Request::new(room_id.to_owned(), event_id.clone());
+
+        let target = match commune()
+            .send_matrix_request(req, Some(self.access_token))
+            .await
+        {
+            Ok(get::Response { event, .. }) => target(&event),
+            Err(e) => {
+                tracing::debug!(?e, %event_id, "failed to look up replied event");
+
+                None
+            }
+        };
+
+        let _ = self.events.insert(event_id.clone(), target.clone());
+
+        target
+    }
+
+    async fn board(&mut self, room_id: &RoomId) -> Option<String> {
+        if let Some(name) = self.boards.get(room_id) {
+            return name.clone();
+        }
+
+        let req = get_room::Request::new(room_id.to_owned());
+
+        let name = match commune()
+            .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+            .await
+        {
+            Ok(get_room::Response { room, .. }) => room.name,
+            Err(e) => {
+                tracing::debug!(?e, %room_id, "failed to look up board");
+
+                None
+            }
+        };
+
+        let _ = self.boards.insert(room_id.to_owned(), name.clone());
+
+        name
+    }
+
+    async fn author(&mut self, user_id: OwnedUserId) -> Author {
+        match self.authors.get(&user_id) {
+            Some(author) => author.clone(),
+            None => {
+                let author = post::author(user_id.clone()).await;
+                let _ = self.authors.insert(user_id, author.clone());
+
+                author
+            }
+        }
+    }
+}
+
+fn target(event: &Raw<AnyTimelineEvent>) -> Option<Target> {
+    Some(Target {
+        sender: event.get_field::<OwnedUserId>("sender").ok()??,
+        title: PostEvent::from_raw(event).map(|post| post.content.post.title),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::user_id;
+
+    use super::*;
+
+    #[test]
+    fn finds_mentions() {
+        let user_id = user_id!("@ferris:example.com");
+
+        let content = |body: &str, user_ids: Vec<OwnedUserId>| Content {
+            body: body.to_owned(),
+            mentions: Mentions { user_ids },
+            ..Default::default()
+        };
+
+        assert!(content("hey @ferris:example.com", Vec::new()).mentions(user_id));
+        assert!(content("hey ferris", vec![user_id.to_owned()]).mentions(user_id));
+        assert!(!content("hey @ferris:example.org", Vec::new()).mentions(user_id));
+        assert!(content("thanks @ferris:example.com.", Vec::new()).mentions(user_id));
+        assert!(content("(@ferris:example.com, see above)", Vec::new()).mentions(user_id));
+        assert!(!content("hey @ferris:example.community", Vec::new()).mentions(user_id));
+        assert!(!content("hey @ferris:example.com.au", Vec::new()).mentions(user_id));
+        assert!(!content("hey @ferris:example.com:8448", Vec::new()).mentions(user_id));
+        assert!(!content("hey @old@ferris:example.com", Vec::new()).mentions(user_id));
+    }
+}
@@ -0,0 +1,13 @@
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId};
+
+use crate::error::Result;
+
+/// Moves the read receipt of the board to the event, which marks it and
+/// everything before it in the board as read.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    board_id: OwnedRoomId,
+    event_id: OwnedEventId,
+) -> Result<()> {
+    crate::read::mark::service(access_token, board_id, None, Some(event_id), false).await
+}
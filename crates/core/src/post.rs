@@ -0,0 +1,358 @@
+//! Posts are top-level `m.room.message` events in a board, extended with a
+//! Commune-namespaced object that holds the title and attachments.
+
+use std::collections::BTreeMap;
+
+use matrix::{
+    client::profile,
+    ruma_common::{
+        serde::Raw, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedMxcUri, OwnedRoomId,
+        OwnedUserId, UserId,
+    },
+    ruma_events::{AnyStateEvent, AnyTimelineEvent, StateEventType, TimelineEventType},
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use self::{format::Formatted, vote::Votes};
+use crate::commune;
+
+pub mod comment;
+pub mod create;
+pub(crate) mod cursor;
+pub mod delete;
+pub mod edit;
+pub mod format;
+pub mod get;
+pub(crate) mod index;
+pub mod list;
+pub mod revisions;
+pub mod vote;
+
+pub const POST_FIELD: &str = "sh.commune.post";
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PostMeta {
+    pub title: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<Url>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<OwnedMxcUri>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Author {
+    pub user_id: OwnedUserId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<OwnedMxcUri>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Post {
+    pub event_id: OwnedEventId,
+
+    pub room_id: OwnedRoomId,
+
+    pub author: Author,
+
+    pub title: String,
+
+    pub body: String,
+
+    /// Sanitized HTML rendering of the body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_body: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<Url>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<OwnedMxcUri>,
+
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    /// Taken from the thread summary the homeserver bundles with the event.
+    pub comment_count: u64,
+
+    pub votes: Votes,
+
+    /// Whether the title or body shown is the one of a later revision.
+    pub edited: bool,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PostEvent {
+    pub(crate) event_id: OwnedEventId,
+
+    pub(crate) sender: OwnedUserId,
+
+    pub(crate) origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    pub(crate) content: PostContent,
+
+    #[serde(default)]
+    pub(crate) unsigned: Unsigned,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct Unsigned {
+    #[serde(rename = "m.relations", default)]
+    pub(crate) relations: Relations,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct Relations {
+    #[serde(rename = "m.thread")]
+    pub(crate) thread: Option<ThreadSummary>,
+
+    #[serde(rename = "m.replace")]
+    pub(crate) replace: Option<Replacement>,
+}
+
+/// The latest edit, bundled by the homeserver. Older homeservers only
+/// bundle the event ID, the original content is shown in that case.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct Replacement {
+    pub(crate) sender: OwnedUserId,
+
+    pub(crate) content: Option<ReplacementContent>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ReplacementContent {
+    #[serde(rename = "m.new_content")]
+    pub(crate) new_content: NewContent,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct NewContent {
+    #[serde(default)]
+    pub(crate) body: String,
+
+    #[serde(flatten)]
+    pub(crate) formatted: Formatted,
+
+    #[serde(rename = "sh.commune.post")]
+    pub(crate) post: Option<PostMeta>,
+}
+
+impl Unsigned {
+    /// Edits are only valid when sent by the author of the original event.
+    pub(crate) fn latest_edit(&self, sender: &UserId) -> Option<&Replacement> {
+        self.relations
+            .replace
+            .as_ref()
+            .filter(|replacement| replacement.sender == sender)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ThreadSummary {
+    #[serde(default)]
+    pub(crate) count: u64,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PostContent {
+    pub(crate) body: String,
+
+    #[serde(flatten)]
+    pub(crate) formatted: Formatted,
+
+    #[serde(rename = "sh.commune.post")]
+    pub(crate) post: PostMeta,
+
+    #[serde(rename = "m.relates_to")]
+    pub(crate) relates_to: Option<Raw<serde_json::Value>>,
+}
+
+impl PostEvent {
+    /// Only top-level messages carrying the Commune namespace are posts, the
+    /// ones with a relation are comments, edits or reactions.
+    pub(crate) fn from_raw(event: &Raw<AnyTimelineEvent>) -> Option<Self> {
+        let kind = event.get_field::<TimelineEventType>("type").ok()??;
+
+        if kind != TimelineEventType::RoomMessage {
+            return None;
+        }
+
+        event
+            .deserialize_as::<Self>()
+            .ok()
+            .filter(|event| event.content.relates_to.is_none())
+    }
+
+    pub(crate) fn into_post(self, room_id: OwnedRoomId, author: Author) -> Post {
+        let PostContent {
+            mut body,
+            mut formatted,
+            mut post,
+            ..
+        } = self.content;
+        let edited = match self.unsigned.latest_edit(&self.sender) {
+            Some(replacement) => {
+                if let Some(ReplacementContent { new_content }) = &replacement.content {
+                    body = new_content.body.clone();
+                    formatted = new_content.formatted.clone();
+                    post = new_content.post.clone().unwrap_or(post);
+                }
+
+                true
+            }
+            None => false,
+        };
+
+        Post {
+            event_id: self.event_id,
+            room_id,
+            author,
+            title: post.title,
+            body,
+            formatted_body: formatted.sanitized(),
+            link: post.link,
+            media: post.media,
+            origin_server_ts: self.origin_server_ts,
+            comment_count: self
+                .unsigned
+                .relations
+                .thread
+                .map_or(0, |thread| thread.count),
+            votes: Votes::default(),
+            edited,
+        }
+    }
+}
+
+/// Collects the room-specific profiles from lazy-loaded member events.
+pub(crate) fn authors_from_state(state: &[Raw<AnyStateEvent>]) -> BTreeMap<OwnedUserId, Author> {
+    #[derive(Deserialize)]
+    struct Member {
+        state_key: OwnedUserId,
+
+        content: MemberContent,
+    }
+
+    #[derive(Deserialize)]
+    struct MemberContent {
+        #[serde(rename = "displayname")]
+        display_name: Option<String>,
+
+        avatar_url: Option<OwnedMxcUri>,
+    }
+
+    state
+        .iter()
+        .filter(|event| {
+            event.get_field::<StateEventType>("type").ok().flatten()
+                == Some(StateEventType::RoomMember)
+        })
+        .filter_map(|event| event.deserialize_as::<Member>().ok())
+        .map(|Member { state_key, content }| {
+            (
+                state_key.clone(),
+                Author {
+                    user_id: state_key,
+                    display_name: content.display_name,
+                    avatar_url: content.avatar_url,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Falls back to the global profile for users we have no member event for,
+/// users without a public profile are returned as-is.
+pub(crate) async fn author(user_id: OwnedUserId) -> Author {
+    let req = profile::root::Request::new(user_id.clone());
+
+    match commune().send_matrix_request(req, None).await {
+        Ok(profile::root::Response {
+            display_name,
+            avatar_url,
+            ..
+        }) => Author {
+            user_id,
+            display_name,
+            avatar_url,
+        },
+        Err(e) => {
+            tracing::debug!(?e, %user_id, "failed to resolve profile");
+
+            Author {
+                user_id,
+                display_name: None,
+                avatar_url: None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::{owned_room_id, owned_user_id};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn shows_latest_edit_of_the_author() {
+        let event = |sender: &str| -> Raw<AnyTimelineEvent> {
+            Raw::new(&json!({
+                "type": "m.room.message",
+                "event_id": "$post",
+                "sender": "@alice:example.com",
+                "origin_server_ts": 1,
+                "content": {
+                    "msgtype": "m.text",
+                    "body": "before",
+                    "sh.commune.post": { "title": "before" },
+                },
+                "unsigned": {
+                    "m.relations": {
+                        "m.replace": {
+                            "event_id": "$edit",
+                            "sender": sender,
+                            "origin_server_ts": 2,
+                            "content": {
+                                "m.new_content": {
+                                    "msgtype": "m.text",
+                                    "body": "after",
+                                    "sh.commune.post": { "title": "after" },
+                                },
+                            },
+                        },
+                    },
+                },
+            }))
+            .unwrap()
+            .cast()
+        };
+
+        let author = Author {
+            user_id: owned_user_id!("@alice:example.com"),
+            display_name: None,
+            avatar_url: None,
+        };
+
+        let post = PostEvent::from_raw(&event("@alice:example.com"))
+            .unwrap()
+            .into_post(owned_room_id!("!board:example.com"), author.clone());
+        assert!(post.edited);
+        assert_eq!(
+            (post.title.as_str(), post.body.as_str()),
+            ("after", "after")
+        );
+
+        let post = PostEvent::from_raw(&event("@mallory:example.com"))
+            .unwrap()
+            .into_post(owned_room_id!("!board:example.com"), author);
+        assert!(!post.edited);
+        assert_eq!(post.body, "before");
+    }
+}
@@ -0,0 +1,354 @@
+//! Comments are `m.thread` relations to a post, the `m.in_reply_to` field is
+//! used to nest them into a tree.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#threading
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use matrix::{
+    ruma_common::{serde::Raw, EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedUserId},
+    ruma_events::{
+        relation::{InReplyTo, RelationType},
+        AnyMessageLikeEvent,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use super::{format::Formatted, Author, ReplacementContent, Unsigned};
+
+pub mod create;
+pub mod list;
+
+/// Shown in place of removed comments, so their replies keep their place.
+pub const REMOVED: &str = "[removed]";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Comment {
+    pub event_id: OwnedEventId,
+
+    pub author: Author,
+
+    pub body: String,
+
+    /// Sanitized HTML rendering of the body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_body: Option<String>,
+
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    /// The amount of direct replies, including the ones that were not loaded.
+    pub reply_count: usize,
+
+    pub replies: Vec<Comment>,
+
+    pub edited: bool,
+
+    pub removed: bool,
+
+    /// Cursor to load the remaining replies with, passed as `from` along with
+    /// this comment as `parent`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub more: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct CommentEvent {
+    pub(crate) event_id: OwnedEventId,
+
+    pub(crate) sender: OwnedUserId,
+
+    pub(crate) origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    pub(crate) content: CommentContent,
+
+    #[serde(default)]
+    pub(crate) unsigned: Unsigned,
+
+    #[serde(skip)]
+    pub(crate) removed: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct CommentContent {
+    #[serde(default)]
+    pub(crate) body: String,
+
+    #[serde(flatten)]
+    pub(crate) formatted: Formatted,
+
+    #[serde(rename = "m.relates_to")]
+    pub(crate) relates_to: ThreadRelation,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ThreadRelation {
+    pub(crate) rel_type: RelationType,
+
+    #[serde(rename = "m.in_reply_to")]
+    pub(crate) in_reply_to: Option<InReplyTo>,
+
+    #[serde(default)]
+    pub(crate) is_falling_back: bool,
+}
+
+impl CommentEvent {
+    pub(crate) fn from_raw(event: &Raw<AnyMessageLikeEvent>) -> Option<Self> {
+        event
+            .deserialize_as::<Self>()
+            .ok()
+            .filter(|event| event.content.relates_to.rel_type == RelationType::Thread)
+    }
+
+    /// Redactions strip the thread relation, so the homeserver no longer
+    /// returns removed comments along with the thread. The placeholder is
+    /// attached to the post since its original parent is unknown.
+    pub(crate) fn removed(
+        event_id: OwnedEventId,
+        sender: OwnedUserId,
+        origin_server_ts: MilliSecondsSinceUnixEpoch,
+    ) -> Self {
+        Self {
+            event_id,
+            sender,
+            origin_server_ts,
+            content: CommentContent {
+                body: REMOVED.to_owned(),
+                formatted: Formatted::default(),
+                relates_to: ThreadRelation {
+                    rel_type: RelationType::Thread,
+                    in_reply_to: None,
+                    is_falling_back: false,
+                },
+            },
+            unsigned: Unsigned::default(),
+            removed: true,
+        }
+    }
+
+    /// The body of the latest edit, if any.
+    fn body(&self) -> (&str, &Formatted, bool) {
+        let CommentContent {
+            body, formatted, ..
+        } = &self.content;
+
+        match self.unsigned.latest_edit(&self.sender) {
+            Some(replacement) => match &replacement.content {
+                Some(ReplacementContent { new_content }) => {
+                    (&new_content.body, &new_content.formatted, true)
+                }
+                None => (body, formatted, true),
+            },
+            None => (body, formatted, false),
+        }
+    }
+
+    /// Replies with `is_falling_back` set were sent by clients unaware of
+    /// nesting, those are treated as replies to the post itself.
+    fn parent(&self) -> Option<&EventId> {
+        let ThreadRelation {
+            in_reply_to,
+            is_falling_back,
+            ..
+        } = &self.content.relates_to;
+
+        in_reply_to
+            .as_ref()
+            .filter(|_| !is_falling_back)
+            .map(|in_reply_to| in_reply_to.event_id.as_ref())
+    }
+}
+
+/// The parents replied to that are neither the post nor a known comment,
+/// these are either removed or outside of the thread.
+pub(crate) fn missing_parents(
+    post_id: &EventId,
+    events: &[CommentEvent],
+) -> BTreeSet<OwnedEventId> {
+    events
+        .iter()
+        .filter_map(CommentEvent::parent)
+        .filter(|parent| *parent != post_id && !events.iter().any(|e| e.event_id == *parent))
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Groups comments by their parent, each group sorted from oldest to newest.
+/// Comments replying to an event outside of the thread are attached to the
+/// post.
+pub(crate) fn group(
+    post_id: &EventId,
+    events: Vec<CommentEvent>,
+) -> BTreeMap<OwnedEventId, Vec<CommentEvent>> {
+    let known: Vec<_> = events.iter().map(|event| event.event_id.clone()).collect();
+    let mut groups: BTreeMap<_, Vec<_>> = BTreeMap::new();
+
+    for event in events {
+        let parent = event
+            .parent()
+            .filter(|parent| known.iter().any(|id| id == parent))
+            .unwrap_or(post_id)
+            .to_owned();
+
+        groups.entry(parent).or_default().push(event);
+    }
+
+    for group in groups.values_mut() {
+        group.sort_by_key(|event| event.origin_server_ts);
+    }
+
+    groups
+}
+
+pub(crate) struct Limits {
+    pub(crate) depth: usize,
+
+    pub(crate) per_level: usize,
+}
+
+/// Renders the replies to `parent` starting at `from`, descending up to
+/// `depth` levels. Returns the cursor for the remaining replies, if any.
+pub(crate) fn render(
+    groups: &BTreeMap<OwnedEventId, Vec<CommentEvent>>,
+    parent: &EventId,
+    from: usize,
+    limits: &Limits,
+) -> (Vec<Comment>, Option<String>) {
+    render_level(groups, parent, from, limits, 1)
+}
+
+fn render_level(
+    groups: &BTreeMap<OwnedEventId, Vec<CommentEvent>>,
+    parent: &EventId,
+    from: usize,
+    limits: &Limits,
+    depth: usize,
+) -> (Vec<Comment>, Option<String>) {
+    let Some(children) = groups.get(parent) else {
+        return (Vec::new(), None);
+    };
+
+    let comments = children
+        .iter()
+        .skip(from)
+        .take(limits.per_level)
+        .map(|event| {
+            let reply_count = groups.get(&event.event_id).map_or(0, Vec::len);
+
+            let (replies, more) = match depth < limits.depth {
+                true => render_level(groups, &event.event_id, 0, limits, depth + 1),
+                false => (Vec::new(), (reply_count > 0).then(|| 0.to_string())),
+            };
+
+            let (body, formatted, edited) = event.body();
+
+            Comment {
+                event_id: event.event_id.clone(),
+                author: Author {
+                    user_id: event.sender.clone(),
+                    display_name: None,
+                    avatar_url: None,
+                },
+                body: body.to_owned(),
+                formatted_body: formatted.sanitized(),
+                origin_server_ts: event.origin_server_ts,
+                reply_count,
+                replies,
+                more,
+                edited,
+                removed: event.removed,
+            }
+        })
+        .collect();
+
+    let next = from.saturating_add(limits.per_level);
+    let more = (next < children.len()).then(|| next.to_string());
+
+    (comments, more)
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::owned_event_id;
+    use serde_json::json;
+
+    use super::*;
+
+    fn comment(event_id: &str, ts: u64, in_reply_to: &str) -> CommentEvent {
+        serde_json::from_value(json!({
+            "event_id": event_id,
+            "sender": "@alice:example.com",
+            "origin_server_ts": ts,
+            "content": {
+                "body": event_id,
+                "m.relates_to": {
+                    "rel_type": "m.thread",
+                    "event_id": "$post",
+                    "m.in_reply_to": { "event_id": in_reply_to },
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn nests_replies() {
+        let post_id = owned_event_id!("$post");
+        let groups = group(
+            &post_id,
+            vec![
+                comment("$b", 2, "$post"),
+                comment("$a", 1, "$post"),
+                comment("$a1", 3, "$a"),
+                comment("$a1x", 4, "$a1"),
+                comment("$orphan", 5, "$unknown"),
+            ],
+        );
+
+        let limits = Limits {
+            depth: 2,
+            per_level: 2,
+        };
+        let (comments, more) = render(&groups, &post_id, 0, &limits);
+
+        let ids: Vec<_> = comments.iter().map(|c| c.event_id.as_str()).collect();
+        assert_eq!(ids, ["$a", "$b"]);
+        assert_eq!(more.as_deref(), Some("2"));
+
+        let a1 = &comments[0].replies[0];
+        assert_eq!(a1.event_id, "$a1");
+        assert_eq!(a1.reply_count, 1);
+        assert!(a1.replies.is_empty());
+        assert_eq!(a1.more.as_deref(), Some("0"));
+
+        let (comments, more) = render(&groups, &post_id, 2, &limits);
+        assert_eq!(comments[0].event_id, "$orphan");
+        assert!(more.is_none());
+    }
+
+    #[test]
+    fn keeps_replies_to_removed_comments() {
+        let post_id = owned_event_id!("$post");
+        let mut events = vec![comment("$reply", 2, "$gone")];
+
+        let missing = missing_parents(&post_id, &events);
+        assert_eq!(missing.len(), 1);
+
+        for event_id in missing {
+            events.push(CommentEvent::removed(
+                event_id,
+                "@bob:example.com".try_into().unwrap(),
+                MilliSecondsSinceUnixEpoch(1u32.into()),
+            ));
+        }
+
+        let limits = Limits {
+            depth: 5,
+            per_level: 10,
+        };
+        let (comments, _) = render(&group(&post_id, events), &post_id, 0, &limits);
+
+        assert!(comments[0].removed);
+        assert_eq!(comments[0].body, REMOVED);
+        assert_eq!(comments[0].replies[0].event_id, "$reply");
+    }
+}
@@ -0,0 +1,36 @@
+use matrix::{
+    client::event::send::*,
+    ruma_common::{OwnedEventId, OwnedRoomId, OwnedTransactionId, TransactionId},
+    ruma_events::{
+        relation::Thread,
+        room::message::{Relation, RoomMessageEventContent},
+    },
+};
+
+use crate::{commune, error::Result, post::format};
+
+/// Comments without a `parent` are replies to the post itself.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    board_id: OwnedRoomId,
+    post_id: OwnedEventId,
+    parent: Option<OwnedEventId>,
+    txn_id: Option<OwnedTransactionId>,
+    body: impl Into<String>,
+) -> Result<Response> {
+    let mut content = RoomMessageEventContent::new(format::markdown(body.into()));
+
+    let in_reply_to = parent.unwrap_or_else(|| post_id.clone());
+    content.relates_to = Some(Relation::Thread(Thread::reply(post_id, in_reply_to)));
+
+    let req = Request::new(
+        board_id,
+        txn_id.unwrap_or_else(TransactionId::new),
+        &content,
+    )?;
+
+    commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await
+        .map_err(Into::into)
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use matrix::{
+    client::{event::get, relations::*},
+    ruma_common::{
+        serde::Raw, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId,
+    },
+    ruma_events::relation::RelationType,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{Comment, CommentEvent, Limits};
+use crate::{
+    commune,
+    error::{Error, Result},
+    post::Author,
+    util::auth,
+};
+
+/// Threads are fetched as a whole to build the tree, this bounds the amount
+/// of requests made for very large threads.
+const MAX_PAGES: usize = 10;
+
+/// Bounds the amount of removed comments looked up.
+const MAX_MISSING: usize = 50;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Thread {
+    pub comment_count: usize,
+
+    pub comments: Vec<Comment>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub more: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn service(
+    access_token: Option<&str>,
+    board_id: OwnedRoomId,
+    post_id: OwnedEventId,
+    parent: Option<OwnedEventId>,
+    from: Option<String>,
+    depth: usize,
+    limit: usize,
+) -> Result<Thread> {
+    let token = auth::read_access_token(access_token, &board_id).await?;
+    let from = from
+        .map(|from| from.parse::<usize>())
+        .transpose()
+        .map_err(|_| Error::InvalidCursor)?
+        .unwrap_or_default();
+
+    let mut events = Vec::new();
+    let mut next_batch = None;
+
+    for _ in 0..MAX_PAGES {
+        let mut req = Request::new(board_id.clone(), post_id.clone(), RelationType::Thread);
+        req.from = next_batch;
+        req.limit = Some(100);
+
+        let resp = commune().send_matrix_request(req, Some(&token)).await?;
+
+        events.extend(resp.chunk.iter().filter_map(CommentEvent::from_raw));
+        next_batch = resp.next_batch;
+
+        if next_batch.is_none() {
+            break;
+        }
+    }
+
+    for event_id in super::missing_parents(&post_id, &events)
+        .into_iter()
+        .take(MAX_MISSING)
+    {
+        if let Some(placeholder) = removed(&token, &board_id, event_id).await {
+            events.push(placeholder);
+        }
+    }
+
+    let comment_count = events.len();
+    let groups = super::group(&post_id, events);
+
+    let limits = Limits {
+        depth,
+        per_level: limit,
+    };
+    let (mut comments, more) = super::render(
+        &groups,
+        parent.as_deref().unwrap_or(&post_id),
+        from,
+        &limits,
+    );
+
+    resolve_authors(&mut comments, &mut BTreeMap::new()).await;
+
+    Ok(Thread {
+        comment_count,
+        comments,
+        more,
+    })
+}
+
+/// Looks up a parent missing from the thread, returning a placeholder when
+/// it was redacted.
+async fn removed(token: &str, board_id: &RoomId, event_id: OwnedEventId) -> Option<CommentEvent> {
+    #[derive(Deserialize)]
+    struct Redacted {
+        sender: OwnedUserId,
+
+        origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+        unsigned: RedactedUnsigned,
+    }
+
+    #[derive(Deserialize)]
+    struct RedactedUnsigned {
+        redacted_because: Option<Raw<serde_json::Value>>,
+    }
+
+    let req = get::Request::new(board_id.to_owned(), event_id.clone());
+    let get::Response { event, .. } = commune().send_matrix_request(req, Some(token)).await.ok()?;
+
+    let Redacted {
+        sender,
+        origin_server_ts,
+        unsigned,
+    } = event.deserialize_as().ok()?;
+
+    unsigned
+        .redacted_because
+        .map(|_| CommentEvent::removed(event_id, sender, origin_server_ts))
+}
+
+async fn resolve_authors(comments: &mut [Comment], authors: &mut BTreeMap<OwnedUserId, Author>) {
+    let mut stack: Vec<&mut Comment> = comments.iter_mut().collect();
+
+    while let Some(comment) = stack.pop() {
+        let user_id = comment.author.user_id.clone();
+
+        comment.author = match authors.get(&user_id) {
+            Some(author) => author.clone(),
+            None => {
+                let author = crate::post::author(user_id.clone()).await;
+                let _ = authors.insert(user_id, author.clone());
+
+                author
+            }
+        };
+
+        stack.extend(comment.replies.iter_mut());
+    }
+}
@@ -0,0 +1,53 @@
+use matrix::{
+    client::event::send::*,
+    ruma_common::{serde::Raw, OwnedMxcUri, OwnedRoomId, OwnedTransactionId, TransactionId},
+    ruma_events::{room::message::RoomMessageEventContent, MessageLikeEventType},
+};
+use serde::Serialize;
+use url::Url;
+
+use super::PostMeta;
+use crate::{commune, error::Result, util::upgrade};
+
+#[derive(Serialize)]
+struct PostEventContent {
+    #[serde(flatten)]
+    message: RoomMessageEventContent,
+
+    #[serde(rename = "sh.commune.post")]
+    post: PostMeta,
+}
+
+/// Retrying with the same `txn_id` does not create a duplicate post. Posts to
+/// a board that was upgraded go to the room that replaced it.
+#[allow(clippy::too_many_arguments)]
+pub async fn service(
+    access_token: impl AsRef<str>,
+    board_id: OwnedRoomId,
+    txn_id: Option<OwnedTransactionId>,
+    title: impl Into<String>,
+    body: impl Into<String>,
+    link: Option<Url>,
+    media: Option<OwnedMxcUri>,
+) -> Result<Response> {
+    let content = PostEventContent {
+        message: RoomMessageEventContent::new(super::format::markdown(body.into())),
+        post: PostMeta {
+            title: title.into(),
+            link,
+            media,
+        },
+    };
+
+    let req = Request::new_raw(
+        upgrade::latest(&board_id).await?,
+        MessageLikeEventType::RoomMessage,
+        txn_id.unwrap_or_else(TransactionId::new),
+        Raw::new(&content)?.cast(),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await
+        .map_err(Into::into)
+}
@@ -0,0 +1,76 @@
+//! Cursors handed out by the post listing are opaque, so the way a listing
+//! pages can change without breaking clients that stored one.
+
+use matrix::ruma_common::{
+    serde::{base64::UrlSafe, Base64},
+    OwnedRoomId,
+};
+use serde::{Deserialize, Serialize};
+
+use super::list::Sort;
+use crate::error::{Error, Result};
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "by", rename_all = "snake_case")]
+pub(crate) enum Cursor {
+    /// A pagination token of the homeserver, for the room a board was
+    /// upgraded from once its own history ran out. No token starts at the
+    /// end of that room.
+    Timeline {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        room_id: Option<OwnedRoomId>,
+
+        token: Option<String>,
+    },
+
+    /// The position in the ranking of one of the vote-based sorts.
+    Ranked { sort: Sort, offset: usize },
+}
+
+impl Cursor {
+    pub(crate) fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("cursors should serialize");
+
+        Base64::<UrlSafe>::new(json).encode()
+    }
+
+    pub(crate) fn decode(cursor: &str) -> Result<Self> {
+        Base64::<UrlSafe>::parse(cursor)
+            .ok()
+            .and_then(|json| serde_json::from_slice(json.as_bytes()).ok())
+            .ok_or(Error::InvalidCursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::post::list::Window;
+
+    #[test]
+    fn round_trips() {
+        for cursor in [
+            Cursor::Timeline {
+                room_id: None,
+                token: Some("t47-1234_0_0".to_owned()),
+            },
+            Cursor::Timeline {
+                room_id: Some("!v1:example.com".try_into().unwrap()),
+                token: None,
+            },
+            Cursor::Ranked {
+                sort: Sort::Top(Window::Week),
+                offset: 40,
+            },
+        ] {
+            let encoded = cursor.encode();
+
+            assert!(encoded
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+            assert_eq!(Cursor::decode(&encoded).unwrap(), cursor);
+        }
+
+        assert!(matches!(Cursor::decode("40"), Err(Error::InvalidCursor)));
+    }
+}
@@ -0,0 +1,46 @@
+use matrix::{
+    client::event::{get, redact::*},
+    ruma_common::{OwnedEventId, OwnedRoomId, OwnedUserId, TransactionId},
+};
+
+use super::{comment::CommentEvent, PostEvent};
+use crate::{
+    commune,
+    error::{Error, Result},
+    util::auth,
+};
+
+/// Redacts a post or comment. Authors can remove their own, others need to
+/// be a moderator of the board, which is checked before redacting.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    board_id: OwnedRoomId,
+    event_id: OwnedEventId,
+    reason: Option<String>,
+) -> Result<Response> {
+    let access_token = access_token.as_ref();
+
+    let user_id = auth::user_id(access_token).await?;
+
+    let req = get::Request::new(board_id.clone(), event_id.clone());
+    let get::Response { event, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    if PostEvent::from_raw(&event).is_none()
+        && CommentEvent::from_raw(&event.clone().cast()).is_none()
+    {
+        return Err(Error::NotFound);
+    }
+
+    if event.get_field::<OwnedUserId>("sender")?.as_ref() != Some(&user_id) {
+        auth::ensure_moderator(access_token, &user_id, &board_id).await?;
+    }
+
+    let req = Request::new(board_id, event_id, TransactionId::new(), reason);
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .map_err(Into::into)
+}
@@ -0,0 +1,81 @@
+use matrix::{
+    client::event::{get, send::*},
+    ruma_common::{
+        serde::Raw, OwnedEventId, OwnedRoomId, OwnedTransactionId, OwnedUserId, TransactionId,
+    },
+    ruma_events::MessageLikeEventType,
+};
+use serde_json::json;
+
+use super::{comment::CommentEvent, format, PostEvent, PostMeta};
+use crate::{
+    commune,
+    error::{Error, Result},
+    util::auth,
+};
+
+/// Sends an `m.replace` relation for a post or comment, only its author can
+/// edit it. The `title` is kept when omitted and ignored for comments.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    board_id: OwnedRoomId,
+    event_id: OwnedEventId,
+    txn_id: Option<OwnedTransactionId>,
+    title: Option<String>,
+    body: impl Into<String>,
+) -> Result<Response> {
+    let access_token = access_token.as_ref();
+    let body = body.into();
+
+    let user_id = auth::user_id(access_token).await?;
+
+    let req = get::Request::new(board_id.clone(), event_id.clone());
+    let get::Response { event, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    if event.get_field::<OwnedUserId>("sender")?.as_ref() != Some(&user_id) {
+        return Err(Error::Forbidden);
+    }
+
+    let mut new_content = serde_json::to_value(format::markdown(body.as_str()))?;
+
+    if let Some(post) = PostEvent::from_raw(&event) {
+        let PostMeta {
+            title: previous,
+            link,
+            media,
+        } = post.content.post;
+        let title = title.unwrap_or(previous);
+
+        new_content[super::POST_FIELD] = serde_json::to_value(PostMeta { title, link, media })?;
+    } else if CommentEvent::from_raw(&event.clone().cast()).is_none() {
+        return Err(Error::NotFound);
+    }
+
+    // clients unaware of edits show the fallback body
+    let mut content = new_content.clone();
+    content["body"] = format!("* {body}").into();
+
+    if let Some(formatted_body) = new_content["formatted_body"].as_str() {
+        content["formatted_body"] = format!("* {formatted_body}").into();
+    }
+
+    content["m.new_content"] = new_content;
+    content["m.relates_to"] = json!({
+        "rel_type": "m.replace",
+        "event_id": event_id,
+    });
+
+    let req = Request::new_raw(
+        board_id,
+        MessageLikeEventType::RoomMessage,
+        txn_id.unwrap_or_else(TransactionId::new),
+        Raw::new(&content)?.cast(),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await
+        .map_err(Into::into)
+}
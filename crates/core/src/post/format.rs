@@ -0,0 +1,211 @@
+//! Bodies are written in markdown and sent along with their HTML rendering,
+//! as most Matrix clients expect. HTML is untrusted on the way out since any
+//! client can send events to a board.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#mroommessage-msgtypes
+
+use matrix::{
+    ruma_events::room::message::{MessageType, TextMessageEventContent},
+    ruma_html::{Html, SanitizerConfig},
+};
+use serde::Deserialize;
+
+pub const HTML_FORMAT: &str = "org.matrix.custom.html";
+
+/// Renders markdown into a text message, `formatted_body` is omitted when
+/// the body is plain text.
+pub fn markdown(body: impl AsRef<str> + Into<String>) -> MessageType {
+    MessageType::Text(TextMessageEventContent::markdown(body))
+}
+
+/// Strips the tags, attributes and URI schemes not allowed by the
+/// specification, along with reply fallbacks.
+pub fn sanitize(html: &str) -> String {
+    let mut html = Html::parse(html);
+    html.sanitize_with(SanitizerConfig::compat().remove_reply_fallback());
+
+    html.to_string()
+}
+
+/// The formatted body of incoming content, flattened into it.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct Formatted {
+    format: Option<String>,
+
+    formatted_body: Option<String>,
+}
+
+impl Formatted {
+    /// The sanitized HTML, if the content has any in a format we know.
+    pub(crate) fn sanitized(&self) -> Option<String> {
+        match (self.format.as_deref(), self.formatted_body.as_deref()) {
+            (Some(HTML_FORMAT), Some(formatted_body)) => Some(sanitize(formatted_body)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TAGS: &[&str] = &[
+        "<script>{}</script>",
+        "<img src=\"{}\">",
+        "<img src=x onerror=\"{}\">",
+        "<a href=\"{}\">link</a>",
+        "<a href=\"https://example.com\" onclick=\"{}\">link</a>",
+        "<iframe src=\"{}\"></iframe>",
+        "<svg onload=\"{}\"><script>{}</script></svg>",
+        "<math><mtext><table><mglyph><style><img src=x onerror=\"{}\">",
+        "<style>@import '{}';</style>",
+        "<object data=\"{}\"></object>",
+        "<embed src=\"{}\">",
+        "<form action=\"{}\"><input type=submit></form>",
+        "<body onload=\"{}\">",
+        "<details open ontoggle=\"{}\">",
+        "<div style=\"background:url({})\">x</div>",
+        "<span data-mx-color=\"red\" onmouseover=\"{}\">x</span>",
+        "<meta http-equiv=\"refresh\" content=\"0;url={}\">",
+        "<base href=\"{}\">",
+        "<!--{}--><img src=x onerror=alert(1)//-->",
+        "<noscript><p title=\"</noscript><img src=x onerror=\"{}\">\"></noscript>",
+        "<mx-reply><a href=\"{}\">quoted</a></mx-reply>",
+        "<<script>{}//<</script>",
+        "<IMG SRC=\"{}\">",
+        "<a href=\"jav&#x09;ascript:{}\">x</a>",
+        "<a href=\"  {}\">x</a>",
+        "<img/src=x/onerror=\"{}\">",
+        "<code class=\"language-rust\" onclick=\"{}\">x</code>",
+    ];
+
+    const PAYLOADS: &[&str] = &[
+        "javascript:alert(1)",
+        "JaVaScRiPt:alert(1)",
+        "java\0script:alert(1)",
+        "&#106;&#97;&#118;&#97;&#115;&#99;&#114;&#105;&#112;&#116;:alert(1)",
+        "vbscript:msgbox(1)",
+        "data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==",
+        "alert(document.cookie)",
+        "\"><script>alert(1)</script>",
+        "' onmouseover='alert(1)",
+        "https://evil.example.com/x.js",
+        "//evil.example.com",
+    ];
+
+    const FORBIDDEN_TAGS: &[&str] = &[
+        "script", "iframe", "svg", "math", "style", "object", "embed", "form", "input", "body",
+        "meta", "base", "noscript", "mx-reply",
+    ];
+
+    /// Walks the tags of serialized HTML, yielding their name and attributes.
+    fn tags(html: &str) -> Vec<(String, Vec<(String, String)>)> {
+        let mut tags = Vec::new();
+        let mut rest = html;
+
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start + 1..];
+
+            let end = rest.find(|c: char| c.is_whitespace() || c == '>' || c == '/');
+            let name = rest[..end.unwrap_or(rest.len())].to_lowercase();
+            rest = &rest[end.unwrap_or(rest.len())..];
+
+            let mut attrs = Vec::new();
+
+            loop {
+                rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+
+                if rest.is_empty() || rest.starts_with('>') {
+                    break;
+                }
+
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '=' || c == '>')
+                    .unwrap_or(rest.len());
+                let attr = rest[..end].to_lowercase();
+                rest = &rest[end..];
+
+                let mut value = String::new();
+
+                // attribute values are always quoted and escaped when serialized
+                if let Some(quoted) = rest.strip_prefix("=\"") {
+                    let end = quoted.find('"').unwrap();
+                    value = quoted[..end].to_lowercase();
+                    rest = &quoted[end + 1..];
+                }
+
+                attrs.push((attr, value));
+            }
+
+            tags.push((name, attrs));
+        }
+
+        tags
+    }
+
+    fn assert_safe(input: &str) {
+        let output = sanitize(input);
+
+        for (name, attrs) in tags(&output) {
+            assert!(
+                !FORBIDDEN_TAGS.contains(&name.as_str()),
+                "`{name}` survived in {output:?} from {input:?}"
+            );
+
+            for (attr, value) in attrs {
+                assert!(
+                    !attr.starts_with("on") && attr != "style",
+                    "`{attr}` survived in {output:?} from {input:?}"
+                );
+
+                if attr == "href" || attr == "src" {
+                    let scheme = value
+                        .split_once(':')
+                        .map(|(scheme, _)| scheme.trim().to_owned())
+                        .unwrap_or_default();
+
+                    let allowed: &[&str] = match (name.as_str(), attr.as_str()) {
+                        ("img", "src") => &["mxc"],
+                        _ => &["https", "http", "ftp", "mailto", "magnet", "matrix"],
+                    };
+
+                    assert!(
+                        allowed.contains(&scheme.as_str()),
+                        "`{value}` survived in {output:?} from {input:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn strips_xss_vectors() {
+        for tag in TAGS {
+            for payload in PAYLOADS {
+                let input = tag.replace("{}", payload);
+
+                assert_safe(&input);
+                assert_safe(&input.to_uppercase());
+                assert_safe(&format!("<p>{input}</p>"));
+                assert_safe(&format!("<blockquote>{input}<blockquote>"));
+                assert_safe(&input.replace(' ', "\n"));
+                assert_safe(&input.replace('"', "'"));
+                assert_safe(&input.replace('"', ""));
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_rendered_markdown() {
+        let MessageType::Text(TextMessageEventContent {
+            formatted: Some(formatted),
+            ..
+        }) = markdown("**hello** [there](https://example.com)")
+        else {
+            panic!("markdown was not rendered");
+        };
+
+        assert_eq!(formatted.format.as_str(), HTML_FORMAT);
+        assert_eq!(sanitize(&formatted.body), formatted.body);
+    }
+}
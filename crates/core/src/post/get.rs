@@ -0,0 +1,34 @@
+use matrix::{
+    client::event::get::*,
+    ruma_common::{OwnedEventId, OwnedRoomId},
+};
+
+use super::{index, Post, PostEvent};
+use crate::{
+    commune,
+    error::{Error, Result},
+    util::auth,
+};
+
+pub async fn service(
+    access_token: Option<&str>,
+    board_id: OwnedRoomId,
+    event_id: OwnedEventId,
+) -> Result<Post> {
+    let token = auth::read_access_token(access_token, &board_id).await?;
+    let req = Request::new(board_id.clone(), event_id);
+
+    let Response { event, .. } = commune().send_matrix_request(req, Some(&token)).await?;
+
+    let event = PostEvent::from_raw(&event).ok_or(Error::NotFound)?;
+    let author = super::author(event.sender.clone()).await;
+    let tallies = index::sync(&board_id).await?;
+
+    let mut post = event.into_post(board_id, author);
+
+    if let Some(tally) = tallies.get(&post.event_id) {
+        post.votes = tally.votes;
+    }
+
+    Ok(post)
+}
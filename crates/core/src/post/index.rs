@@ -0,0 +1,303 @@
+//! Keeps the vote tallies of every board that was listed so far. Each sync
+//! only pages forward from where the previous one ended, so a listing does
+//! not have to read every reaction again.
+//!
+//! The tallies are shared by every reader, so they are always synced through
+//! the administrator account and count every vote. Callers check that the
+//! reader may see the board before handing them out.
+
+use std::{collections::BTreeMap, sync::Mutex};
+
+use matrix::{
+    admin::room::get_messages::*,
+    ruma_common::{
+        api::Direction, serde::Raw, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId,
+        OwnedUserId, RoomId,
+    },
+    ruma_events::{reaction::ReactionEventContent, AnyTimelineEvent, TimelineEventType},
+};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{
+    vote::{Vote, Votes},
+    PostEvent,
+};
+use crate::{commune, error::Result};
+
+static INDEX: Mutex<BTreeMap<OwnedRoomId, Board>> = Mutex::new(BTreeMap::new());
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Tally {
+    pub(crate) origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    pub(crate) votes: Votes,
+}
+
+#[derive(Default)]
+pub(crate) struct Board {
+    /// The pagination token the next sync resumes from.
+    since: Option<String>,
+
+    posts: BTreeMap<OwnedEventId, Entry>,
+
+    /// Maps reactions to the post and user they belong to, so redactions
+    /// can be undone without looking up the reaction.
+    reactions: BTreeMap<OwnedEventId, (OwnedEventId, OwnedUserId)>,
+}
+
+struct Entry {
+    origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    votes: BTreeMap<OwnedUserId, (Vote, OwnedEventId)>,
+}
+
+impl Entry {
+    fn tally(&self) -> Tally {
+        let mut votes = Votes::default();
+
+        for (vote, _) in self.votes.values() {
+            match vote {
+                Vote::Up => votes.up += 1,
+                Vote::Down => votes.down += 1,
+            }
+        }
+
+        Tally {
+            origin_server_ts: self.origin_server_ts,
+            votes,
+        }
+    }
+}
+
+impl Board {
+    fn tallies(&self) -> BTreeMap<OwnedEventId, Tally> {
+        self.posts
+            .iter()
+            .map(|(event_id, entry)| (event_id.clone(), entry.tally()))
+            .collect()
+    }
+
+    /// Events have to be applied in timeline order, applying the same event
+    /// twice is harmless.
+    pub(crate) fn apply(&mut self, event: &Raw<AnyTimelineEvent>) {
+        #[derive(Deserialize)]
+        struct Reaction {
+            event_id: OwnedEventId,
+
+            sender: OwnedUserId,
+
+            content: ReactionEventContent,
+        }
+
+        #[derive(Deserialize)]
+        struct Redaction {
+            redacts: Option<OwnedEventId>,
+
+            content: RedactionContent,
+        }
+
+        #[derive(Deserialize)]
+        struct RedactionContent {
+            redacts: Option<OwnedEventId>,
+        }
+
+        match event.get_field::<TimelineEventType>("type").ok().flatten() {
+            Some(TimelineEventType::RoomMessage) => {
+                if let Some(post) = PostEvent::from_raw(event) {
+                    let _ = self.posts.entry(post.event_id).or_insert(Entry {
+                        origin_server_ts: post.origin_server_ts,
+                        votes: BTreeMap::new(),
+                    });
+                }
+            }
+            Some(TimelineEventType::Reaction) => {
+                let Ok(Reaction {
+                    event_id,
+                    sender,
+                    content,
+                }) = event.deserialize_as()
+                else {
+                    return;
+                };
+
+                let Some(vote) = Vote::from_key(&content.relates_to.key) else {
+                    return;
+                };
+                let post_id = content.relates_to.event_id;
+
+                let Some(entry) = self.posts.get_mut(&post_id) else {
+                    return;
+                };
+
+                if let Some((_, previous)) =
+                    entry.votes.insert(sender.clone(), (vote, event_id.clone()))
+                {
+                    let _ = self.reactions.remove(&previous);
+                }
+
+                let _ = self.reactions.insert(event_id, (post_id, sender));
+            }
+            Some(TimelineEventType::RoomRedaction) => {
+                // rooms from version 11 onwards moved `redacts` into the content
+                let Some(redacts) = event
+                    .deserialize_as::<Redaction>()
+                    .ok()
+                    .and_then(|redaction| redaction.redacts.or(redaction.content.redacts))
+                else {
+                    return;
+                };
+
+                if self.posts.remove(&redacts).is_some() {
+                    return;
+                }
+
+                if let Some((post_id, sender)) = self.reactions.remove(&redacts) {
+                    if let Some(entry) = self.posts.get_mut(&post_id) {
+                        if entry
+                            .votes
+                            .get(&sender)
+                            .is_some_and(|(_, event_id)| *event_id == redacts)
+                        {
+                            let _ = entry.votes.remove(&sender);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Catches up with the timeline of a board and returns the tally of each of
+/// its posts.
+pub(crate) async fn sync(board_id: &RoomId) -> Result<BTreeMap<OwnedEventId, Tally>> {
+    let admin_token = commune().config.matrix.admin_token.inner();
+
+    let since = INDEX
+        .lock()
+        .unwrap()
+        .get(board_id)
+        .and_then(|board| board.since.clone());
+
+    let mut events = Vec::new();
+    let mut from = since.clone();
+
+    loop {
+        let mut req = Request::new(board_id.to_owned(), Direction::Forward);
+        req.from = from.clone();
+        req.limit = Some(500);
+        req.filter = json!({
+            "types": ["m.room.message", "m.reaction", "m.room.redaction"],
+        })
+        .to_string();
+
+        let Response { chunk, end, .. } = commune()
+            .send_matrix_request(req, Some(&admin_token))
+            .await?;
+
+        let done = chunk.is_empty() || end.is_none();
+
+        events.extend(chunk);
+        from = end.or(from);
+
+        if done {
+            break;
+        }
+    }
+
+    let mut index = INDEX.lock().unwrap();
+    let board = index.entry(board_id.to_owned()).or_default();
+
+    // a concurrent sync got here first, its result includes ours
+    if board.since == since {
+        for event in &events {
+            board.apply(event);
+        }
+
+        board.since = from;
+    }
+
+    Ok(board.tallies())
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::owned_event_id;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn raw(value: Value) -> Raw<AnyTimelineEvent> {
+        Raw::new(&value).unwrap().cast()
+    }
+
+    fn reaction(event_id: &str, sender: &str, key: &str) -> Raw<AnyTimelineEvent> {
+        raw(json!({
+            "type": "m.reaction",
+            "event_id": event_id,
+            "sender": sender,
+            "origin_server_ts": 2,
+            "content": {
+                "m.relates_to": {
+                    "rel_type": "m.annotation",
+                    "event_id": "$post",
+                    "key": key,
+                },
+            },
+        }))
+    }
+
+    #[test]
+    fn counts_one_vote_per_user() {
+        let mut board = Board::default();
+
+        for event in [
+            raw(json!({
+                "type": "m.room.message",
+                "event_id": "$post",
+                "sender": "@alice:example.com",
+                "origin_server_ts": 1,
+                "content": {
+                    "msgtype": "m.text",
+                    "body": "hello",
+                    "sh.commune.post": { "title": "hello" },
+                },
+            })),
+            reaction("$up", "@bob:example.com", "👍"),
+            reaction("$down", "@bob:example.com", "👎"),
+            reaction("$other", "@carol:example.com", "👍"),
+            reaction("$emoji", "@dave:example.com", "🎉"),
+            // redacting a replaced vote does not affect the current one
+            raw(json!({
+                "type": "m.room.redaction",
+                "event_id": "$redaction",
+                "sender": "@bob:example.com",
+                "origin_server_ts": 3,
+                "redacts": "$up",
+                "content": {},
+            })),
+        ] {
+            board.apply(&event);
+            board.apply(&event);
+        }
+
+        let tallies = board.tallies();
+        let tally = tallies.get(&owned_event_id!("$post")).unwrap();
+
+        assert_eq!(tally.votes, Votes { up: 1, down: 1 });
+
+        board.apply(&raw(json!({
+            "type": "m.room.redaction",
+            "event_id": "$redaction2",
+            "sender": "@carol:example.com",
+            "origin_server_ts": 4,
+            "content": { "redacts": "$other" },
+        })));
+
+        let tallies = board.tallies();
+        let tally = tallies.get(&owned_event_id!("$post")).unwrap();
+
+        assert_eq!(tally.votes, Votes { up: 0, down: 1 });
+    }
+}
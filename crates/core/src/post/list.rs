@@ -0,0 +1,387 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+use matrix::{
+    client::{event::get, messages::*},
+    ruma_common::{
+        api::Direction, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{cursor::Cursor, index, index::Tally, Author, Post, PostEvent};
+use crate::{
+    commune,
+    error::{Error, Result},
+    util::{auth, upgrade},
+};
+
+const DEFAULT_LIMIT: u64 = 20;
+
+/// Bounds the requests made for one page of a board that is mostly comments.
+const MAX_PAGES: usize = 10;
+
+/// Reddit's epoch, so scores stay comparable with theirs.
+const HOT_EPOCH: i64 = 1_134_028_003;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sort {
+    #[default]
+    New,
+    Hot,
+    Top(Window),
+    Controversial,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Window {
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+    #[default]
+    All,
+}
+
+impl Window {
+    fn seconds(self) -> Option<u64> {
+        match self {
+            Self::Hour => Some(60 * 60),
+            Self::Day => Some(24 * 60 * 60),
+            Self::Week => Some(7 * 24 * 60 * 60),
+            Self::Month => Some(30 * 24 * 60 * 60),
+            Self::Year => Some(365 * 24 * 60 * 60),
+            Self::All => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Posts {
+    pub posts: Vec<Post>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_batch: Option<String>,
+}
+
+/// A room of the board along with the access token to read it.
+type Room = (OwnedRoomId, String);
+
+/// Lists the posts of a board. Newest first pages through the timeline, the
+/// other orders page through the vote index. Passing `next_batch` as `from`
+/// continues where the page ended, until it is omitted at the end.
+///
+/// Boards that were upgraded are listed from the room that replaced them
+/// last, followed by the history of the rooms before it. Posts keep the ID of
+/// the room they were made in.
+pub async fn service(
+    access_token: Option<&str>,
+    board_id: OwnedRoomId,
+    sort: Sort,
+    from: Option<String>,
+    limit: Option<u64>,
+) -> Result<Posts> {
+    let mut rooms = rooms(access_token, &board_id).await?;
+    let mut tallies = BTreeMap::new();
+    let mut homes = BTreeMap::new();
+
+    for (position, (room_id, _)) in rooms.iter().enumerate() {
+        let room = match index::sync(room_id).await {
+            Ok(room) => room,
+            Err(e) if position > 0 => {
+                tracing::debug!(?e, %room_id, "history of board ends before predecessor");
+
+                rooms.truncate(position);
+
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+
+        for (event_id, tally) in room {
+            let _ = homes.insert(event_id.clone(), position);
+            let _ = tallies.insert(event_id, tally);
+        }
+    }
+
+    let from = from.as_deref().map(Cursor::decode).transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    match (sort, from) {
+        (Sort::New, None) => timeline(&rooms, &tallies, 0, None, limit).await,
+        (Sort::New, Some(Cursor::Timeline { room_id, token })) => {
+            let position = match room_id {
+                Some(room_id) => rooms
+                    .iter()
+                    .position(|(id, _)| *id == room_id)
+                    .ok_or(Error::InvalidCursor)?,
+                None => 0,
+            };
+
+            timeline(&rooms, &tallies, position, token, limit).await
+        }
+        (sort, None) => ranked(&rooms, &tallies, &homes, sort, 0, limit).await,
+        (sort, Some(Cursor::Ranked { sort: from, offset })) if from == sort => {
+            ranked(&rooms, &tallies, &homes, sort, offset, limit).await
+        }
+        // cursors only continue the listing they were handed out for
+        _ => Err(Error::InvalidCursor),
+    }
+}
+
+/// The latest room of the board and the ones it was upgraded from, as far
+/// back as the caller may read them.
+async fn rooms(access_token: Option<&str>, board_id: &RoomId) -> Result<Vec<Room>> {
+    let board_id = upgrade::latest(board_id).await?;
+    let token = auth::read_access_token(access_token, &board_id).await?;
+
+    let mut rooms = vec![(board_id.clone(), token)];
+
+    for room_id in upgrade::predecessors(&board_id).await? {
+        match auth::read_access_token(access_token, &room_id).await {
+            Ok(token) => rooms.push((room_id, token)),
+            Err(_) => break,
+        }
+    }
+
+    Ok(rooms)
+}
+
+/// Pages back through the board until `limit` posts are found, moving on to
+/// the room it was upgraded from once the start of a room is reached. Each
+/// request asks for no more events than posts are missing, so no post is
+/// skipped when the page fills up.
+async fn timeline(
+    rooms: &[Room],
+    tallies: &BTreeMap<OwnedEventId, Tally>,
+    mut position: usize,
+    mut from: Option<String>,
+    limit: u64,
+) -> Result<Posts> {
+    let mut authors = BTreeMap::new();
+    let mut posts = Vec::new();
+    let mut done = false;
+
+    for _ in 0..MAX_PAGES {
+        let (board_id, token) = &rooms[position];
+
+        let mut req = Request::new(board_id.clone(), Direction::Backward);
+        req.from = from.take();
+        req.limit = Some(limit - posts.len() as u64);
+        req.filter = json!({
+            "types": ["m.room.message"],
+            "lazy_load_members": true,
+        })
+        .to_string();
+
+        let Response {
+            chunk, state, end, ..
+        } = commune().send_matrix_request(req, Some(token)).await?;
+
+        authors.extend(super::authors_from_state(&state));
+
+        for event in chunk.iter().filter_map(PostEvent::from_raw) {
+            let author = cached_author(&mut authors, &event).await;
+            let mut post = event.into_post(board_id.clone(), author);
+
+            if let Some(tally) = tallies.get(&post.event_id) {
+                post.votes = tally.votes;
+            }
+
+            posts.push(post);
+        }
+
+        // the homeserver omits `end` once the start of the room is reached
+        from = end.filter(|_| !chunk.is_empty());
+
+        if from.is_none() {
+            match position + 1 < rooms.len() {
+                true => position += 1,
+                false => done = true,
+            }
+        }
+
+        if done || posts.len() as u64 >= limit {
+            break;
+        }
+    }
+
+    Ok(Posts {
+        posts,
+        next_batch: (!done).then(|| {
+            Cursor::Timeline {
+                room_id: (position > 0).then(|| rooms[position].0.clone()),
+                token: from,
+            }
+            .encode()
+        }),
+    })
+}
+
+async fn ranked(
+    rooms: &[Room],
+    tallies: &BTreeMap<OwnedEventId, Tally>,
+    homes: &BTreeMap<OwnedEventId, usize>,
+    sort: Sort,
+    from: usize,
+    limit: u64,
+) -> Result<Posts> {
+    let limit = limit as usize;
+
+    let ranked = rank(tallies, sort, MilliSecondsSinceUnixEpoch::now());
+
+    // only reachable with a cursor that was not handed out by us
+    if from > ranked.len() {
+        return Err(Error::InvalidCursor);
+    }
+
+    let mut authors = BTreeMap::new();
+    let mut posts = Vec::new();
+
+    for (event_id, tally) in ranked.iter().skip(from).take(limit) {
+        let (board_id, token) = &rooms[homes.get(event_id).copied().unwrap_or_default()];
+        let req = get::Request::new(board_id.clone(), event_id.clone());
+
+        let event = match commune().send_matrix_request(req, Some(token)).await {
+            Ok(get::Response { event, .. }) => event,
+            Err(e) => {
+                tracing::debug!(?e, %event_id, "skipping unavailable post");
+
+                continue;
+            }
+        };
+
+        let Some(event) = PostEvent::from_raw(&event) else {
+            continue;
+        };
+
+        let author = cached_author(&mut authors, &event).await;
+        let mut post = event.into_post(board_id.clone(), author);
+        post.votes = tally.votes;
+
+        posts.push(post);
+    }
+
+    let next = from.saturating_add(limit);
+
+    Ok(Posts {
+        posts,
+        next_batch: (next < ranked.len()).then(|| Cursor::Ranked { sort, offset: next }.encode()),
+    })
+}
+
+async fn cached_author(authors: &mut BTreeMap<OwnedUserId, Author>, event: &PostEvent) -> Author {
+    match authors.get(&event.sender) {
+        Some(author) => author.clone(),
+        None => {
+            let author = super::author(event.sender.clone()).await;
+            let _ = authors.insert(event.sender.clone(), author.clone());
+
+            author
+        }
+    }
+}
+
+/// Orders posts for the vote-based sorts, newer posts break ties.
+pub(crate) fn rank(
+    tallies: &BTreeMap<OwnedEventId, Tally>,
+    sort: Sort,
+    now: MilliSecondsSinceUnixEpoch,
+) -> Vec<(OwnedEventId, Tally)> {
+    let mut ranked: Vec<_> = tallies
+        .iter()
+        .map(|(event_id, tally)| (event_id.clone(), *tally))
+        .filter(|(_, tally)| match sort {
+            Sort::Top(window) => window.seconds().map_or(true, |seconds| {
+                u64::from(now.as_secs()).saturating_sub(u64::from(tally.origin_server_ts.as_secs()))
+                    <= seconds
+            }),
+            _ => true,
+        })
+        .collect();
+
+    let score = |tally: &Tally| -> f64 {
+        let votes = tally.votes;
+
+        match sort {
+            Sort::New => 0.0,
+            Sort::Top(_) => votes.score() as f64,
+            Sort::Hot => {
+                let order = (votes.score().unsigned_abs().max(1) as f64).log10();
+                let seconds = i64::from(tally.origin_server_ts.as_secs()) - HOT_EPOCH;
+
+                votes.score().signum() as f64 * order + seconds as f64 / 45_000.0
+            }
+            Sort::Controversial => {
+                if votes.up == 0 || votes.down == 0 {
+                    return 0.0;
+                }
+
+                let balance = votes.up.min(votes.down) as f64 / votes.up.max(votes.down) as f64;
+
+                ((votes.up + votes.down) as f64).powf(balance)
+            }
+        }
+    };
+
+    ranked.sort_by(|(_, a), (_, b)| {
+        score(b)
+            .partial_cmp(&score(a))
+            .unwrap_or(Ordering::Equal)
+            .then(b.origin_server_ts.cmp(&a.origin_server_ts))
+    });
+
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::owned_event_id;
+
+    use super::*;
+    use crate::post::vote::Votes;
+
+    fn ts(secs: u64) -> MilliSecondsSinceUnixEpoch {
+        MilliSecondsSinceUnixEpoch((secs * 1000).try_into().unwrap())
+    }
+
+    fn tally(secs: u64, up: u64, down: u64) -> Tally {
+        Tally {
+            origin_server_ts: ts(secs),
+            votes: Votes { up, down },
+        }
+    }
+
+    #[test]
+    fn ranks_by_sort() {
+        let day = 24 * 60 * 60;
+        let now = ts(10 * day);
+
+        let tallies = BTreeMap::from([
+            (owned_event_id!("$old_popular"), tally(day, 50, 0)),
+            (owned_event_id!("$new_quiet"), tally(10 * day - 60, 2, 0)),
+            (owned_event_id!("$divisive"), tally(9 * day, 10, 9)),
+        ]);
+
+        let ids = |sort| -> Vec<String> {
+            rank(&tallies, sort, now)
+                .into_iter()
+                .map(|(event_id, _)| event_id.to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            ids(Sort::Top(Window::All)),
+            ["$old_popular", "$new_quiet", "$divisive"]
+        );
+        assert_eq!(ids(Sort::Top(Window::Day)), ["$new_quiet", "$divisive"]);
+        assert_eq!(ids(Sort::Hot), ["$new_quiet", "$divisive", "$old_popular"]);
+        assert_eq!(
+            ids(Sort::Controversial),
+            ["$divisive", "$new_quiet", "$old_popular"]
+        );
+    }
+}
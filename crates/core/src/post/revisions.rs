@@ -0,0 +1,134 @@
+use matrix::{
+    client::{event::get, relations},
+    ruma_common::{MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId},
+    ruma_events::relation::RelationType,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{NewContent, ReplacementContent};
+use crate::{
+    commune,
+    error::{Error, Result},
+    util::auth,
+};
+
+/// Bounds the amount of requests made for heavily edited events.
+const MAX_PAGES: usize = 10;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Revision {
+    pub event_id: OwnedEventId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    pub body: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted_body: Option<String>,
+
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+}
+
+#[derive(Deserialize)]
+struct Original {
+    event_id: OwnedEventId,
+
+    sender: OwnedUserId,
+
+    origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    content: NewContent,
+}
+
+#[derive(Deserialize)]
+struct Edit {
+    event_id: OwnedEventId,
+
+    sender: OwnedUserId,
+
+    origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    content: ReplacementContent,
+}
+
+/// Lists every version of a post or comment, oldest first. Only moderators
+/// of the board can look at prior versions.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    board_id: OwnedRoomId,
+    event_id: OwnedEventId,
+) -> Result<Vec<Revision>> {
+    let access_token = auth::read_access_token(Some(access_token.as_ref()), &board_id).await?;
+    let access_token = access_token.as_str();
+
+    let user_id = auth::user_id(access_token).await?;
+    auth::ensure_moderator(access_token, &user_id, &board_id).await?;
+
+    let req = get::Request::new(board_id.clone(), event_id.clone());
+    let get::Response { event, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let original = event
+        .deserialize_as::<Original>()
+        .map_err(|_| Error::NotFound)?;
+
+    let mut revisions = vec![Revision {
+        event_id: original.event_id,
+        title: original.content.post.map(|post| post.title),
+        formatted_body: original.content.formatted.sanitized(),
+        body: original.content.body,
+        origin_server_ts: original.origin_server_ts,
+    }];
+
+    let mut edits = Vec::new();
+    let mut next_batch = None;
+
+    for _ in 0..MAX_PAGES {
+        let mut req = relations::Request::new(
+            board_id.clone(),
+            event_id.clone(),
+            RelationType::Replacement,
+        );
+        req.from = next_batch;
+        req.limit = Some(100);
+
+        let resp = commune()
+            .send_matrix_request(req, Some(access_token))
+            .await?;
+
+        // edits by anyone but the author are invalid and not shown by clients
+        edits.extend(
+            resp.chunk
+                .iter()
+                .filter_map(|event| event.deserialize_as::<Edit>().ok())
+                .filter(|edit| edit.sender == original.sender),
+        );
+        next_batch = resp.next_batch;
+
+        if next_batch.is_none() {
+            break;
+        }
+    }
+
+    edits.sort_by_key(|edit| edit.origin_server_ts);
+
+    revisions.extend(edits.into_iter().map(|edit| {
+        let NewContent {
+            body,
+            formatted,
+            post,
+        } = edit.content.new_content;
+
+        Revision {
+            event_id: edit.event_id,
+            title: post.map(|post| post.title),
+            body,
+            formatted_body: formatted.sanitized(),
+            origin_server_ts: edit.origin_server_ts,
+        }
+    }));
+
+    Ok(revisions)
+}
@@ -0,0 +1,150 @@
+//! Votes are `m.reaction` annotations on a post, keyed with the thumbs
+//! commonly offered by Matrix clients so reactions sent from elsewhere count
+//! as well. Only the latest vote of each user is taken into account.
+
+use matrix::{
+    client::{event::redact, event::send, relations},
+    ruma_common::{OwnedEventId, OwnedRoomId, OwnedUserId, TransactionId},
+    ruma_events::{
+        reaction::ReactionEventContent,
+        relation::{Annotation, RelationType},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use super::index;
+use crate::{
+    commune,
+    error::{Error, Result},
+    util::auth,
+};
+
+pub const UPVOTE_KEY: &str = "👍";
+pub const DOWNVOTE_KEY: &str = "👎";
+
+/// Bounds the amount of requests made to find previous votes.
+const MAX_PAGES: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Vote {
+    Up,
+    Down,
+}
+
+impl Vote {
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            UPVOTE_KEY => Some(Self::Up),
+            DOWNVOTE_KEY => Some(Self::Down),
+            _ => None,
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Up => UPVOTE_KEY,
+            Self::Down => DOWNVOTE_KEY,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Votes {
+    pub up: u64,
+
+    pub down: u64,
+}
+
+impl Votes {
+    pub fn score(&self) -> i64 {
+        self.up as i64 - self.down as i64
+    }
+}
+
+#[derive(Deserialize)]
+struct Reaction {
+    event_id: OwnedEventId,
+
+    sender: OwnedUserId,
+
+    content: ReactionEventContent,
+}
+
+/// Replaces the vote of the user on a post, passing `None` retracts it.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    board_id: OwnedRoomId,
+    post_id: OwnedEventId,
+    vote: Option<Vote>,
+) -> Result<Votes> {
+    let access_token = auth::read_access_token(Some(access_token.as_ref()), &board_id).await?;
+    let access_token = access_token.as_str();
+    let user_id = auth::user_id(access_token).await?;
+
+    if !index::sync(&board_id).await?.contains_key(&post_id) {
+        return Err(Error::NotFound);
+    }
+
+    let mut previous = Vec::new();
+    let mut next_batch = None;
+
+    for _ in 0..MAX_PAGES {
+        let mut req =
+            relations::Request::new(board_id.clone(), post_id.clone(), RelationType::Annotation);
+        req.from = next_batch;
+        req.limit = Some(100);
+
+        let resp = commune()
+            .send_matrix_request(req, Some(access_token))
+            .await?;
+
+        previous.extend(
+            resp.chunk
+                .iter()
+                .filter_map(|event| event.deserialize_as::<Reaction>().ok())
+                .filter(|reaction| reaction.sender == user_id)
+                .filter_map(|reaction| {
+                    Vote::from_key(&reaction.content.relates_to.key)
+                        .map(|vote| (reaction.event_id, vote))
+                }),
+        );
+        next_batch = resp.next_batch;
+
+        if next_batch.is_none() {
+            break;
+        }
+    }
+
+    let mut kept = false;
+
+    for (event_id, previous) in previous {
+        if !kept && Some(previous) == vote {
+            kept = true;
+
+            continue;
+        }
+
+        let req = redact::Request::new(board_id.clone(), event_id, TransactionId::new(), None);
+
+        let _ = commune()
+            .send_matrix_request(req, Some(access_token))
+            .await?;
+    }
+
+    if let Some(vote) = vote.filter(|_| !kept) {
+        let content =
+            ReactionEventContent::new(Annotation::new(post_id.clone(), vote.key().to_owned()));
+        let req = send::Request::new(board_id.clone(), TransactionId::new(), &content)?;
+
+        let _ = commune()
+            .send_matrix_request(req, Some(access_token))
+            .await?;
+    }
+
+    Ok(index::sync(&board_id)
+        .await?
+        .get(&post_id)
+        .map(|tally| tally.votes)
+        .unwrap_or_default())
+}
@@ -0,0 +1,8 @@
+//! Read markers of boards and what is left unread. The fully read marker is
+//! where the user stopped reading, the read receipt the latest post they
+//! saw, which the homeserver counts notifications from.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#receipts
+
+pub mod mark;
+pub mod unread;
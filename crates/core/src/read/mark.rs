@@ -0,0 +1,30 @@
+use matrix::{
+    client::read_markers::*,
+    ruma_common::{OwnedEventId, OwnedRoomId},
+};
+
+use crate::{commune, error::Result};
+
+/// Private receipts move the counts without telling other members.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    fully_read: Option<OwnedEventId>,
+    read: Option<OwnedEventId>,
+    private: bool,
+) -> Result<()> {
+    let mut req = Request::new(room_id);
+    req.fully_read = fully_read;
+
+    match private {
+        true => req.private_read = read,
+        false => req.read = read,
+    }
+
+    commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await
+        .map_err(crate::membership::refused)?;
+
+    Ok(())
+}
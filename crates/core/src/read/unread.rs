@@ -0,0 +1,146 @@
+use matrix::{
+    client::sync::v3,
+    ruma_common::{serde::Raw, OwnedEventId, OwnedRoomId, UserId},
+    ruma_events::{
+        fully_read::FullyReadEventContent, receipt::ReceiptEventContent, AnySyncEphemeralRoomEvent,
+        RoomAccountDataEventType,
+    },
+};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{commune, error::Result, space, util::auth};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Unread {
+    pub space_id: OwnedRoomId,
+
+    /// Summed over the boards.
+    pub notification_count: usize,
+
+    /// Summed over the boards.
+    pub highlight_count: usize,
+
+    /// Only the boards of the space the user joined.
+    pub boards: Vec<Board>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Board {
+    pub board_id: OwnedRoomId,
+
+    pub notification_count: usize,
+
+    /// Notifications that mention the user.
+    pub highlight_count: usize,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fully_read: Option<OwnedEventId>,
+
+    /// The latest event the user sent a receipt for, public or private.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read: Option<OwnedEventId>,
+}
+
+pub async fn service(access_token: impl AsRef<str>, space_id: OwnedRoomId) -> Result<Unread> {
+    let access_token = access_token.as_ref();
+    let user_id = auth::user_id(access_token).await?;
+
+    let board_ids = space::children(access_token, &space_id).await?;
+
+    let mut unread = Unread {
+        space_id,
+        notification_count: 0,
+        highlight_count: 0,
+        boards: Vec::new(),
+    };
+
+    if board_ids.is_empty() {
+        return Ok(unread);
+    }
+
+    // Only the summaries are needed, everything else is filtered out.
+    let mut req = v3::Request::new();
+    req.timeout = None;
+    req.filter = json!({
+        "presence": { "types": [] },
+        "account_data": { "types": [] },
+        "room": {
+            "rooms": board_ids,
+            "timeline": { "limit": 1, "types": [] },
+            "state": { "types": [] },
+            "ephemeral": { "types": ["m.receipt"] },
+            "account_data": { "types": ["m.fully_read"] },
+        },
+    })
+    .to_string();
+
+    let v3::Response { rooms, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    // kept in the order of the space
+    for board_id in board_ids {
+        let Some(room) = rooms.join.get(&board_id) else {
+            continue;
+        };
+
+        let board = Board {
+            board_id,
+            notification_count: room
+                .unread_notifications
+                .notification_count
+                .unwrap_or_default(),
+            highlight_count: room
+                .unread_notifications
+                .highlight_count
+                .unwrap_or_default(),
+            fully_read: room
+                .account_data
+                .events
+                .iter()
+                .filter(|event| {
+                    event
+                        .get_field::<RoomAccountDataEventType>("type")
+                        .ok()
+                        .flatten()
+                        == Some(RoomAccountDataEventType::FullyRead)
+                })
+                .find_map(|event| {
+                    event
+                        .get_field::<FullyReadEventContent>("content")
+                        .ok()
+                        .flatten()
+                })
+                .map(|content| content.event_id),
+            read: receipt(&room.ephemeral.events, &user_id),
+        };
+
+        unread.notification_count += board.notification_count;
+        unread.highlight_count += board.highlight_count;
+        unread.boards.push(board);
+    }
+
+    Ok(unread)
+}
+
+/// The receipts arrive as a map of events to the users that read them.
+fn receipt(events: &[Raw<AnySyncEphemeralRoomEvent>], user_id: &UserId) -> Option<OwnedEventId> {
+    events
+        .iter()
+        .filter_map(|event| match event.deserialize().ok()? {
+            AnySyncEphemeralRoomEvent::Receipt(receipt) => Some(receipt.content),
+            _ => None,
+        })
+        .flat_map(|ReceiptEventContent(content)| content)
+        .filter_map(|(event_id, receipts)| {
+            receipts
+                .values()
+                .filter_map(|users| users.get(user_id))
+                .filter_map(|receipt| receipt.ts)
+                .max()
+                .map(|ts| (ts, event_id))
+        })
+        .max_by_key(|(ts, _)| *ts)
+        .map(|(_, event_id)| event_id)
+}
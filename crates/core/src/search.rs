@@ -0,0 +1,470 @@
+//! Full-text search over the posts and comments of a space. Members search
+//! through the `/search` of the homeserver scoped to the boards of the space,
+//! which only indexes message bodies, so titles are not matched there.
+//! Anonymous visitors have not joined anything for it to cover, they search
+//! the world-readable boards in the local [`index`] instead.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#server-side-search
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use matrix::{
+    admin::room::get_room,
+    client::{event::get, search},
+    ruma_common::{
+        serde::Raw, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId,
+    },
+    ruma_events::{AnyTimelineEvent, TimelineEventType},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub mod index;
+
+use crate::{
+    commune,
+    error::{Error, Result},
+    post::{self, Author, PostEvent},
+    space,
+    util::auth,
+};
+
+/// The amount of characters kept on each side of the first highlight.
+const SNIPPET_CONTEXT: usize = 80;
+
+/// The amount of results per page from the local index.
+const PAGE_SIZE: usize = 20;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Order {
+    #[default]
+    Rank,
+    Recent,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Results {
+    /// An estimate of the total amount of results.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+
+    /// Words to highlight in the snippets.
+    pub highlights: Vec<String>,
+
+    pub results: Vec<Hit>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_batch: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Hit {
+    pub board_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub board: Option<String>,
+
+    /// The matching post, or the post of the matching comment.
+    pub post_id: OwnedEventId,
+
+    pub event_id: OwnedEventId,
+
+    /// Whether the match is a comment rather than the post itself.
+    pub comment: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    pub author: Author,
+
+    pub snippet: String,
+
+    pub origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<f64>,
+}
+
+/// Searches the boards of a space, or only `board` within it. Members search
+/// the boards they joined, anonymous visitors only the public, world-readable
+/// ones. Private boards are left out for anyone but their members.
+pub async fn service(
+    access_token: Option<&str>,
+    space_id: OwnedRoomId,
+    term: String,
+    board: Option<OwnedRoomId>,
+    author: Option<OwnedUserId>,
+    order: Order,
+    next_batch: Option<String>,
+) -> Result<Results> {
+    let admin_token = commune().config.matrix.admin_token.inner();
+    let token = access_token.map_or(admin_token.clone(), ToOwned::to_owned);
+
+    if access_token.is_some() {
+        let _ = auth::read_access_token(access_token, &space_id).await?;
+    }
+
+    let mut boards = BTreeMap::new();
+
+    for board_id in space::children(&admin_token, &space_id).await? {
+        let req = get_room::Request::new(board_id.clone());
+
+        let room = match commune().send_matrix_request(req, Some(&admin_token)).await {
+            Ok(get_room::Response { room, .. }) => room,
+            Err(e) => {
+                tracing::debug!(?e, %board_id, "skipping unavailable board");
+
+                continue;
+            }
+        };
+
+        if auth::authorize(access_token, &room).await.is_ok() {
+            let _ = boards.insert(board_id, room.name);
+        }
+    }
+
+    if let Some(board) = &board {
+        boards.retain(|board_id, _| board_id == board);
+    }
+
+    if boards.is_empty() {
+        return Ok(Results {
+            count: Some(0),
+            highlights: Vec::new(),
+            results: Vec::new(),
+            next_batch: None,
+        });
+    }
+
+    if access_token.is_none() {
+        return local(&admin_token, &boards, &term, author, order, next_batch).await;
+    }
+
+    let mut filter = json!({
+        "rooms": boards.keys().collect::<Vec<_>>(),
+        "types": ["m.room.message"],
+    });
+
+    if let Some(author) = author {
+        filter["senders"] = json!([author]);
+    }
+
+    let mut req = search::Request::new(search::Criteria {
+        search_term: term,
+        keys: vec!["content.body".to_owned()],
+        filter: Some(filter),
+        order_by: Some(match order {
+            Order::Rank => search::OrderBy::Rank,
+            Order::Recent => search::OrderBy::Recent,
+        }),
+    });
+    req.next_batch = next_batch;
+
+    let search::Response {
+        search_categories, ..
+    } = commune().send_matrix_request(req, Some(&token)).await?;
+    let search::ResultRoomEvents {
+        count,
+        highlights,
+        next_batch,
+        results,
+    } = search_categories.room_events;
+
+    let mut authors = BTreeMap::<OwnedUserId, Author>::new();
+    let mut titles = BTreeMap::new();
+    let mut hits = Vec::new();
+
+    for search::SearchResult { rank, result } in results {
+        let Some(event) = result else {
+            continue;
+        };
+
+        let Some(found) = Found::from_raw(&event) else {
+            continue;
+        };
+
+        // results from outside the space would mean the filter was ignored
+        let Some(board) = boards.get(&found.room_id) else {
+            continue;
+        };
+
+        let title = match found.title.clone() {
+            Some(title) => Some(title),
+            None => {
+                if !titles.contains_key(&found.post_id) {
+                    let title = title(&token, &found.room_id, &found.post_id).await;
+                    let _ = titles.insert(found.post_id.clone(), title);
+                }
+
+                titles.get(&found.post_id).cloned().flatten()
+            }
+        };
+
+        let author = match authors.get(&found.sender) {
+            Some(author) => author.clone(),
+            None => {
+                let author = post::author(found.sender.clone()).await;
+                let _ = authors.insert(found.sender.clone(), author.clone());
+
+                author
+            }
+        };
+
+        hits.push(Hit {
+            board_id: found.room_id.clone(),
+            board: board.clone(),
+            post_id: found.post_id,
+            event_id: found.event_id,
+            comment: found.comment,
+            title,
+            author,
+            snippet: snippet(&found.body, &highlights),
+            origin_server_ts: found.origin_server_ts,
+            rank,
+        });
+    }
+
+    Ok(Results {
+        count,
+        highlights,
+        results: hits,
+        next_batch,
+    })
+}
+
+/// Pages are counted in results, `next_batch` being the offset of the next.
+async fn local(
+    admin_token: &str,
+    boards: &BTreeMap<OwnedRoomId, Option<String>>,
+    term: &str,
+    author: Option<OwnedUserId>,
+    order: Order,
+    next_batch: Option<String>,
+) -> Result<Results> {
+    let from = next_batch
+        .map(|next_batch| next_batch.parse::<usize>())
+        .transpose()
+        .map_err(|_| Error::InvalidCursor)?
+        .unwrap_or_default();
+
+    let board_ids = boards.keys().cloned().collect();
+    let (docs, count) = index::search(term, &board_ids, author.as_deref(), order, from, PAGE_SIZE);
+
+    let highlights = index::tokenize(term)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let next_batch = (from + docs.len() < count).then(|| (from + docs.len()).to_string());
+
+    let mut authors = BTreeMap::<OwnedUserId, Author>::new();
+    let mut hits = Vec::with_capacity(docs.len());
+
+    for (doc, rank) in docs {
+        let title = match doc.title {
+            Some(title) => Some(title),
+            None => match index::title(&doc.post_id) {
+                Some(title) => Some(title),
+                None => title(admin_token, &doc.board_id, &doc.post_id).await,
+            },
+        };
+
+        let author = match authors.get(&doc.author) {
+            Some(author) => author.clone(),
+            None => {
+                let author = post::author(doc.author.clone()).await;
+                let _ = authors.insert(doc.author.clone(), author.clone());
+
+                author
+            }
+        };
+
+        hits.push(Hit {
+            board: boards.get(&doc.board_id).cloned().flatten(),
+            board_id: doc.board_id,
+            comment: doc.post_id != doc.event_id,
+            post_id: doc.post_id,
+            event_id: doc.event_id,
+            title,
+            author,
+            snippet: snippet(&doc.body, &highlights),
+            origin_server_ts: doc.origin_server_ts,
+            rank: Some(rank),
+        });
+    }
+
+    Ok(Results {
+        count: Some(count as u64),
+        highlights,
+        results: hits,
+        next_batch,
+    })
+}
+
+/// A post or comment returned by the homeserver.
+struct Found {
+    room_id: OwnedRoomId,
+
+    event_id: OwnedEventId,
+
+    post_id: OwnedEventId,
+
+    sender: OwnedUserId,
+
+    origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+    title: Option<String>,
+
+    body: String,
+
+    comment: bool,
+}
+
+impl Found {
+    fn from_raw(event: &Raw<AnyTimelineEvent>) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Message {
+            room_id: OwnedRoomId,
+
+            event_id: OwnedEventId,
+
+            sender: OwnedUserId,
+
+            origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+            content: Content,
+        }
+
+        #[derive(Deserialize)]
+        struct Content {
+            #[serde(default)]
+            body: String,
+
+            #[serde(rename = "m.relates_to")]
+            relates_to: Option<Relation>,
+        }
+
+        #[derive(Deserialize)]
+        struct Relation {
+            rel_type: Option<String>,
+
+            event_id: Option<OwnedEventId>,
+        }
+
+        if event.get_field::<TimelineEventType>("type").ok()?? != TimelineEventType::RoomMessage {
+            return None;
+        }
+
+        let message = event.deserialize_as::<Message>().ok()?;
+
+        if let Some(post) = PostEvent::from_raw(event) {
+            return Some(Self {
+                room_id: message.room_id,
+                post_id: post.event_id.clone(),
+                event_id: post.event_id,
+                sender: post.sender,
+                origin_server_ts: post.origin_server_ts,
+                title: Some(post.content.post.title),
+                body: post.content.body,
+                comment: false,
+            });
+        }
+
+        // edits are found along with the original, only comments are kept
+        match message.content.relates_to {
+            Some(Relation {
+                rel_type: Some(rel_type),
+                event_id: Some(post_id),
+            }) if rel_type == "m.thread" => Some(Self {
+                room_id: message.room_id,
+                event_id: message.event_id,
+                post_id,
+                sender: message.sender,
+                origin_server_ts: message.origin_server_ts,
+                title: None,
+                body: message.content.body,
+                comment: true,
+            }),
+            _ => None,
+        }
+    }
+}
+
+async fn title(token: &str, room_id: &RoomId, post_id: &OwnedEventId) -> Option<String> {
+    let req = get::Request::new(room_id.to_owned(), post_id.clone());
+
+    match commune().send_matrix_request(req, Some(token)).await {
+        Ok(get::Response { event, .. }) => {
+            PostEvent::from_raw(&event).map(|post| post.content.post.title)
+        }
+        Err(e) => {
+            tracing::debug!(?e, %post_id, "failed to look up post of comment");
+
+            None
+        }
+    }
+}
+
+/// Cuts the body around the first highlighted word.
+pub(crate) fn snippet(body: &str, highlights: &[String]) -> String {
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+
+    let chars = body.chars().collect::<Vec<_>>();
+    let folded = chars.iter().copied().map(fold).collect::<Vec<_>>();
+
+    let first = highlights
+        .iter()
+        .map(|highlight| highlight.chars().map(fold).collect::<Vec<_>>())
+        .filter(|highlight| !highlight.is_empty())
+        .filter_map(|highlight| {
+            folded
+                .windows(highlight.len())
+                .position(|window| window == highlight.as_slice())
+        })
+        .min()
+        .unwrap_or_default();
+
+    let start = first.saturating_sub(SNIPPET_CONTEXT);
+    let end = (first + SNIPPET_CONTEXT)
+        .max(start + 2 * SNIPPET_CONTEXT)
+        .min(chars.len());
+
+    let mut snippet = String::new();
+
+    if start > 0 {
+        snippet.push('…');
+    }
+
+    snippet.extend(&chars[start..end]);
+
+    if end < chars.len() {
+        snippet.push('…');
+    }
+
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cuts_snippets_around_highlights() {
+        let body = format!(
+            "{} Ferris is the unofficial mascot {}",
+            "a".repeat(200),
+            "b".repeat(200)
+        );
+        let snippet = snippet(&body, &["ferris".to_owned()]);
+
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("Ferris is the unofficial mascot"));
+        assert_eq!(snippet.chars().count(), 2 * SNIPPET_CONTEXT + 2);
+
+        assert_eq!(
+            super::snippet("short ünïcode", &["missing".to_owned()]),
+            "short ünïcode"
+        );
+    }
+}
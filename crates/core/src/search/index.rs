@@ -0,0 +1,715 @@
+//! An embedded full-text index over the world-readable boards of the spaces,
+//! since the `/search` of the homeserver only covers the rooms the searching
+//! user joined. It follows the events of the sync loop that also serves the
+//! stream, and can be rebuilt from the history of the boards with
+//! `commune reindex`, which a running server picks up instead of saving over.
+//!
+//! Only the documents are written to disk, the postings are rebuilt when the
+//! index is loaded.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    sync::RwLock,
+    time::{Duration, SystemTime},
+};
+
+use matrix::{
+    client::messages,
+    ruma_common::{
+        api::Direction, serde::Raw, EventId, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId,
+        OwnedUserId, RoomId, UserId,
+    },
+    ruma_events::{relation::RelationType, AnyTimelineEvent, TimelineEventType},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+use super::Order;
+use crate::{
+    commune,
+    error::Result,
+    post::{NewContent, PostEvent},
+    space,
+    stream::{self, Event},
+    util::auth,
+};
+
+/// How often to look for boards that became world-readable, or stopped being
+/// readable by everyone.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+static INDEX: RwLock<Index> = RwLock::new(Index::new());
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct Doc {
+    pub(crate) board_id: OwnedRoomId,
+
+    /// The post itself, or the post of the thread for comments.
+    pub(crate) post_id: OwnedEventId,
+
+    pub(crate) event_id: OwnedEventId,
+
+    pub(crate) author: OwnedUserId,
+
+    pub(crate) title: Option<String>,
+
+    pub(crate) body: String,
+
+    pub(crate) origin_server_ts: MilliSecondsSinceUnixEpoch,
+}
+
+impl Doc {
+    /// Title and body positions are apart, so phrases cannot span both.
+    fn terms(&self) -> impl Iterator<Item = (u32, String)> + '_ {
+        let title = self.title.as_deref().map(tokenize).into_iter().flatten();
+        let body = tokenize(&self.body);
+
+        title
+            .chain(std::iter::once(String::new()))
+            .chain(body)
+            .enumerate()
+            .filter(|(_, term)| !term.is_empty())
+            .map(|(position, term)| (position as u32, term))
+    }
+}
+
+#[derive(Default, Deserialize, Serialize)]
+pub(crate) struct Index {
+    docs: BTreeMap<OwnedEventId, Doc>,
+
+    #[serde(skip)]
+    postings: BTreeMap<String, BTreeMap<OwnedEventId, Vec<u32>>>,
+}
+
+impl Index {
+    const fn new() -> Self {
+        Self {
+            docs: BTreeMap::new(),
+            postings: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, doc: Doc) {
+        self.remove(&doc.event_id);
+
+        for (position, term) in doc.terms() {
+            self.postings
+                .entry(term)
+                .or_default()
+                .entry(doc.event_id.clone())
+                .or_default()
+                .push(position);
+        }
+
+        let _ = self.docs.insert(doc.event_id.clone(), doc);
+    }
+
+    pub(crate) fn remove(&mut self, event_id: &EventId) {
+        let Some(doc) = self.docs.remove(event_id) else {
+            return;
+        };
+
+        for (_, term) in doc.terms() {
+            if let Some(postings) = self.postings.get_mut(&term) {
+                let _ = postings.remove(event_id);
+
+                if postings.is_empty() {
+                    let _ = self.postings.remove(&term);
+                }
+            }
+        }
+    }
+
+    /// Drops the documents of boards that are no longer indexed, such as ones
+    /// that were made private. Returns whether there were any.
+    pub(crate) fn retain(&mut self, boards: &BTreeSet<OwnedRoomId>) -> bool {
+        let stale: Vec<_> = self
+            .docs
+            .values()
+            .filter(|doc| !boards.contains(&doc.board_id))
+            .map(|doc| doc.event_id.clone())
+            .collect();
+
+        for event_id in &stale {
+            self.remove(event_id);
+        }
+
+        !stale.is_empty()
+    }
+
+    /// Edits only count when made by the author, `sender` is left out when
+    /// that was checked already.
+    pub(crate) fn edit(
+        &mut self,
+        event_id: &EventId,
+        sender: Option<&UserId>,
+        title: Option<String>,
+        body: String,
+    ) {
+        let Some(doc) = self.docs.get(event_id) else {
+            return;
+        };
+
+        if sender.is_some_and(|sender| sender != doc.author) {
+            return;
+        }
+
+        let mut doc = doc.clone();
+        doc.title = title.or(doc.title);
+        doc.body = body;
+
+        self.insert(doc);
+    }
+
+    /// Every word must appear, words in double quotes next to each other.
+    /// Returns a page of the matches and their total amount.
+    pub(crate) fn search(
+        &self,
+        query: &str,
+        boards: &BTreeSet<OwnedRoomId>,
+        author: Option<&UserId>,
+        order: Order,
+        from: usize,
+        limit: usize,
+    ) -> (Vec<(Doc, f64)>, usize) {
+        let phrases = parse(query);
+
+        let Some(rarest) = phrases
+            .iter()
+            .flatten()
+            .min_by_key(|term| self.postings.get(*term).map_or(0, BTreeMap::len))
+        else {
+            return (Vec::new(), 0);
+        };
+
+        let Some(candidates) = self.postings.get(rarest) else {
+            return (Vec::new(), 0);
+        };
+
+        let total = self.docs.len() as f64;
+
+        let mut matches = candidates
+            .keys()
+            .filter_map(|event_id| self.docs.get(event_id))
+            .filter(|doc| boards.contains(&doc.board_id))
+            .filter(|doc| author.map_or(true, |author| doc.author == author))
+            .filter_map(|doc| {
+                let mut score = 0.0;
+
+                for phrase in &phrases {
+                    let hits = self.phrase_hits(&doc.event_id, phrase);
+
+                    if hits == 0 {
+                        return None;
+                    }
+
+                    let found_in = self.postings.get(&phrase[0]).map_or(1, BTreeMap::len);
+
+                    score += hits as f64 * (1.0 + total / found_in as f64).ln();
+                }
+
+                let length = doc.terms().count().max(1) as f64;
+
+                Some((doc, score / (1.0 + length.ln())))
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|(a, a_score), (b, b_score)| match order {
+            Order::Rank => b_score
+                .total_cmp(a_score)
+                .then_with(|| b.origin_server_ts.cmp(&a.origin_server_ts)),
+            Order::Recent => b.origin_server_ts.cmp(&a.origin_server_ts),
+        });
+
+        let count = matches.len();
+
+        let page = matches
+            .into_iter()
+            .skip(from)
+            .take(limit)
+            .map(|(doc, score)| (doc.clone(), score))
+            .collect();
+
+        (page, count)
+    }
+
+    /// Counts the positions the phrase starts at.
+    fn phrase_hits(&self, event_id: &EventId, phrase: &[String]) -> usize {
+        let positions = |term: &String| {
+            self.postings
+                .get(term)
+                .and_then(|postings| postings.get(event_id))
+        };
+
+        let Some(first) = positions(&phrase[0]) else {
+            return 0;
+        };
+
+        first
+            .iter()
+            .filter(|start| {
+                phrase.iter().enumerate().skip(1).all(|(offset, term)| {
+                    positions(term)
+                        .is_some_and(|positions| positions.contains(&(**start + offset as u32)))
+                })
+            })
+            .count()
+    }
+
+    fn load(path: &Path) -> std::io::Result<Self> {
+        let docs: BTreeMap<OwnedEventId, Doc> = serde_json::from_slice(&std::fs::read(path)?)?;
+
+        let mut index = Self::new();
+
+        for doc in docs.into_values() {
+            index.insert(doc);
+        }
+
+        Ok(index)
+    }
+
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // written aside first, so a crash cannot leave half an index behind
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, serde_json::to_vec(&self.docs)?)?;
+        std::fs::rename(partial, path)
+    }
+}
+
+pub(crate) fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Splits a query into phrases, single words being phrases of their own.
+fn parse(query: &str) -> Vec<Vec<String>> {
+    query
+        .split('"')
+        .enumerate()
+        .flat_map(|(i, part)| match i % 2 {
+            0 => tokenize(part).map(|term| vec![term]).collect::<Vec<_>>(),
+            _ => vec![tokenize(part).collect()],
+        })
+        .filter(|phrase: &Vec<String>| !phrase.is_empty())
+        .collect()
+}
+
+pub(crate) fn search(
+    query: &str,
+    boards: &BTreeSet<OwnedRoomId>,
+    author: Option<&UserId>,
+    order: Order,
+    from: usize,
+    limit: usize,
+) -> (Vec<(Doc, f64)>, usize) {
+    INDEX
+        .read()
+        .unwrap()
+        .search(query, boards, author, order, from, limit)
+}
+
+/// The title of an indexed post.
+pub(crate) fn title(post_id: &EventId) -> Option<String> {
+    INDEX
+        .read()
+        .unwrap()
+        .docs
+        .get(post_id)
+        .and_then(|doc| doc.title.clone())
+}
+
+/// When the index file was written last, to tell whether someone else did.
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Loads the index and keeps it updated from then on.
+pub async fn start() {
+    let mut loaded = None;
+
+    if let Some(path) = &commune().config.search.index {
+        match Index::load(path) {
+            Ok(index) => {
+                *INDEX.write().unwrap() = index;
+                loaded = modified(path);
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!(?e, "failed to load search index, run `commune reindex`"),
+        }
+    }
+
+    tokio::spawn(run(loaded));
+}
+
+async fn run(mut saved: Option<SystemTime>) {
+    // subscribed before watching, so no event falls in between
+    let mut events = stream::events();
+
+    let mut rescan = tokio::time::interval(RESCAN_INTERVAL);
+    let mut save = tokio::time::interval(SAVE_INTERVAL);
+
+    let mut boards = BTreeSet::new();
+    let mut dirty = false;
+
+    loop {
+        tokio::select! {
+            _ = rescan.tick() => match world_readable_boards().await {
+                Ok(found) => {
+                    for board_id in &found {
+                        if let Err(e) = stream::watch(board_id).await {
+                            tracing::debug!(?e, %board_id, "failed to watch board for search");
+                        }
+                    }
+
+                    dirty |= INDEX.write().unwrap().retain(&found);
+                    boards = found;
+                }
+                Err(e) => tracing::warn!(?e, "failed to list world-readable boards"),
+            },
+            _ = save.tick() => {
+                let Some(path) = &commune().config.search.index else {
+                    continue;
+                };
+
+                // rebuilt by `commune reindex` meanwhile, which wins over the
+                // changes since the last save
+                if modified(path) != saved {
+                    match Index::load(path) {
+                        Ok(mut index) => {
+                            let _ = index.retain(&boards);
+                            *INDEX.write().unwrap() = index;
+
+                            tracing::info!("reloaded rebuilt search index");
+                        }
+                        Err(e) => tracing::warn!(?e, "failed to reload search index"),
+                    }
+
+                    saved = modified(path);
+                    dirty = false;
+                } else if dirty {
+                    match INDEX.read().unwrap().save(path) {
+                        Ok(()) => {
+                            saved = modified(path);
+                            dirty = false;
+                        }
+                        Err(e) => tracing::warn!(?e, "failed to save search index"),
+                    }
+                }
+            },
+            entry = events.recv() => match entry {
+                Ok(entry) => dirty |= apply(&boards, &entry.1),
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::warn!(missed, "search index fell behind, run `commune reindex`");
+                }
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+}
+
+/// Returns whether the index changed.
+fn apply(boards: &BTreeSet<OwnedRoomId>, event: &Event) -> bool {
+    let mut index = INDEX.write().unwrap();
+
+    match event {
+        Event::Post { post } if boards.contains(&post.room_id) => index.insert(Doc {
+            board_id: post.room_id.clone(),
+            post_id: post.event_id.clone(),
+            event_id: post.event_id.clone(),
+            author: post.author.user_id.clone(),
+            title: Some(post.title.clone()),
+            body: post.body.clone(),
+            origin_server_ts: post.origin_server_ts,
+        }),
+        Event::Comment {
+            board_id,
+            post_id,
+            comment,
+            ..
+        } if boards.contains(board_id) => index.insert(Doc {
+            board_id: board_id.clone(),
+            post_id: post_id.clone(),
+            event_id: comment.event_id.clone(),
+            author: comment.author.user_id.clone(),
+            title: None,
+            body: comment.body.clone(),
+            origin_server_ts: comment.origin_server_ts,
+        }),
+        // the stream only passes on edits made by the author
+        Event::Edit {
+            event_id,
+            title,
+            body,
+            ..
+        } => index.edit(event_id, None, title.clone(), body.clone()),
+        Event::Redaction { event_id, .. } => index.remove(event_id),
+        _ => return false,
+    }
+
+    true
+}
+
+/// Rebuilds the index from the history of every world-readable board and
+/// saves it, returning the amount of posts and comments indexed. A running
+/// server loads the result the next time it would save.
+pub async fn reindex() -> Result<usize> {
+    let admin_token = commune().config.matrix.admin_token.inner();
+    let mut index = Index::new();
+
+    for board_id in world_readable_boards().await? {
+        tracing::info!(%board_id, "indexing board");
+
+        let mut events = Vec::new();
+        let mut from = None;
+
+        loop {
+            let mut req = messages::Request::new(board_id.clone(), Direction::Backward);
+            req.from = from;
+            req.limit = Some(500);
+            req.filter = json!({ "types": ["m.room.message"] }).to_string();
+
+            let messages::Response { chunk, end, .. } = commune()
+                .send_matrix_request(req, Some(&admin_token))
+                .await?;
+
+            if chunk.is_empty() {
+                break;
+            }
+
+            events.extend(chunk);
+
+            match end {
+                Some(end) => from = Some(end),
+                None => break,
+            }
+        }
+
+        // oldest first, so edits apply to what they replace
+        for event in events.iter().rev() {
+            replay(&mut index, &board_id, event);
+        }
+    }
+
+    if let Some(path) = &commune().config.search.index {
+        index.save(path).map_err(anyhow::Error::from)?;
+    }
+
+    let count = index.docs.len();
+    *INDEX.write().unwrap() = index;
+
+    Ok(count)
+}
+
+fn replay(index: &mut Index, board_id: &RoomId, event: &Raw<AnyTimelineEvent>) {
+    #[derive(Deserialize)]
+    struct Message {
+        event_id: OwnedEventId,
+
+        sender: OwnedUserId,
+
+        origin_server_ts: MilliSecondsSinceUnixEpoch,
+
+        content: Content,
+    }
+
+    #[derive(Deserialize)]
+    struct Content {
+        #[serde(default)]
+        body: String,
+
+        #[serde(rename = "m.relates_to")]
+        relates_to: Option<Relation>,
+
+        #[serde(rename = "m.new_content")]
+        new_content: Option<NewContent>,
+    }
+
+    #[derive(Deserialize)]
+    struct Relation {
+        rel_type: Option<RelationType>,
+
+        event_id: Option<OwnedEventId>,
+    }
+
+    if let Some(post) = PostEvent::from_raw(event) {
+        return index.insert(Doc {
+            board_id: board_id.to_owned(),
+            post_id: post.event_id.clone(),
+            event_id: post.event_id,
+            author: post.sender,
+            title: Some(post.content.post.title),
+            body: post.content.body,
+            origin_server_ts: post.origin_server_ts,
+        });
+    }
+
+    if event.get_field::<TimelineEventType>("type").ok().flatten()
+        != Some(TimelineEventType::RoomMessage)
+    {
+        return;
+    }
+
+    let Ok(message) = event.deserialize_as::<Message>() else {
+        return;
+    };
+
+    let Some(Relation {
+        rel_type: Some(rel_type),
+        event_id: Some(target),
+    }) = message.content.relates_to
+    else {
+        return;
+    };
+
+    match rel_type {
+        RelationType::Thread => index.insert(Doc {
+            board_id: board_id.to_owned(),
+            post_id: target,
+            event_id: message.event_id,
+            author: message.sender,
+            title: None,
+            body: message.content.body,
+            origin_server_ts: message.origin_server_ts,
+        }),
+        RelationType::Replacement => {
+            if let Some(new_content) = message.content.new_content {
+                index.edit(
+                    &target,
+                    Some(&message.sender),
+                    new_content.post.map(|post| post.title),
+                    new_content.body,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn world_readable_boards() -> Result<BTreeSet<OwnedRoomId>> {
+    Ok(space::boards()
+        .await?
+        .into_iter()
+        .filter(|room| {
+            auth::readers(room.join_rules.as_ref(), room.history_visibility.as_ref())
+                == auth::Readers::Everyone
+        })
+        .map(|room| room.room_id)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::{owned_room_id, owned_user_id};
+
+    use super::*;
+
+    fn doc(event_id: &str, board_id: OwnedRoomId, title: &str, body: &str) -> Doc {
+        Doc {
+            board_id,
+            post_id: EventId::parse(event_id).unwrap(),
+            event_id: EventId::parse(event_id).unwrap(),
+            author: owned_user_id!("@alice:example.com"),
+            title: Some(title.to_owned()),
+            body: body.to_owned(),
+            origin_server_ts: MilliSecondsSinceUnixEpoch(event_id.len().try_into().unwrap()),
+        }
+    }
+
+    fn ids(results: (Vec<(Doc, f64)>, usize)) -> Vec<String> {
+        results
+            .0
+            .into_iter()
+            .map(|(doc, _)| doc.event_id.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn matches_words_and_phrases() {
+        let rust = owned_room_id!("!rust:example.com");
+        let go = owned_room_id!("!go:example.com");
+        let boards = BTreeSet::from([rust.clone()]);
+
+        let mut index = Index::new();
+        index.insert(doc(
+            "$a",
+            rust.clone(),
+            "Borrow checker",
+            "fighting the borrow checker",
+        ));
+        index.insert(doc(
+            "$b",
+            rust.clone(),
+            "Checker",
+            "borrow it, then the checker",
+        ));
+        index.insert(doc(
+            "$c",
+            go.clone(),
+            "Borrow checker",
+            "borrow checker in go?",
+        ));
+
+        let search =
+            |index: &Index, query| ids(index.search(query, &boards, None, Order::Rank, 0, 10));
+
+        assert_eq!(search(&index, "borrow checker"), ["$a", "$b"]);
+        assert_eq!(search(&index, "\"borrow checker\""), ["$a"]);
+        // phrases do not span the title and the body
+        assert!(search(&index, "\"checker borrow\"").is_empty());
+
+        index.edit(
+            &EventId::parse("$a").unwrap(),
+            Some(&owned_user_id!("@mallory:example.com")),
+            None,
+            "vandalized".to_owned(),
+        );
+        assert_eq!(search(&index, "\"borrow checker\""), ["$a"]);
+
+        index.remove(&EventId::parse("$a").unwrap());
+        assert!(search(&index, "\"borrow checker\"").is_empty());
+        assert_eq!(
+            ids(index.search(
+                "borrow",
+                &boards,
+                Some(&owned_user_id!("@bob:example.com")),
+                Order::Recent,
+                0,
+                10
+            )),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn forgets_boards_that_became_private() {
+        let public = owned_room_id!("!public:example.com");
+        let private = owned_room_id!("!private:example.com");
+        let boards = BTreeSet::from([public.clone(), private.clone()]);
+
+        let mut index = Index::new();
+        index.insert(doc("$a", public.clone(), "Release notes", "shipped"));
+        index.insert(doc("$b", private.clone(), "Release plans", "secret"));
+
+        let remaining = BTreeSet::from([public]);
+        assert!(index.retain(&remaining));
+        assert!(!index.retain(&remaining));
+
+        // gone even for searches still scoped to the private board
+        assert_eq!(
+            ids(index.search("release", &boards, None, Order::Rank, 0, 10)),
+            ["$a"]
+        );
+        assert!(ids(index.search("secret", &boards, None, Order::Rank, 0, 10)).is_empty());
+    }
+}
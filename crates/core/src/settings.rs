@@ -0,0 +1,47 @@
+//! The settings of a board or space are its state events, which are read and
+//! written with their typed `ruma_events` contents.
+
+use matrix::{
+    ruma_common::OwnedMxcUri,
+    ruma_events::room::{
+        guest_access::GuestAccess, history_visibility::HistoryVisibility, join_rules::JoinRule,
+        join_rules::RoomJoinRulesEventContent,
+    },
+};
+use serde::Serialize;
+
+pub mod get;
+pub mod update;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Settings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<OwnedMxcUri>,
+
+    pub join_rules: RoomJoinRulesEventContent,
+
+    pub history_visibility: HistoryVisibility,
+
+    pub guest_access: GuestAccess,
+}
+
+/// Rooms lacking a state event fall back to the values the specification
+/// assumes in that case.
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            name: None,
+            topic: None,
+            avatar: None,
+            join_rules: RoomJoinRulesEventContent::new(JoinRule::Invite),
+            history_visibility: HistoryVisibility::Shared,
+            guest_access: GuestAccess::Forbidden,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use matrix::{
+    client::state::list::*,
+    ruma_common::{serde::Raw, OwnedRoomId},
+    ruma_events::{
+        room::{
+            avatar::RoomAvatarEventContent, guest_access::RoomGuestAccessEventContent,
+            history_visibility::RoomHistoryVisibilityEventContent,
+            join_rules::RoomJoinRulesEventContent, name::RoomNameEventContent,
+            topic::RoomTopicEventContent,
+        },
+        AnyStateEvent, StateEventType,
+    },
+};
+use serde::{de::DeserializeOwned, Deserialize};
+
+use super::Settings;
+use crate::{commune, error::Result, util::auth};
+
+#[derive(Deserialize)]
+struct StateEvent<C> {
+    content: C,
+}
+
+/// State that fails to deserialize, such as a removed avatar, is ignored.
+fn content<C: DeserializeOwned>(event: &Raw<AnyStateEvent>) -> Option<C> {
+    event
+        .deserialize_as::<StateEvent<C>>()
+        .ok()
+        .map(|event| event.content)
+}
+
+pub async fn service(access_token: Option<&str>, room_id: OwnedRoomId) -> Result<Settings> {
+    let token = auth::read_access_token(access_token, &room_id).await?;
+
+    let req = Request::new(room_id);
+    let Response { state, .. } = commune().send_matrix_request(req, Some(&token)).await?;
+
+    let mut settings = Settings::default();
+
+    for event in &state {
+        match event.get_field::<StateEventType>("type")? {
+            Some(StateEventType::RoomName) => {
+                settings.name = content::<RoomNameEventContent>(event).map(|c| c.name);
+            }
+            Some(StateEventType::RoomTopic) => {
+                settings.topic = content::<RoomTopicEventContent>(event).map(|c| c.topic);
+            }
+            Some(StateEventType::RoomAvatar) => {
+                settings.avatar = content::<RoomAvatarEventContent>(event).and_then(|c| c.url);
+            }
+            Some(StateEventType::RoomJoinRules) => {
+                if let Some(join_rules) = content::<RoomJoinRulesEventContent>(event) {
+                    settings.join_rules = join_rules;
+                }
+            }
+            Some(StateEventType::RoomHistoryVisibility) => {
+                if let Some(c) = content::<RoomHistoryVisibilityEventContent>(event) {
+                    settings.history_visibility = c.history_visibility;
+                }
+            }
+            Some(StateEventType::RoomGuestAccess) => {
+                if let Some(c) = content::<RoomGuestAccessEventContent>(event) {
+                    settings.guest_access = c.guest_access;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(settings)
+}
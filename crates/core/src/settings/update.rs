@@ -0,0 +1,106 @@
+use matrix::{
+    client::state::send,
+    ruma_common::{serde::Raw, OwnedMxcUri, OwnedRoomId},
+    ruma_events::{
+        room::{
+            avatar::RoomAvatarEventContent,
+            guest_access::{GuestAccess, RoomGuestAccessEventContent},
+            history_visibility::{HistoryVisibility, RoomHistoryVisibilityEventContent},
+            join_rules::RoomJoinRulesEventContent,
+            name::RoomNameEventContent,
+            topic::RoomTopicEventContent,
+        },
+        StateEventContent, StateEventType,
+    },
+};
+use serde_json::Value;
+
+use super::Settings;
+use crate::{
+    commune,
+    error::{Error, Result},
+    util::auth,
+};
+
+/// Omitted fields are left untouched.
+#[derive(Clone, Debug, Default)]
+pub struct Changes {
+    pub name: Option<String>,
+
+    pub topic: Option<String>,
+
+    pub avatar: Option<OwnedMxcUri>,
+
+    pub join_rules: Option<RoomJoinRulesEventContent>,
+
+    pub history_visibility: Option<HistoryVisibility>,
+
+    pub guest_access: Option<GuestAccess>,
+}
+
+/// Every change is checked against the power levels before any state is
+/// sent, so a refused change does not leave the room half updated.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    room_id: OwnedRoomId,
+    changes: Changes,
+) -> Result<Settings> {
+    let access_token = access_token.as_ref();
+
+    let user_id = auth::user_id(access_token).await?;
+    let power_levels = auth::power_levels(access_token, &room_id).await?;
+
+    let mut contents = Vec::new();
+
+    fn push<C: StateEventContent>(
+        contents: &mut Vec<(StateEventType, Value)>,
+        content: Option<C>,
+    ) -> Result<()> {
+        if let Some(content) = content {
+            contents.push((content.event_type(), serde_json::to_value(content)?));
+        }
+
+        Ok(())
+    }
+
+    push(&mut contents, changes.name.map(RoomNameEventContent::new))?;
+    push(&mut contents, changes.topic.map(RoomTopicEventContent::new))?;
+    push(
+        &mut contents,
+        changes.avatar.map(|url| {
+            let mut content = RoomAvatarEventContent::new();
+            content.url = Some(url);
+
+            content
+        }),
+    )?;
+    push(&mut contents, changes.join_rules)?;
+    push(
+        &mut contents,
+        changes
+            .history_visibility
+            .map(RoomHistoryVisibilityEventContent::new),
+    )?;
+    push(
+        &mut contents,
+        changes.guest_access.map(RoomGuestAccessEventContent::new),
+    )?;
+
+    if contents
+        .iter()
+        .any(|(event_type, _)| !power_levels.user_can_send_state(&user_id, event_type.clone()))
+    {
+        return Err(Error::Forbidden);
+    }
+
+    for (event_type, content) in contents {
+        let req =
+            send::Request::new_raw(room_id.clone(), event_type, "", Raw::new(&content)?.cast());
+
+        commune()
+            .send_matrix_request(req, Some(access_token))
+            .await?;
+    }
+
+    super::get::service(Some(access_token), room_id).await
+}
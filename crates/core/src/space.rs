@@ -0,0 +1,129 @@
+use std::{cmp::Ordering, collections::BTreeSet};
+
+use matrix::{
+    admin::room::{get_rooms, get_state, Room},
+    client::state::list,
+    ruma_common::{
+        api::Direction, room::RoomType, MilliSecondsSinceUnixEpoch, OwnedRoomId, RoomId,
+    },
+    ruma_events::{space::child::SpaceChildEventContent, StateEventType},
+};
+use serde::Deserialize;
+
+use crate::{commune, error::Result};
+
+pub mod board;
+pub mod create;
+pub mod hierarchy;
+
+/// Orders the children of a space as described by the specification: children
+/// with an `order` come first, the rest is sorted by the time they were added
+/// to the space, with the room ID as tiebreaker.
+pub(crate) fn cmp_children(
+    (a_order, a_ts, a_id): (&Option<String>, MilliSecondsSinceUnixEpoch, &RoomId),
+    (b_order, b_ts, b_id): (&Option<String>, MilliSecondsSinceUnixEpoch, &RoomId),
+) -> Ordering {
+    a_order
+        .is_none()
+        .cmp(&b_order.is_none())
+        .then_with(|| a_order.cmp(b_order))
+        .then_with(|| a_ts.cmp(&b_ts))
+        .then_with(|| a_id.cmp(b_id))
+}
+
+/// The rooms linked as children of a space, which is empty for other rooms.
+/// Removed children have their content emptied, these are skipped.
+pub(crate) async fn children(access_token: &str, room_id: &RoomId) -> Result<Vec<OwnedRoomId>> {
+    #[derive(Deserialize)]
+    struct Child {
+        state_key: OwnedRoomId,
+
+        content: SpaceChildEventContent,
+    }
+
+    let req = list::Request::new(room_id.to_owned());
+    let list::Response { state, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    Ok(state
+        .iter()
+        .filter(|event| {
+            event.get_field::<StateEventType>("type").ok().flatten()
+                == Some(StateEventType::SpaceChild)
+        })
+        .filter_map(|event| event.deserialize_as::<Child>().ok())
+        .filter(|child| !child.content.via.is_empty())
+        .map(|child| child.state_key)
+        .collect())
+}
+
+/// The rooms of the homeserver linked as children of one of its spaces, read
+/// through the administrator account. Other rooms are not boards, whatever
+/// state they hold.
+pub(crate) async fn boards() -> Result<Vec<Room>> {
+    let admin_token = commune().config.matrix.admin_token.inner();
+
+    let mut spaces = Vec::new();
+    let mut rooms = Vec::new();
+    let mut from = 0;
+
+    loop {
+        let mut req = get_rooms::Request::new(get_rooms::OrderBy::Name, Direction::Forward);
+        req.from = from;
+        req.limit = Some(500);
+
+        let get_rooms::Response {
+            rooms: page,
+            next_batch,
+            ..
+        } = commune()
+            .send_matrix_request(req, Some(&admin_token))
+            .await?;
+
+        for room in page {
+            match room.room_type {
+                Some(RoomType::Space) => spaces.push(room.room_id),
+                _ => rooms.push(room),
+            }
+        }
+
+        match next_batch.and_then(|next_batch| next_batch.parse().ok()) {
+            Some(next_batch) => from = next_batch,
+            None => break,
+        }
+    }
+
+    let mut linked = BTreeSet::new();
+
+    for space_id in spaces {
+        let req = get_state::Request::new(space_id.clone());
+
+        let state = match commune().send_matrix_request(req, Some(&admin_token)).await {
+            Ok(get_state::Response { state, .. }) => state,
+            Err(e) => {
+                tracing::debug!(?e, %space_id, "failed to read children of space");
+
+                continue;
+            }
+        };
+
+        // removed children have their content emptied
+        linked.extend(
+            state
+                .iter()
+                .filter(|event| event.kind == "m.space.child")
+                .filter(|event| {
+                    event
+                        .content
+                        .deserialize_as::<SpaceChildEventContent>()
+                        .is_ok_and(|child| !child.via.is_empty())
+                })
+                .filter_map(|event| OwnedRoomId::try_from(event.state_key.as_str()).ok()),
+        );
+    }
+
+    rooms.retain(|room| linked.contains(&room.room_id));
+
+    Ok(rooms)
+}
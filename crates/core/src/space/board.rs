@@ -0,0 +1,81 @@
+//! Boards are the rooms of a space in which discussions take place. They are
+//! linked to their space through `m.space.child` and `m.space.parent` state.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#spaces
+
+use matrix::ruma_common::{space::SpaceRoomJoinRule, OwnedMxcUri, OwnedRoomId};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+pub mod archive;
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod relink;
+pub mod update;
+
+/// State event sent in the space, keyed by the ID of the archived board.
+pub const ARCHIVED_EVENT_TYPE: &str = "sh.commune.board.archived";
+
+/// Who may join a board, and with that read it. The content of private
+/// boards is only ever served to their members.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Access {
+    #[default]
+    Public,
+
+    /// Members of the space may join.
+    Restricted,
+
+    /// Only invited users may join.
+    Invite,
+}
+
+impl From<Option<&SpaceRoomJoinRule>> for Access {
+    fn from(join_rule: Option<&SpaceRoomJoinRule>) -> Self {
+        match join_rule {
+            Some(SpaceRoomJoinRule::Public) => Self::Public,
+            Some(SpaceRoomJoinRule::Restricted | SpaceRoomJoinRule::KnockRestricted) => {
+                Self::Restricted
+            }
+            _ => Self::Invite,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Board {
+    pub room_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<OwnedMxcUri>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+
+    pub archived: bool,
+
+    pub access: Access,
+
+    /// Default boards are joined along with the space.
+    pub default: bool,
+
+    pub joined_members: u64,
+}
+
+/// The specification only allows `order` to contain up to 50 characters in the
+/// range `\x20` to `\x7E`.
+pub(crate) fn validate_order(order: &str) -> Result<()> {
+    match order.len() <= 50 && order.chars().all(|c| (' '..='~').contains(&c)) {
+        true => Ok(()),
+        false => Err(Error::InvalidOrder),
+    }
+}
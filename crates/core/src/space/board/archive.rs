@@ -0,0 +1,48 @@
+use matrix::{
+    client::state::{get, send},
+    ruma_common::{serde::Raw, OwnedRoomId},
+    ruma_events::{room::power_levels::RoomPowerLevelsEventContent, StateEventType},
+};
+use serde_json::{json, value::to_raw_value};
+
+use crate::{commune, error::Result};
+
+/// Archiving makes a board read-only for everyone but its administrators and
+/// marks it as such in the space, so listings can tell it apart.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    space_id: OwnedRoomId,
+    board_id: OwnedRoomId,
+) -> Result<()> {
+    let req = get::Request::new(
+        board_id.clone(),
+        StateEventType::RoomPowerLevels,
+        String::new(),
+    );
+
+    let get::Response { content, .. } = commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await?;
+
+    let mut power_levels = content.deserialize_as::<RoomPowerLevelsEventContent>()?;
+    power_levels.events_default = 100.into();
+
+    let req = send::Request::new(board_id.clone(), "", &power_levels)?;
+
+    commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await?;
+
+    let req = send::Request::new_raw(
+        space_id,
+        super::ARCHIVED_EVENT_TYPE.into(),
+        board_id.as_str(),
+        Raw::from_json(to_raw_value(&json!({ "archived": true }))?),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use matrix::{
+    client::{create_room::*, state::send},
+    ruma_common::OwnedRoomId,
+    ruma_events::{
+        room::join_rules::{AllowRule, RoomJoinRulesEventContent},
+        space::{child::SpaceChildEventContent, parent::SpaceParentEventContent},
+        InitialStateEvent,
+    },
+};
+
+use super::Access;
+use crate::{commune, error::Result};
+
+pub async fn service(
+    access_token: impl AsRef<str>,
+    space_id: OwnedRoomId,
+    name: impl Into<String>,
+    topic: Option<String>,
+    order: Option<String>,
+    default: bool,
+    access: Access,
+) -> Result<Response> {
+    if let Some(order) = order.as_deref() {
+        super::validate_order(order)?;
+    }
+
+    let via = vec![commune().config.matrix.server_name.clone()];
+
+    let mut parent = SpaceParentEventContent::new(via.clone());
+    parent.canonical = true;
+
+    let mut req = Request::new();
+
+    req.name = Some(name.into());
+    req.topic = topic;
+    req.preset = Some(match access {
+        Access::Public => RoomPreset::PublicChat,
+        Access::Restricted | Access::Invite => RoomPreset::PrivateChat,
+    });
+    req.initial_state = vec![InitialStateEvent {
+        content: parent,
+        state_key: space_id.clone(),
+    }
+    .to_raw_any()];
+
+    if access == Access::Restricted {
+        req.initial_state.push(
+            InitialStateEvent {
+                content: RoomJoinRulesEventContent::restricted(vec![AllowRule::room_membership(
+                    space_id.clone(),
+                )]),
+                state_key: Default::default(),
+            }
+            .to_raw_any(),
+        );
+    }
+
+    let resp = commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await?;
+
+    let mut child = SpaceChildEventContent::new(via);
+    child.order = order;
+    child.suggested = default;
+
+    let req = send::Request::new(space_id, resp.room_id.as_str(), &child)?;
+
+    commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await?;
+
+    Ok(resp)
+}
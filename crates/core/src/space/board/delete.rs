@@ -0,0 +1,61 @@
+use matrix::{
+    admin::room::delete_room,
+    client::state::send,
+    ruma_common::{serde::Raw, OwnedRoomId},
+    ruma_events::StateEventType,
+};
+use serde_json::{json, value::to_raw_value};
+
+use crate::{
+    commune,
+    error::{Error, Result},
+    space,
+    util::auth,
+};
+
+/// Only moderators of the board can delete it, and only through a space it
+/// belongs to, since the room is purged with the administrator account.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    space_id: OwnedRoomId,
+    board_id: OwnedRoomId,
+) -> Result<delete_room::Response> {
+    let access_token = access_token.as_ref();
+    let admin_token = commune().config.matrix.admin_token.inner();
+
+    if !space::children(access_token, &space_id)
+        .await?
+        .contains(&board_id)
+    {
+        return Err(Error::Forbidden);
+    }
+
+    let user_id = auth::user_id(access_token).await?;
+
+    if !auth::admin_power_levels(&board_id)
+        .await?
+        .user_can_redact(&user_id)
+    {
+        return Err(Error::Forbidden);
+    }
+
+    // unlinking the board with the caller's credentials ensures they are
+    // allowed to manage the space before we purge the room as an administrator
+    let req = send::Request::new_raw(
+        space_id,
+        StateEventType::SpaceChild,
+        board_id.as_str(),
+        Raw::from_json(to_raw_value(&json!({}))?),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let req = delete_room::Request::new(board_id, false, true);
+
+    commune()
+        .send_matrix_request(req, Some(&admin_token))
+        .await
+        .map_err(Into::into)
+}
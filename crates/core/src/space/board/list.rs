@@ -0,0 +1,143 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use matrix::{
+    admin::room::{get_room, RoomDetails},
+    client::state::list,
+    ruma_common::{MilliSecondsSinceUnixEpoch, OwnedRoomId},
+    ruma_events::{space::child::SpaceChildEventContent, StateEventType},
+};
+use serde::Deserialize;
+
+use super::Board;
+use crate::{
+    commune,
+    error::Result,
+    membership,
+    util::{auth, upgrade},
+};
+
+#[derive(Deserialize)]
+struct StateEvent<C> {
+    state_key: String,
+
+    content: C,
+
+    origin_server_ts: MilliSecondsSinceUnixEpoch,
+}
+
+#[derive(Deserialize)]
+struct Archived {
+    #[serde(default)]
+    archived: bool,
+}
+
+/// Private boards are only listed for their members. Boards that were
+/// upgraded are listed as the room that replaced them, moderators link the
+/// space to it with [`super::relink`].
+pub async fn service(access_token: impl AsRef<str>, space_id: OwnedRoomId) -> Result<Vec<Board>> {
+    let req = list::Request::new(space_id.clone());
+
+    let list::Response { state, .. } = commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await?;
+
+    let mut children = BTreeMap::new();
+    let mut archived = BTreeSet::new();
+
+    for event in state {
+        match event.get_field::<StateEventType>("type")? {
+            Some(StateEventType::SpaceChild) => {
+                // removed children have their content emptied so we skip
+                // the ones that fail to deserialize or lack a `via`
+                let Ok(StateEvent::<SpaceChildEventContent> {
+                    state_key,
+                    content,
+                    origin_server_ts,
+                }) = event.deserialize_as()
+                else {
+                    continue;
+                };
+
+                if let Ok(room_id) = OwnedRoomId::try_from(state_key) {
+                    if !content.via.is_empty() {
+                        let _ = children.insert(
+                            room_id,
+                            (content.order, content.suggested, origin_server_ts),
+                        );
+                    }
+                }
+            }
+            Some(kind) if kind.to_string() == super::ARCHIVED_EVENT_TYPE => {
+                let StateEvent::<Archived> {
+                    state_key, content, ..
+                } = event.deserialize_as()?;
+
+                if content.archived {
+                    let _ = archived.insert(state_key);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut children: Vec<_> = children.into_iter().collect();
+
+    children.sort_by(|(a_id, (a_order, _, a_ts)), (b_id, (b_order, _, b_ts))| {
+        crate::space::cmp_children((a_order, *a_ts, a_id), (b_order, *b_ts, b_id))
+    });
+
+    let admin_token = commune().config.matrix.admin_token.inner();
+    let mut boards = Vec::with_capacity(children.len());
+    let mut listed = BTreeSet::new();
+
+    for (child_id, (order, default, _)) in children {
+        // one unreachable board should not hide the others
+        let room_id = match upgrade::latest(&child_id).await {
+            Ok(room_id) => room_id,
+            Err(e) => {
+                tracing::debug!(?e, %child_id, "failed to follow upgrades of board");
+
+                continue;
+            }
+        };
+
+        // a space linking both rooms of an upgrade lists the board once
+        if !listed.insert(room_id.clone()) {
+            continue;
+        }
+
+        let req = get_room::Request::new(room_id.clone());
+
+        let room = match commune().send_matrix_request(req, Some(&admin_token)).await {
+            Ok(get_room::Response { room, .. }) => room,
+            Err(e) => {
+                tracing::debug!(?e, %room_id, "failed to read board");
+
+                continue;
+            }
+        };
+
+        if auth::authorize(Some(access_token.as_ref()), &room)
+            .await
+            .is_err()
+        {
+            continue;
+        }
+
+        let joined_members = membership::joined_members(&room_id, room.joined_members).await;
+
+        boards.push(Board {
+            archived: archived.contains(room_id.as_str()) || archived.contains(child_id.as_str()),
+            access: room.join_rules.as_ref().into(),
+            room_id,
+            name: room.name,
+            topic: room.details.and_then(|RoomDetails { topic, .. }| topic),
+            avatar: room.avatar,
+            order,
+            default,
+            joined_members,
+        });
+    }
+
+    Ok(boards)
+}
@@ -0,0 +1,111 @@
+use matrix::{
+    client::state::{list, send},
+    ruma_common::{serde::Raw, OwnedRoomId, RoomId},
+    ruma_events::{space::child::SpaceChildEventContent, StateEventType},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, value::to_raw_value};
+
+use crate::{commune, error::Result, util::upgrade};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Relinked {
+    /// The room the space linked before.
+    pub old_id: OwnedRoomId,
+
+    pub room_id: OwnedRoomId,
+}
+
+/// Points the space at the rooms that replaced its upgraded boards, keeping
+/// their order, then unlinks the old ones. Requires the power to edit the
+/// children of the space.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    space_id: OwnedRoomId,
+) -> Result<Vec<Relinked>> {
+    #[derive(Deserialize)]
+    struct Child {
+        state_key: OwnedRoomId,
+
+        content: SpaceChildEventContent,
+    }
+
+    let access_token = access_token.as_ref();
+
+    let req = list::Request::new(space_id.clone());
+
+    let list::Response { state, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let children: Vec<_> = state
+        .iter()
+        .filter(|event| {
+            event.get_field::<StateEventType>("type").ok().flatten()
+                == Some(StateEventType::SpaceChild)
+        })
+        .filter_map(|event| event.deserialize_as::<Child>().ok())
+        .filter(|child| !child.content.via.is_empty())
+        .collect();
+
+    let mut relinked = Vec::new();
+
+    for Child { state_key, content } in children {
+        let room_id = match upgrade::latest(&state_key).await {
+            Ok(room_id) => room_id,
+            Err(e) => {
+                tracing::warn!(?e, child_id = %state_key, "failed to follow upgrades of board");
+
+                continue;
+            }
+        };
+
+        if room_id == state_key {
+            continue;
+        }
+
+        let mut child =
+            SpaceChildEventContent::new(vec![commune().config.matrix.server_name.clone()]);
+        child.order = content.order;
+        child.suggested = content.suggested;
+
+        relink(access_token, &space_id, &state_key, &room_id, child).await?;
+
+        tracing::info!(%space_id, old_id = %state_key, %room_id, "linked upgraded board");
+
+        relinked.push(Relinked {
+            old_id: state_key,
+            room_id,
+        });
+    }
+
+    Ok(relinked)
+}
+
+/// Links the new room first, so a failure never leaves the board unlinked.
+async fn relink(
+    access_token: &str,
+    space_id: &RoomId,
+    old_id: &RoomId,
+    new_id: &RoomId,
+    child: SpaceChildEventContent,
+) -> Result<()> {
+    let req = send::Request::new(space_id.to_owned(), new_id.as_str(), &child)?;
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    let req = send::Request::new_raw(
+        space_id.to_owned(),
+        StateEventType::SpaceChild,
+        old_id.as_str(),
+        Raw::from_json(to_raw_value(&json!({}))?),
+    );
+
+    commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    Ok(())
+}
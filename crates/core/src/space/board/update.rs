@@ -0,0 +1,61 @@
+use matrix::{
+    client::state::{get, send},
+    ruma_common::OwnedRoomId,
+    ruma_events::{
+        room::name::RoomNameEventContent, space::child::SpaceChildEventContent, StateEventType,
+    },
+};
+
+use crate::{commune, error::Result};
+
+pub async fn service(
+    access_token: impl AsRef<str>,
+    space_id: OwnedRoomId,
+    board_id: OwnedRoomId,
+    name: Option<String>,
+    order: Option<String>,
+    default: Option<bool>,
+) -> Result<()> {
+    if let Some(name) = name {
+        let req = send::Request::new(board_id.clone(), "", &RoomNameEventContent::new(name))?;
+
+        commune()
+            .send_matrix_request(req, Some(access_token.as_ref()))
+            .await?;
+    }
+
+    if let Some(order) = order.as_deref() {
+        super::validate_order(order)?;
+    }
+
+    if order.is_some() || default.is_some() {
+        let req = get::Request::new(
+            space_id.clone(),
+            StateEventType::SpaceChild,
+            board_id.to_string(),
+        );
+
+        let get::Response { content, .. } = commune()
+            .send_matrix_request(req, Some(access_token.as_ref()))
+            .await?;
+
+        // keep `via` intact, an empty order is used to reset it
+        let mut child = content.deserialize_as::<SpaceChildEventContent>()?;
+
+        if let Some(order) = order {
+            child.order = Some(order).filter(|order| !order.is_empty());
+        }
+
+        if let Some(default) = default {
+            child.suggested = default;
+        }
+
+        let req = send::Request::new(space_id, board_id.as_str(), &child)?;
+
+        commune()
+            .send_matrix_request(req, Some(access_token.as_ref()))
+            .await?;
+    }
+
+    Ok(())
+}
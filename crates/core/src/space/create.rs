@@ -0,0 +1,39 @@
+use matrix::{
+    client::create_room::*,
+    ruma_common::{room::RoomType, serde::Raw},
+};
+use serde_json::{json, value::to_raw_value};
+
+use crate::{commune, error::Result};
+
+pub async fn service(
+    access_token: impl AsRef<str>,
+    name: impl Into<String>,
+    topic: Option<String>,
+    public: bool,
+) -> Result<Response> {
+    let mut req = Request::new();
+
+    req.name = Some(name.into());
+    req.topic = topic;
+    req.creation_content = Some(CreationContent {
+        federate: true,
+        room_type: Some(RoomType::Space),
+    });
+
+    (req.preset, req.visibility) = match public {
+        true => (Some(RoomPreset::PublicChat), Visibility::Public),
+        false => (Some(RoomPreset::PrivateChat), Visibility::Private),
+    };
+
+    // spaces only hold state, so regular members should not be able to post,
+    // the override is merged with the defaults so other fields are kept
+    req.power_level_content_override = Some(Raw::from_json(to_raw_value(&json!({
+        "events_default": 100,
+    }))?));
+
+    commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await
+        .map_err(Into::into)
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeSet;
+
+use matrix::{
+    client::space::hierarchy::*,
+    ruma_common::{
+        room::RoomType, space::SpaceRoomJoinRule, OwnedMxcUri, OwnedRoomAliasId, OwnedRoomId,
+    },
+    ruma_events::room::history_visibility::HistoryVisibility,
+};
+use serde::Serialize;
+
+use crate::{commune, error::Result, membership, util::auth};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Room {
+    pub room_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_alias: Option<OwnedRoomAliasId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<OwnedMxcUri>,
+
+    pub num_joined_members: u64,
+
+    pub world_readable: bool,
+
+    pub join_rule: SpaceRoomJoinRule,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub room_type: Option<RoomType>,
+
+    /// Ordered IDs of the children, only the ones present in this response
+    /// or a later page are listed.
+    pub children: Vec<OwnedRoomId>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Hierarchy {
+    pub rooms: Vec<Room>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_batch: Option<String>,
+}
+
+/// Anonymous visitors are served through the administrator account, in which
+/// case only public, world-readable rooms are returned.
+pub async fn service(
+    access_token: Option<&str>,
+    space_id: OwnedRoomId,
+    from: Option<String>,
+    limit: Option<u64>,
+    max_depth: Option<u64>,
+) -> Result<Hierarchy> {
+    let token = auth::read_access_token(access_token, &space_id).await?;
+
+    let mut req = Request::new(space_id);
+    req.from = from;
+    req.limit = limit;
+    req.max_depth = max_depth;
+
+    let Response {
+        rooms, next_batch, ..
+    } = commune().send_matrix_request(req, Some(&token)).await?;
+
+    let rooms = match access_token {
+        Some(_) => rooms,
+        None => filter_world_readable(rooms),
+    };
+
+    let mut rooms: Vec<_> = rooms.into_iter().map(into_room).collect();
+
+    for room in &mut rooms {
+        room.num_joined_members =
+            membership::joined_members(&room.room_id, room.num_joined_members).await;
+    }
+
+    Ok(Hierarchy { rooms, next_batch })
+}
+
+fn anyone_can_read(room: &Chunk) -> bool {
+    let history_visibility = room
+        .world_readable
+        .then_some(&HistoryVisibility::WorldReadable);
+
+    auth::readers(Some(&room.join_rule), history_visibility) == auth::Readers::Everyone
+}
+
+/// Drops rooms that are private or not world-readable along with the links
+/// pointing to them.
+fn filter_world_readable(rooms: Vec<Chunk>) -> Vec<Chunk> {
+    let hidden: BTreeSet<_> = rooms
+        .iter()
+        .filter(|room| !anyone_can_read(room))
+        .map(|room| room.room_id.clone())
+        .collect();
+
+    rooms
+        .into_iter()
+        .filter(anyone_can_read)
+        .map(|mut room| {
+            room.children_state.retain(|event| {
+                event
+                    .get_field::<OwnedRoomId>("state_key")
+                    .ok()
+                    .flatten()
+                    .is_some_and(|room_id| !hidden.contains(&room_id))
+            });
+
+            room
+        })
+        .collect()
+}
+
+fn into_room(chunk: Chunk) -> Room {
+    let mut children: Vec<_> = chunk
+        .children_state
+        .iter()
+        .filter_map(|event| event.deserialize().ok())
+        .filter(|event| !event.content.via.is_empty())
+        .filter_map(|event| {
+            OwnedRoomId::try_from(event.state_key)
+                .ok()
+                .map(|room_id| (room_id, event.content.order, event.origin_server_ts))
+        })
+        .collect();
+
+    children.sort_by(|(a_id, a_order, a_ts), (b_id, b_order, b_ts)| {
+        super::cmp_children((a_order, *a_ts, a_id), (b_order, *b_ts, b_id))
+    });
+
+    Room {
+        room_id: chunk.room_id,
+        canonical_alias: chunk.canonical_alias,
+        name: chunk.name,
+        topic: chunk.topic,
+        avatar_url: chunk.avatar_url,
+        num_joined_members: chunk.num_joined_members,
+        world_readable: chunk.world_readable,
+        join_rule: chunk.join_rule,
+        room_type: chunk.room_type,
+        children: children.into_iter().map(|(room_id, ..)| room_id).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn chunk(room_id: &str, world_readable: bool, children: &[&str]) -> Chunk {
+        private_chunk(room_id, world_readable, "public", children)
+    }
+
+    fn private_chunk(
+        room_id: &str,
+        world_readable: bool,
+        join_rule: &str,
+        children: &[&str],
+    ) -> Chunk {
+        serde_json::from_value(json!({
+            "room_id": room_id,
+            "num_joined_members": 1,
+            "world_readable": world_readable,
+            "join_rule": join_rule,
+            "guest_can_join": false,
+            "children_state": children.iter().map(|child| json!({
+                "type": "m.space.child",
+                "state_key": child,
+                "sender": "@alice:example.com",
+                "origin_server_ts": 1,
+                "content": { "via": ["example.com"] },
+            })).collect::<Vec<_>>(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn hides_rooms_that_are_not_world_readable() {
+        let rooms = filter_world_readable(vec![
+            chunk(
+                "!space:example.com",
+                true,
+                &["!public:example.com", "!private:example.com"],
+            ),
+            chunk("!public:example.com", true, &[]),
+            chunk("!private:example.com", false, &[]),
+        ]);
+
+        let rooms: Vec<_> = rooms.into_iter().map(into_room).collect();
+
+        assert_eq!(rooms.len(), 2);
+        assert_eq!(rooms[0].children, ["!public:example.com"]);
+    }
+
+    #[test]
+    fn hides_private_rooms_even_if_world_readable() {
+        let rooms = filter_world_readable(vec![
+            chunk(
+                "!space:example.com",
+                true,
+                &["!invite:example.com", "!restricted:example.com"],
+            ),
+            private_chunk("!invite:example.com", true, "invite", &[]),
+            private_chunk("!restricted:example.com", true, "restricted", &[]),
+        ]);
+
+        let rooms: Vec<_> = rooms.into_iter().map(into_room).collect();
+
+        assert_eq!(rooms.len(), 1);
+        assert!(rooms[0].children.is_empty());
+    }
+}
@@ -0,0 +1,874 @@
+//! Live events for boards and threads. A single `/sync` loop runs with the
+//! admin account, which joins the boards someone subscribes to, and fans the
+//! new posts, comments, edits, removals and vote changes out to every
+//! subscriber. Private boards do not let the admin account in, those are
+//! polled through the admin API instead. The latest events are kept in
+//! memory, so clients can resume from the last one they saw after
+//! reconnecting.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::{Arc, Mutex, OnceLock},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use matrix::{
+    admin::room::{get_context, get_messages},
+    client::{membership::join, sync::v3},
+    ruma_common::{
+        api::Direction, serde::Raw, EventId, OwnedEventId, OwnedRoomId, OwnedUserId, RoomId,
+    },
+    ruma_events::{
+        relation::{InReplyTo, RelationType},
+        AnyTimelineEvent, TimelineEventType,
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::{
+    commune,
+    error::{Error, Result},
+    membership,
+    post::{self, comment::Comment, index, vote::Votes, NewContent, Post, PostEvent},
+    util::{auth, upgrade},
+};
+
+/// The amount of events kept to resume from.
+const BACKLOG: usize = 1024;
+
+/// The amount of posts and comments whose thread is remembered, to route
+/// edits and removals to thread subscribers.
+const ROOTS: usize = 16 * BACKLOG;
+
+const POLL_TIMEOUT: Duration = Duration::from_secs(30);
+
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// How often boards the admin account could not join are polled.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How often subscriptions check again that the subscriber may still read
+/// the boards, so those that became private or were left stop streaming.
+const RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+static HUB: OnceLock<Hub> = OnceLock::new();
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    Post {
+        post: Post,
+    },
+    Comment {
+        board_id: OwnedRoomId,
+        post_id: OwnedEventId,
+        /// The comment replied to, the post itself otherwise.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        parent: Option<OwnedEventId>,
+        comment: Comment,
+    },
+    Edit {
+        board_id: OwnedRoomId,
+        /// The post of the thread, if the edited event is known to be in one.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        post_id: Option<OwnedEventId>,
+        event_id: OwnedEventId,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        body: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        formatted_body: Option<String>,
+    },
+    Redaction {
+        board_id: OwnedRoomId,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        post_id: Option<OwnedEventId>,
+        event_id: OwnedEventId,
+    },
+    Votes {
+        board_id: OwnedRoomId,
+        post_id: OwnedEventId,
+        votes: Votes,
+    },
+    /// Events were missed, everything shown should be loaded again.
+    Reset,
+}
+
+impl Event {
+    fn board_id(&self) -> Option<&RoomId> {
+        match self {
+            Self::Post { post } => Some(&post.room_id),
+            Self::Comment { board_id, .. }
+            | Self::Edit { board_id, .. }
+            | Self::Redaction { board_id, .. }
+            | Self::Votes { board_id, .. } => Some(board_id),
+            Self::Reset => None,
+        }
+    }
+
+    fn post_id(&self) -> Option<&EventId> {
+        match self {
+            Self::Post { post } => Some(&post.event_id),
+            Self::Comment { post_id, .. } | Self::Votes { post_id, .. } => Some(post_id),
+            Self::Edit { post_id, .. } | Self::Redaction { post_id, .. } => post_id.as_deref(),
+            Self::Reset => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Item {
+    /// Passed back as the last event ID to resume after this event.
+    pub id: String,
+
+    pub event: Event,
+}
+
+pub(crate) type Entry = Arc<(u64, Event)>;
+
+struct Hub {
+    /// Cursors from before a restart cannot be resumed from.
+    boot: u64,
+
+    sender: broadcast::Sender<Entry>,
+
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    seq: u64,
+
+    backlog: VecDeque<Entry>,
+
+    boards: BTreeSet<OwnedRoomId>,
+
+    votes: BTreeMap<OwnedRoomId, BTreeMap<OwnedEventId, Votes>>,
+
+    roots: BTreeMap<OwnedEventId, OwnedEventId>,
+
+    order: VecDeque<OwnedEventId>,
+}
+
+fn hub() -> &'static Hub {
+    HUB.get_or_init(|| {
+        tokio::spawn(run());
+
+        Hub {
+            boot: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+            sender: broadcast::channel(BACKLOG).0,
+            state: Mutex::new(State::default()),
+        }
+    })
+}
+
+/// Follows the board in the sync loop without subscribing to it.
+pub(crate) async fn watch(board_id: &RoomId) -> Result<()> {
+    hub().watch(board_id).await
+}
+
+/// Every event published from now on, for all watched boards.
+pub(crate) fn events() -> broadcast::Receiver<Entry> {
+    hub().sender.subscribe()
+}
+
+impl Hub {
+    fn cursor(&self, seq: u64) -> String {
+        format!("{}_{seq}", self.boot)
+    }
+
+    fn publish(&self, event: Event) {
+        let mut state = self.state.lock().unwrap();
+
+        match &event {
+            Event::Post { post } => state.remember(post.event_id.clone(), post.event_id.clone()),
+            Event::Comment {
+                post_id, comment, ..
+            } => state.remember(comment.event_id.clone(), post_id.clone()),
+            _ => {}
+        }
+
+        state.seq += 1;
+
+        let entry = Arc::new((state.seq, event));
+
+        if state.backlog.len() == BACKLOG {
+            let _ = state.backlog.pop_front();
+        }
+        state.backlog.push_back(entry.clone());
+
+        // fails when no one is listening, which is fine
+        let _ = self.sender.send(entry);
+    }
+
+    fn root(&self, event_id: &EventId) -> Option<OwnedEventId> {
+        self.state.lock().unwrap().roots.get(event_id).cloned()
+    }
+
+    fn watching(&self, board_id: &RoomId) -> bool {
+        self.state.lock().unwrap().boards.contains(board_id)
+    }
+
+    /// Joins the admin account to the board, so the sync loop receives its
+    /// events. Boards it cannot join are polled instead.
+    async fn watch(&self, board_id: &RoomId) -> Result<()> {
+        if self.watching(board_id) {
+            return Ok(());
+        }
+
+        let admin_token = commune().config.matrix.admin_token.inner();
+        let req = join::Request::new(board_id.to_owned().into());
+
+        let joined = match commune().send_matrix_request(req, Some(&admin_token)).await {
+            Ok(_) => {
+                membership::forget_admin_rooms();
+
+                true
+            }
+            Err(e) => {
+                tracing::debug!(?e, %board_id, "polling board the admin account cannot join");
+
+                false
+            }
+        };
+
+        let votes = tally(board_id).await?;
+
+        {
+            let mut state = self.state.lock().unwrap();
+
+            // a concurrent watch got here first
+            if !state.boards.insert(board_id.to_owned()) {
+                return Ok(());
+            }
+
+            let _ = state.votes.insert(board_id.to_owned(), votes);
+        }
+
+        if !joined {
+            tokio::spawn(poll(board_id.to_owned()));
+        }
+
+        Ok(())
+    }
+
+    /// Publishes the vote counts that changed since they were last seen.
+    fn votes(&self, board_id: &RoomId, votes: BTreeMap<OwnedEventId, Votes>) {
+        let previous = {
+            let mut state = self.state.lock().unwrap();
+
+            state
+                .votes
+                .insert(board_id.to_owned(), votes.clone())
+                .unwrap_or_default()
+        };
+
+        for (post_id, votes) in votes {
+            if previous.get(&post_id).copied().unwrap_or_default() != votes {
+                self.publish(Event::Votes {
+                    board_id: board_id.to_owned(),
+                    post_id,
+                    votes,
+                });
+            }
+        }
+    }
+}
+
+impl State {
+    fn remember(&mut self, event_id: OwnedEventId, root: OwnedEventId) {
+        if self.order.len() == ROOTS {
+            if let Some(oldest) = self.order.pop_front() {
+                let _ = self.roots.remove(&oldest);
+            }
+        }
+
+        self.order.push_back(event_id.clone());
+        let _ = self.roots.insert(event_id, root);
+    }
+}
+
+pub struct Subscription {
+    backlog: VecDeque<Entry>,
+
+    receiver: broadcast::Receiver<Entry>,
+
+    boards: BTreeSet<OwnedRoomId>,
+
+    /// Keyed by board too, so a thread cannot be followed into a board that
+    /// was not checked.
+    threads: BTreeSet<(OwnedRoomId, OwnedEventId)>,
+
+    access_token: Option<String>,
+
+    checked: Instant,
+
+    last: u64,
+
+    reset: bool,
+}
+
+impl Subscription {
+    /// Waits for the next event matching the subscription, `None` once the
+    /// sync loop is gone.
+    pub async fn next(&mut self) -> Option<Item> {
+        if std::mem::take(&mut self.reset) {
+            return Some(self.item(Event::Reset));
+        }
+
+        self.recheck().await;
+
+        if let Some(entry) = self.backlog.pop_front() {
+            self.last = entry.0;
+
+            return Some(self.item(entry.1.clone()));
+        }
+
+        loop {
+            let entry = self.receiver.recv().await;
+
+            self.recheck().await;
+
+            match entry {
+                Ok(entry) if entry.0 > self.last && self.matches(&entry.1) => {
+                    self.last = entry.0;
+
+                    return Some(self.item(entry.1.clone()));
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(_)) => return Some(self.item(Event::Reset)),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    fn matches(&self, event: &Event) -> bool {
+        let Some(board_id) = event.board_id() else {
+            return false;
+        };
+
+        self.boards.contains(board_id)
+            || event.post_id().is_some_and(|post_id| {
+                self.threads
+                    .contains(&(board_id.to_owned(), post_id.to_owned()))
+            })
+    }
+
+    /// Drops the boards and threads the subscriber may no longer read. Boards
+    /// that cannot be checked are dropped as well.
+    async fn recheck(&mut self) {
+        if self.checked.elapsed() < RECHECK_INTERVAL {
+            return;
+        }
+
+        let boards: BTreeSet<_> = self
+            .boards
+            .iter()
+            .chain(self.threads.iter().map(|(board_id, _)| board_id))
+            .cloned()
+            .collect();
+
+        for board_id in boards {
+            if let Err(e) = auth::read_access_token(self.access_token.as_deref(), &board_id).await {
+                tracing::debug!(?e, %board_id, "no longer streaming board");
+
+                let _ = self.boards.remove(&board_id);
+                self.threads
+                    .retain(|(thread_board, _)| *thread_board != board_id);
+            }
+        }
+
+        self.checked = Instant::now();
+    }
+
+    fn item(&self, event: Event) -> Item {
+        Item {
+            id: hub().cursor(self.last),
+            event,
+        }
+    }
+}
+
+/// Subscribes to whole boards and to single threads, given as the board and
+/// post. Boards that were upgraded are followed in the room that replaced
+/// them, threads stay in the room of their post. Passing the ID of the last
+/// event received replays the ones missed since, or starts with
+/// [`Event::Reset`] if they are no longer known.
+pub async fn service(
+    access_token: Option<&str>,
+    boards: Vec<OwnedRoomId>,
+    threads: Vec<(OwnedRoomId, OwnedEventId)>,
+    last_event_id: Option<String>,
+) -> Result<Subscription> {
+    let hub = hub();
+
+    let mut latest = Vec::with_capacity(boards.len());
+
+    for board_id in boards {
+        latest.push(upgrade::latest(&board_id).await?);
+    }
+
+    let boards = latest;
+
+    for board_id in boards
+        .iter()
+        .chain(threads.iter().map(|(board_id, _)| board_id))
+    {
+        auth::read_access_token(access_token, board_id).await?;
+
+        hub.watch(board_id).await?;
+    }
+
+    let last = match last_event_id {
+        Some(cursor) => {
+            let (boot, seq) = cursor.split_once('_').ok_or(Error::InvalidCursor)?;
+            let boot = boot.parse::<u64>().map_err(|_| Error::InvalidCursor)?;
+            let seq = seq.parse::<u64>().map_err(|_| Error::InvalidCursor)?;
+
+            Some((boot, seq))
+        }
+        None => None,
+    };
+
+    let mut subscription = Subscription {
+        backlog: VecDeque::new(),
+        receiver: hub.sender.subscribe(),
+        boards: boards.into_iter().collect(),
+        threads: threads.into_iter().collect(),
+        access_token: access_token.map(ToOwned::to_owned),
+        checked: Instant::now(),
+        last: 0,
+        reset: false,
+    };
+
+    let state = hub.state.lock().unwrap();
+
+    subscription.last = state.seq;
+
+    if let Some((boot, seq)) = last {
+        let oldest = state.backlog.front().map_or(state.seq + 1, |entry| entry.0);
+
+        if boot != hub.boot || seq + 1 < oldest {
+            subscription.reset = true;
+        } else {
+            subscription.backlog = state
+                .backlog
+                .iter()
+                .filter(|entry| entry.0 > seq && subscription.matches(&entry.1))
+                .cloned()
+                .collect();
+        }
+    }
+
+    Ok(subscription)
+}
+
+async fn run() {
+    let admin_token = commune().config.matrix.admin_token.inner();
+
+    let filter = json!({
+        "presence": { "types": [] },
+        "account_data": { "types": [] },
+        "room": {
+            "state": { "types": [] },
+            "ephemeral": { "types": [] },
+            "account_data": { "types": [] },
+            "timeline": {
+                "limit": 100,
+                "types": ["m.room.message", "m.reaction", "m.room.redaction"],
+            },
+        },
+    })
+    .to_string();
+
+    let mut since = None;
+
+    loop {
+        let mut req = v3::Request::new();
+        req.filter = filter.clone();
+        req.since = since.clone();
+        req.timeout = since.as_ref().map(|_| POLL_TIMEOUT);
+
+        let v3::Response {
+            next_batch, rooms, ..
+        } = match commune().send_matrix_request(req, Some(&admin_token)).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                tracing::warn!(?e, "failed to sync events to stream");
+
+                tokio::time::sleep(RETRY_DELAY).await;
+
+                continue;
+            }
+        };
+
+        // the initial sync only serves as a starting point
+        if since.is_some() {
+            for (room_id, room) in rooms.join {
+                if !hub().watching(&room_id) {
+                    continue;
+                }
+
+                let mut reactions = false;
+
+                for event in room.timeline.events {
+                    reactions |= dispatch(&admin_token, &room_id, &event.cast()).await;
+                }
+
+                if reactions {
+                    match tally(&room_id).await {
+                        Ok(votes) => hub().votes(&room_id, votes),
+                        Err(e) => tracing::warn!(?e, %room_id, "failed to count votes"),
+                    }
+                }
+            }
+        }
+
+        since = Some(next_batch);
+    }
+}
+
+/// Follows a board the admin account is not part of, paging forward through
+/// its timeline with the admin API.
+async fn poll(board_id: OwnedRoomId) {
+    let admin_token = commune().config.matrix.admin_token.inner();
+
+    let filter = json!({
+        "types": ["m.room.message", "m.reaction", "m.room.redaction"],
+    })
+    .to_string();
+
+    // paging backwards without a token starts at the latest event
+    let mut from = loop {
+        let mut req = get_messages::Request::new(board_id.clone(), Direction::Backward);
+        req.limit = Some(1);
+
+        match commune().send_matrix_request(req, Some(&admin_token)).await {
+            Ok(get_messages::Response { start, .. }) => break start,
+            Err(e) => {
+                tracing::warn!(?e, %board_id, "failed to poll board for stream");
+
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+        }
+    };
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let mut req = get_messages::Request::new(board_id.clone(), Direction::Forward);
+        req.from = Some(from.clone());
+        req.limit = Some(100);
+        req.filter = filter.clone();
+
+        let get_messages::Response { chunk, end, .. } =
+            match commune().send_matrix_request(req, Some(&admin_token)).await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    tracing::warn!(?e, %board_id, "failed to poll board for stream");
+
+                    continue;
+                }
+            };
+
+        let mut reactions = false;
+
+        for event in &chunk {
+            reactions |= dispatch(&admin_token, &board_id, event).await;
+        }
+
+        if reactions {
+            match tally(&board_id).await {
+                Ok(votes) => hub().votes(&board_id, votes),
+                Err(e) => tracing::warn!(?e, %board_id, "failed to count votes"),
+            }
+        }
+
+        if let Some(end) = end {
+            from = end;
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Relation {
+    rel_type: Option<RelationType>,
+
+    event_id: Option<OwnedEventId>,
+
+    #[serde(rename = "m.in_reply_to")]
+    in_reply_to: Option<InReplyTo>,
+
+    #[serde(default)]
+    is_falling_back: bool,
+}
+
+/// Publishes the event if it is a post, comment, edit or removal. Returns
+/// whether the votes of the board may have changed.
+async fn dispatch(admin_token: &str, board_id: &RoomId, event: &Raw<AnyTimelineEvent>) -> bool {
+    #[derive(Deserialize)]
+    struct Message {
+        event_id: OwnedEventId,
+
+        sender: OwnedUserId,
+
+        content: MessageContent,
+    }
+
+    #[derive(Deserialize)]
+    struct MessageContent {
+        #[serde(rename = "m.relates_to")]
+        relates_to: Option<Relation>,
+
+        #[serde(rename = "m.new_content")]
+        new_content: Option<NewContent>,
+    }
+
+    #[derive(Deserialize)]
+    struct Redaction {
+        redacts: Option<OwnedEventId>,
+
+        content: RedactionContent,
+    }
+
+    #[derive(Deserialize)]
+    struct RedactionContent {
+        redacts: Option<OwnedEventId>,
+    }
+
+    match event.get_field::<TimelineEventType>("type").ok().flatten() {
+        Some(TimelineEventType::RoomMessage) => {
+            if let Some(event) = PostEvent::from_raw(event) {
+                let author = post::author(event.sender.clone()).await;
+
+                hub().publish(Event::Post {
+                    post: event.into_post(board_id.to_owned(), author),
+                });
+
+                return false;
+            }
+
+            let Ok(Message {
+                event_id,
+                sender,
+                content,
+            }) = event.deserialize_as::<Message>()
+            else {
+                return false;
+            };
+
+            let Some(Relation {
+                rel_type: Some(rel_type),
+                event_id: Some(target),
+                in_reply_to,
+                is_falling_back,
+            }) = content.relates_to
+            else {
+                return false;
+            };
+
+            match rel_type {
+                RelationType::Thread => {
+                    let Some(event) = post::comment::CommentEvent::from_raw(&event.clone().cast())
+                    else {
+                        return false;
+                    };
+
+                    let author = post::author(sender).await;
+                    let (body, formatted) = (&event.content.body, &event.content.formatted);
+
+                    hub().publish(Event::Comment {
+                        board_id: board_id.to_owned(),
+                        post_id: target,
+                        parent: in_reply_to
+                            .filter(|_| !is_falling_back)
+                            .map(|in_reply_to| in_reply_to.event_id),
+                        comment: Comment {
+                            event_id,
+                            author,
+                            body: body.clone(),
+                            formatted_body: formatted.sanitized(),
+                            origin_server_ts: event.origin_server_ts,
+                            reply_count: 0,
+                            replies: Vec::new(),
+                            edited: false,
+                            removed: false,
+                            more: None,
+                        },
+                    });
+                }
+                RelationType::Replacement => {
+                    let Some(new_content) = content.new_content else {
+                        return false;
+                    };
+
+                    // edits only count when made by the author
+                    let Some(original) = original(admin_token, board_id, &target).await else {
+                        return false;
+                    };
+
+                    if original.sender != sender {
+                        return false;
+                    }
+
+                    hub().publish(Event::Edit {
+                        board_id: board_id.to_owned(),
+                        post_id: hub().root(&target).or(original.root),
+                        event_id: target,
+                        title: new_content.post.map(|post| post.title),
+                        body: new_content.body,
+                        formatted_body: new_content.formatted.sanitized(),
+                    });
+                }
+                _ => {}
+            }
+
+            false
+        }
+        Some(TimelineEventType::Reaction) => true,
+        Some(TimelineEventType::RoomRedaction) => {
+            // rooms from version 11 onwards moved `redacts` into the content
+            let Some(redacts) = event
+                .deserialize_as::<Redaction>()
+                .ok()
+                .and_then(|redaction| redaction.redacts.or(redaction.content.redacts))
+            else {
+                return false;
+            };
+
+            let kind = original(admin_token, board_id, &redacts)
+                .await
+                .map(|original| original.kind);
+
+            if kind != Some(TimelineEventType::Reaction) {
+                hub().publish(Event::Redaction {
+                    board_id: board_id.to_owned(),
+                    post_id: hub().root(&redacts),
+                    event_id: redacts,
+                });
+            }
+
+            true
+        }
+        _ => false,
+    }
+}
+
+struct Original {
+    kind: TimelineEventType,
+
+    sender: OwnedUserId,
+
+    /// The post of the thread for comments, the event itself for posts.
+    root: Option<OwnedEventId>,
+}
+
+/// Looks up the event an edit or removal targets, through the admin API so
+/// it works for boards the admin account is not part of. Removed events keep
+/// their type and sender, but lose their relations.
+async fn original(admin_token: &str, board_id: &RoomId, event_id: &EventId) -> Option<Original> {
+    #[derive(Deserialize)]
+    struct Target {
+        #[serde(rename = "type")]
+        kind: TimelineEventType,
+
+        sender: OwnedUserId,
+
+        content: TargetContent,
+    }
+
+    #[derive(Deserialize)]
+    struct TargetContent {
+        #[serde(rename = "m.relates_to")]
+        relates_to: Option<Relation>,
+    }
+
+    let req = get_context::Request::new(board_id.to_owned(), event_id.to_owned());
+
+    let get_context::Response { event, .. } =
+        match commune().send_matrix_request(req, Some(admin_token)).await {
+            Ok(resp) => resp,
+            Err(e) => {
+                tracing::debug!(?e, %event_id, "failed to look up event");
+
+                return None;
+            }
+        };
+
+    let Target {
+        kind,
+        sender,
+        content,
+    } = event.deserialize_as().ok()?;
+
+    let root = match content.relates_to {
+        Some(Relation {
+            rel_type: Some(RelationType::Thread),
+            event_id,
+            ..
+        }) => event_id,
+        Some(_) => None,
+        None => Some(event_id.to_owned()),
+    };
+
+    Some(Original { kind, sender, root })
+}
+
+async fn tally(board_id: &RoomId) -> Result<BTreeMap<OwnedEventId, Votes>> {
+    Ok(index::sync(board_id)
+        .await?
+        .into_iter()
+        .map(|(post_id, tally)| (post_id, tally.votes))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::{owned_event_id, owned_room_id};
+
+    use super::*;
+
+    #[test]
+    fn routes_events_to_subscribers() {
+        let subscription = Subscription {
+            backlog: VecDeque::new(),
+            receiver: broadcast::channel(1).1,
+            boards: BTreeSet::from([owned_room_id!("!rust:example.com")]),
+            threads: BTreeSet::from([(
+                owned_room_id!("!go:example.com"),
+                owned_event_id!("$thread"),
+            )]),
+            access_token: None,
+            checked: Instant::now(),
+            last: 0,
+            reset: false,
+        };
+
+        let redaction = |board_id: OwnedRoomId, post_id: Option<OwnedEventId>| Event::Redaction {
+            board_id,
+            post_id,
+            event_id: owned_event_id!("$comment"),
+        };
+
+        assert!(subscription.matches(&redaction(owned_room_id!("!rust:example.com"), None)));
+        assert!(subscription.matches(&redaction(
+            owned_room_id!("!go:example.com"),
+            Some(owned_event_id!("$thread"))
+        )));
+        assert!(!subscription.matches(&redaction(
+            owned_room_id!("!go:example.com"),
+            Some(owned_event_id!("$other"))
+        )));
+        // the same post ID in a board that was not checked, such as a private
+        // one, does not reach thread subscribers
+        assert!(!subscription.matches(&redaction(
+            owned_room_id!("!private:example.com"),
+            Some(owned_event_id!("$thread"))
+        )));
+        assert!(!subscription.matches(&Event::Reset));
+    }
+}
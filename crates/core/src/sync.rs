@@ -0,0 +1,343 @@
+//! Live updates through sliding sync (MSC3575). Every user keeps one
+//! upstream connection per `conn_id`, which remembers the position and the
+//! windows requested last. Clients only send what changed and get the board
+//! lists and timelines back as differences, instead of polling each endpoint.
+//!
+//! reference: https://github.com/matrix-org/matrix-spec-proposals/pull/3575
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use http::StatusCode;
+use matrix::{
+    client::sync::{
+        Request, Response, RoomDetailsConfig, RoomSubscription, SlidingSyncRoom, SyncList,
+        SyncRequestList, SyncRequestListFilters,
+    },
+    ruma_client::Error::FromHttpResponse,
+    ruma_common::{
+        api::error::{FromHttpResponseError, MatrixError},
+        OwnedMxcUri, OwnedRoomId, OwnedUserId,
+    },
+    ruma_events::{StateEventType, TimelineEventType},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commune,
+    error::Result,
+    post::{self, index, Post, PostEvent},
+    util::auth,
+};
+
+pub const BOARDS_LIST: &str = "boards";
+pub const SPACES_LIST: &str = "spaces";
+
+/// Connections unused for this long are dropped, the next request on them
+/// starts over with a complete response.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+pub const MAX_TIMEOUT: Duration = Duration::from_secs(30);
+
+const DEFAULT_TIMELINE_LIMIT: usize = 10;
+
+type Connections = BTreeMap<(OwnedUserId, String), (Arc<tokio::sync::Mutex<Connection>>, Instant)>;
+
+static CONNECTIONS: Mutex<Connections> = Mutex::new(BTreeMap::new());
+
+#[derive(Default)]
+struct Connection {
+    access_token: String,
+
+    pos: Option<String>,
+
+    boards: Vec<(usize, usize)>,
+
+    spaces: Vec<(usize, usize)>,
+
+    subscriptions: BTreeSet<OwnedRoomId>,
+
+    timeline_limit: Option<usize>,
+}
+
+/// Changes to the windows of a connection. Omitted fields keep the value
+/// sent last.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Window {
+    /// Ranges over the boards the user joined, most recently active first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boards: Option<Vec<(usize, usize)>>,
+
+    /// Ranges over the spaces the user joined, most recently active first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spaces: Option<Vec<(usize, usize)>>,
+
+    /// Boards to follow whether or not they are inside a range.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subscribe: Vec<OwnedRoomId>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unsubscribe: Vec<OwnedRoomId>,
+
+    /// The number of posts to include for boards entering a window.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeline_limit: Option<usize>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Update {
+    /// To be sent with the next request, omitting or changing it restarts
+    /// the connection.
+    pub pos: String,
+
+    /// Whether this update replaces everything the client knew.
+    pub initial: bool,
+
+    /// Keyed by [`BOARDS_LIST`] and [`SPACES_LIST`].
+    pub lists: BTreeMap<String, SyncList>,
+
+    pub rooms: BTreeMap<OwnedRoomId, RoomUpdate>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RoomUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<OwnedMxcUri>,
+
+    /// Whether the room was not sent on this connection before, the posts
+    /// then replace the ones the client has.
+    pub initial: bool,
+
+    /// New posts, newest first.
+    pub posts: Vec<Post>,
+
+    /// Whether posts are missing between these and the ones the client has.
+    pub limited: bool,
+
+    /// Pages back through the rest of the board with the post listing.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_batch: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_count: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight_count: Option<usize>,
+}
+
+/// Waits up to `timeout` for changes on the connection `conn_id`.
+pub async fn service(
+    access_token: impl AsRef<str>,
+    conn_id: String,
+    pos: Option<String>,
+    window: Window,
+    timeout: Duration,
+) -> Result<Update> {
+    let access_token = access_token.as_ref();
+    let user_id = auth::user_id(access_token).await?;
+
+    let connection = {
+        let mut connections = CONNECTIONS.lock().unwrap();
+
+        connections.retain(|_, (_, used)| used.elapsed() < IDLE_TIMEOUT);
+
+        let (connection, used) = connections
+            .entry((user_id, conn_id.clone()))
+            .or_insert_with(|| (Default::default(), Instant::now()));
+        *used = Instant::now();
+
+        connection.clone()
+    };
+
+    let mut connection = connection.lock().await;
+
+    // Upstream positions belong to a device, a client that lost track of its
+    // position or logged in again starts from scratch.
+    if connection.access_token != access_token || connection.pos != pos {
+        connection.access_token = access_token.to_owned();
+        connection.pos = None;
+    }
+
+    if let Some(boards) = window.boards {
+        connection.boards = boards;
+    }
+
+    if let Some(spaces) = window.spaces {
+        connection.spaces = spaces;
+    }
+
+    if window.timeline_limit.is_some() {
+        connection.timeline_limit = window.timeline_limit;
+    }
+
+    for room_id in &window.unsubscribe {
+        let _ = connection.subscriptions.remove(room_id);
+    }
+
+    for room_id in &window.subscribe {
+        let _ = auth::read_access_token(Some(access_token), room_id).await?;
+    }
+
+    connection.subscriptions.extend(window.subscribe);
+
+    let timeout = timeout.min(MAX_TIMEOUT);
+
+    let resp = match sync(&connection, &conn_id, &window.unsubscribe, timeout).await {
+        // Expired positions are refused as a bad request.
+        Err(FromHttpResponse(FromHttpResponseError::Server(MatrixError {
+            status_code: StatusCode::BAD_REQUEST,
+            ..
+        }))) if connection.pos.is_some() => {
+            connection.pos = None;
+
+            sync(&connection, &conn_id, &window.unsubscribe, timeout).await?
+        }
+        resp => resp?,
+    };
+
+    connection.pos = Some(resp.pos.clone());
+
+    let mut rooms = BTreeMap::new();
+
+    for (room_id, room) in resp.rooms {
+        let update = room_update(room_id.clone(), room).await?;
+
+        let _ = rooms.insert(room_id, update);
+    }
+
+    Ok(Update {
+        pos: resp.pos,
+        initial: resp.initial,
+        lists: resp.lists,
+        rooms,
+    })
+}
+
+async fn sync(
+    connection: &Connection,
+    conn_id: &str,
+    unsubscribe: &[OwnedRoomId],
+    timeout: Duration,
+) -> std::result::Result<Response, matrix::HandleError> {
+    let timeline_limit = connection.timeline_limit.or(Some(DEFAULT_TIMELINE_LIMIT));
+    let required_state = vec![
+        (StateEventType::RoomName, String::new()),
+        (StateEventType::RoomAvatar, String::new()),
+        (StateEventType::RoomMember, "$LAZY".to_owned()),
+    ];
+
+    let boards = SyncRequestList {
+        ranges: connection.boards.clone(),
+        sort: vec!["by_recency".to_owned()],
+        room_details: RoomDetailsConfig {
+            required_state: required_state.clone(),
+            timeline_limit,
+        },
+        filters: Some(SyncRequestListFilters {
+            is_dm: Some(false),
+            is_invite: Some(false),
+            not_room_types: vec!["m.space".to_owned()],
+            ..Default::default()
+        }),
+        bump_event_types: vec![TimelineEventType::RoomMessage],
+        ..Default::default()
+    };
+
+    let spaces = SyncRequestList {
+        ranges: connection.spaces.clone(),
+        sort: vec!["by_recency".to_owned()],
+        room_details: RoomDetailsConfig {
+            required_state: required_state.clone(),
+            timeline_limit: Some(0),
+        },
+        filters: Some(SyncRequestListFilters {
+            is_invite: Some(false),
+            room_types: vec!["m.space".to_owned()],
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let mut req = Request::default();
+    req.pos = connection.pos.clone();
+    req.conn_id = Some(conn_id.to_owned());
+    req.timeout = Some(timeout);
+    req.lists = BTreeMap::from([
+        (BOARDS_LIST.to_owned(), boards),
+        (SPACES_LIST.to_owned(), spaces),
+    ]);
+    req.room_subscriptions = connection
+        .subscriptions
+        .iter()
+        .map(|room_id| {
+            (
+                room_id.clone(),
+                RoomSubscription {
+                    required_state: required_state.clone(),
+                    timeline_limit,
+                },
+            )
+        })
+        .collect();
+    req.unsubscribe_rooms = unsubscribe.to_vec();
+
+    commune()
+        .send_matrix_request(req, Some(&connection.access_token))
+        .await
+}
+
+/// Keeps the posts of the timeline, comments and other events are left to
+/// the dedicated endpoints.
+async fn room_update(room_id: OwnedRoomId, room: SlidingSyncRoom) -> Result<RoomUpdate> {
+    let state = room
+        .required_state
+        .iter()
+        .map(|event| event.clone().cast())
+        .collect::<Vec<_>>();
+    let authors = post::authors_from_state(&state);
+
+    let events = room
+        .timeline
+        .iter()
+        .rev()
+        .filter_map(|event| PostEvent::from_raw(&event.clone().cast()))
+        .collect::<Vec<_>>();
+
+    let mut posts = Vec::with_capacity(events.len());
+
+    if !events.is_empty() {
+        let tallies = index::sync(&room_id).await?;
+
+        for event in events {
+            let author = match authors.get(&event.sender) {
+                Some(author) => author.clone(),
+                None => post::author(event.sender.clone()).await,
+            };
+
+            let mut post = event.into_post(room_id.clone(), author);
+
+            if let Some(tally) = tallies.get(&post.event_id) {
+                post.votes = tally.votes;
+            }
+
+            posts.push(post);
+        }
+    }
+
+    Ok(RoomUpdate {
+        name: room.name,
+        avatar: room.avatar,
+        initial: room.initial.unwrap_or_default(),
+        posts,
+        limited: room.limited,
+        prev_batch: room.prev_batch,
+        notification_count: room.unread_notifications.notification_count,
+        highlight_count: room.unread_notifications.highlight_count,
+    })
+}
@@ -1 +1,3 @@
+pub mod auth;
 pub mod secret;
+pub mod upgrade;
@@ -0,0 +1,210 @@
+use std::sync::OnceLock;
+
+use matrix::{
+    admin::{
+        room::{get_room, get_state, Room},
+        user::get_user,
+    },
+    client::{account::whoami, state},
+    ruma_common::{space::SpaceRoomJoinRule, OwnedUserId, RoomId, UserId},
+    ruma_events::{
+        room::{
+            history_visibility::HistoryVisibility,
+            member::MembershipState,
+            power_levels::{RoomPowerLevels, RoomPowerLevelsEventContent},
+        },
+        StateEventType,
+    },
+};
+
+use crate::{
+    commune,
+    error::{Error, Result},
+};
+
+/// Resolves the user an access token belongs to.
+pub async fn user_id(access_token: impl AsRef<str>) -> Result<OwnedUserId> {
+    let req = whoami::Request::new();
+
+    let whoami::Response { user_id, .. } = commune()
+        .send_matrix_request(req, Some(access_token.as_ref()))
+        .await?;
+
+    Ok(user_id)
+}
+
+/// The user of the administrator account, looked up once.
+pub(crate) async fn admin_id() -> Result<OwnedUserId> {
+    static ADMIN_ID: OnceLock<OwnedUserId> = OnceLock::new();
+
+    if let Some(admin_id) = ADMIN_ID.get() {
+        return Ok(admin_id.clone());
+    }
+
+    let admin_id = user_id(commune().config.matrix.admin_token.inner()).await?;
+
+    Ok(ADMIN_ID.get_or_init(|| admin_id).clone())
+}
+
+/// Ensures the user is an administrator of the homeserver.
+pub async fn ensure_admin(user_id: &UserId) -> Result<()> {
+    let req = get_user::Request::new(user_id.to_owned());
+
+    let get_user::Response { user, .. } = commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await?;
+
+    match user.admin {
+        true => Ok(()),
+        false => Err(Error::Forbidden),
+    }
+}
+
+/// Who the content of a room may be served to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Readers {
+    /// Anonymous visitors included, through the administrator account.
+    Everyone,
+
+    /// Signed in users, the homeserver applies the history visibility.
+    Users,
+
+    /// Joined members only, whatever the history visibility says.
+    Members,
+}
+
+/// Rooms that are not public are private: their content never leaves for
+/// anyone but their members. Rooms without join rules are invite-only.
+pub(crate) fn readers(
+    join_rule: Option<&SpaceRoomJoinRule>,
+    history_visibility: Option<&HistoryVisibility>,
+) -> Readers {
+    match (join_rule, history_visibility) {
+        (Some(SpaceRoomJoinRule::Public), Some(HistoryVisibility::WorldReadable)) => {
+            Readers::Everyone
+        }
+        (Some(SpaceRoomJoinRule::Public), _) => Readers::Users,
+        _ => Readers::Members,
+    }
+}
+
+/// The check every path serving the content of a room goes through, picking
+/// the access token used to read from it on behalf of the caller.
+pub(crate) async fn authorize(access_token: Option<&str>, room: &Room) -> Result<String> {
+    match (
+        readers(room.join_rules.as_ref(), room.history_visibility.as_ref()),
+        access_token,
+    ) {
+        (Readers::Everyone, None) => Ok(commune().config.matrix.admin_token.inner()),
+        (Readers::Everyone | Readers::Users, Some(access_token)) => Ok(access_token.to_owned()),
+        (Readers::Members, Some(access_token)) => {
+            let user_id = user_id(access_token).await?;
+
+            match crate::membership::membership(&room.room_id, &user_id).await? {
+                Some(MembershipState::Join) => Ok(access_token.to_owned()),
+                _ => Err(Error::Forbidden),
+            }
+        }
+        (Readers::Users | Readers::Members, None) => Err(Error::Forbidden),
+    }
+}
+
+/// Picks the access token used to read from a room on behalf of the caller.
+/// Anonymous visitors are served through the administrator account, but only
+/// for public rooms with world-readable history, and private rooms are kept
+/// to their members.
+pub async fn read_access_token(access_token: Option<&str>, room_id: &RoomId) -> Result<String> {
+    let req = get_room::Request::new(room_id.to_owned());
+
+    let get_room::Response { room, .. } = commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await?;
+
+    authorize(access_token, &room).await
+}
+
+/// Reads the power levels of a room on behalf of the caller.
+pub async fn power_levels(access_token: &str, room_id: &RoomId) -> Result<RoomPowerLevels> {
+    let req = state::get::Request::new(
+        room_id.to_owned(),
+        StateEventType::RoomPowerLevels,
+        String::new(),
+    );
+
+    let state::get::Response { content, .. } = commune()
+        .send_matrix_request(req, Some(access_token))
+        .await?;
+
+    Ok(content
+        .deserialize_as::<RoomPowerLevelsEventContent>()?
+        .into())
+}
+
+/// Reads the power levels of a room through the administrator account, for
+/// rooms the caller may not be able to see.
+pub(crate) async fn admin_power_levels(room_id: &RoomId) -> Result<RoomPowerLevels> {
+    let req = get_state::Request::new(room_id.to_owned());
+
+    let get_state::Response { state, .. } = commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await?;
+
+    let event = state
+        .into_iter()
+        .find(|event| event.kind == "m.room.power_levels" && event.state_key.is_empty())
+        .ok_or(Error::NotFound)?;
+
+    Ok(event
+        .content
+        .deserialize_as::<RoomPowerLevelsEventContent>()?
+        .into())
+}
+
+/// Moderators are the users allowed to redact the events of others.
+pub async fn ensure_moderator(
+    access_token: &str,
+    user_id: &UserId,
+    room_id: &RoomId,
+) -> Result<()> {
+    match power_levels(access_token, room_id)
+        .await?
+        .user_can_redact(user_id)
+    {
+        true => Ok(()),
+        false => Err(Error::Forbidden),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_private_rooms_to_members() {
+        let world_readable = Some(&HistoryVisibility::WorldReadable);
+        let shared = Some(&HistoryVisibility::Shared);
+
+        assert_eq!(
+            readers(Some(&SpaceRoomJoinRule::Public), world_readable),
+            Readers::Everyone
+        );
+        assert_eq!(
+            readers(Some(&SpaceRoomJoinRule::Public), shared),
+            Readers::Users
+        );
+
+        for join_rule in [
+            Some(SpaceRoomJoinRule::Invite),
+            Some(SpaceRoomJoinRule::Restricted),
+            Some(SpaceRoomJoinRule::Knock),
+            Some(SpaceRoomJoinRule::KnockRestricted),
+            None,
+        ] {
+            assert_eq!(
+                readers(join_rule.as_ref(), world_readable),
+                Readers::Members
+            );
+            assert_eq!(readers(join_rule.as_ref(), shared), Readers::Members);
+        }
+    }
+}
@@ -71,6 +71,6 @@ mod tests {
         let secret = Secret::new("secret");
         let value = secret.inner();
 
-        assert_eq!(value, "secret".into());
+        assert_eq!(value, "secret".to_owned());
     }
 }
@@ -0,0 +1,188 @@
+//! Upgrading a room replaces it with a new one. The old room is left with an
+//! `m.room.tombstone` pointing at its replacement, and the `m.room.create` of
+//! the new room names its predecessor. Boards are resolved to the room that
+//! replaced them last, and their history carries on into the ones before.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#room-upgrades
+
+use std::collections::BTreeSet;
+
+use matrix::{
+    admin::room::get_state::{self, State},
+    ruma_common::{OwnedRoomId, RoomId},
+};
+use serde::Deserialize;
+
+use crate::{commune, error::Result};
+
+/// Bounds the chain of upgrades followed, in case rooms point at each other.
+const MAX_UPGRADES: usize = 16;
+
+#[derive(Deserialize)]
+struct TombstoneContent {
+    replacement_room: OwnedRoomId,
+}
+
+#[derive(Deserialize)]
+struct CreateContent {
+    predecessor: Option<Predecessor>,
+}
+
+#[derive(Deserialize)]
+struct Predecessor {
+    room_id: OwnedRoomId,
+}
+
+/// Where the upgrades of a room lead, as told by its state.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Links {
+    replacement: Option<OwnedRoomId>,
+
+    predecessor: Option<OwnedRoomId>,
+}
+
+fn links(state: &[State]) -> Links {
+    let mut links = Links::default();
+
+    for event in state.iter().filter(|event| event.state_key.is_empty()) {
+        match event.kind.as_str() {
+            "m.room.tombstone" => {
+                links.replacement = event
+                    .content
+                    .deserialize_as::<TombstoneContent>()
+                    .ok()
+                    .map(|content| content.replacement_room);
+            }
+            "m.room.create" => {
+                links.predecessor = event
+                    .content
+                    .deserialize_as::<CreateContent>()
+                    .ok()
+                    .and_then(|content| content.predecessor)
+                    .map(|predecessor| predecessor.room_id);
+            }
+            _ => {}
+        }
+    }
+
+    links
+}
+
+/// Read through the administrator account, since the caller may not have
+/// joined the rooms of the chain yet.
+async fn fetch(room_id: &RoomId) -> Result<Links> {
+    let req = get_state::Request::new(room_id.to_owned());
+
+    let get_state::Response { state, .. } = commune()
+        .send_matrix_request(req, Some(&commune().config.matrix.admin_token.inner()))
+        .await?;
+
+    Ok(links(&state))
+}
+
+/// The room that replaced this one last, the room itself if it was never
+/// upgraded.
+pub(crate) async fn latest(room_id: &RoomId) -> Result<OwnedRoomId> {
+    let mut latest = room_id.to_owned();
+    let mut seen = BTreeSet::from([latest.clone()]);
+
+    for _ in 0..MAX_UPGRADES {
+        let Some(replacement) = fetch(&latest).await?.replacement else {
+            break;
+        };
+
+        if !seen.insert(replacement.clone()) {
+            break;
+        }
+
+        latest = replacement;
+    }
+
+    Ok(latest)
+}
+
+/// The rooms this one replaced, the most recent first. A predecessor only
+/// counts if its tombstone points back, so no room can claim the history of
+/// another it did not replace.
+pub(crate) async fn predecessors(room_id: &RoomId) -> Result<Vec<OwnedRoomId>> {
+    let mut predecessors = Vec::new();
+    let mut current = room_id.to_owned();
+    let mut links = fetch(&current).await?;
+
+    for _ in 0..MAX_UPGRADES {
+        let Some(predecessor) = links.predecessor.take() else {
+            break;
+        };
+
+        if predecessor == room_id || predecessors.contains(&predecessor) {
+            break;
+        }
+
+        links = match fetch(&predecessor).await {
+            Ok(links) => links,
+            Err(e) => {
+                tracing::debug!(?e, %predecessor, "failed to read predecessor");
+
+                break;
+            }
+        };
+
+        if links.replacement.as_ref() != Some(&current) {
+            break;
+        }
+
+        predecessors.push(predecessor.clone());
+        current = predecessor;
+    }
+
+    Ok(predecessors)
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::ruma_common::owned_room_id;
+    use serde_json::json;
+
+    use super::*;
+
+    fn state(kind: &str, content: serde_json::Value) -> State {
+        serde_json::from_value(json!({
+            "type": kind,
+            "state_key": "",
+            "sender": "@alice:example.com",
+            "content": content,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reads_links_from_state() {
+        let links = links(&[
+            state(
+                "m.room.create",
+                json!({
+                    "room_version": "10",
+                    "predecessor": { "room_id": "!v1:example.com", "event_id": "$tombstone" },
+                }),
+            ),
+            state(
+                "m.room.tombstone",
+                json!({ "body": "upgraded", "replacement_room": "!v3:example.com" }),
+            ),
+            state("m.room.name", json!({ "name": "general" })),
+        ]);
+
+        assert_eq!(
+            links,
+            Links {
+                replacement: Some(owned_room_id!("!v3:example.com")),
+                predecessor: Some(owned_room_id!("!v1:example.com")),
+            }
+        );
+
+        assert_eq!(
+            super::links(&[state("m.room.create", json!({ "room_version": "10" }))]),
+            Links::default()
+        );
+    }
+}
@@ -3,14 +3,17 @@
 //! reference: https://matrix-org.github.io/synapse/latest/admin_api/rooms.html
 
 use ruma_common::{
-    room::RoomType, EventEncryptionAlgorithm, OwnedMxcUri, OwnedRoomAliasId, OwnedRoomId,
-    OwnedUserId, RoomVersionId,
+    room::RoomType, space::SpaceRoomJoinRule, EventEncryptionAlgorithm, OwnedMxcUri,
+    OwnedRoomAliasId, OwnedRoomId, OwnedUserId, RoomVersionId,
 };
-use ruma_events::room::{history_visibility::HistoryVisibility, join_rules::JoinRule};
+use ruma_events::room::history_visibility::HistoryVisibility;
 use serde::Deserialize;
 
 pub mod delete_room;
+pub mod forward_extremities;
+pub mod get_context;
 pub mod get_members;
+pub mod get_messages;
 pub mod get_room;
 pub mod get_rooms;
 pub mod get_state;
@@ -39,7 +42,7 @@ pub struct Room {
 
     pub public: bool,
 
-    pub join_rules: Option<JoinRule>,
+    pub join_rules: Option<SpaceRoomJoinRule>,
 
     pub history_visibility: Option<HistoryVisibility>,
 
@@ -30,7 +30,20 @@ pub struct Request {
     pub force_purge: bool,
 }
 
+impl Request {
+    pub fn new(room_id: OwnedRoomId, block: bool, purge: bool) -> Self {
+        Self {
+            room_id,
+            new_room: None,
+            block,
+            purge,
+            force_purge: false,
+        }
+    }
+}
+
 #[response(error = crate::Error)]
+#[derive(Serialize)]
 pub struct Response {
     pub delete_id: String,
 }
@@ -0,0 +1,8 @@
+//! Forward extremities are the latest events of a room that no other event
+//! points to yet. Rooms with many of them get slow, since every new event has
+//! to resolve the state between all of them.
+//!
+//! reference: https://matrix-org.github.io/synapse/latest/admin_api/rooms.html#forward-extremities-admin-api
+
+pub mod delete;
+pub mod get;
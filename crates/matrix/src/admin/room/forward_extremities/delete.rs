@@ -0,0 +1,32 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedRoomId,
+};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: DELETE,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_synapse/admin/v1/rooms/:room_id/forward_extremities",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId) -> Self {
+        Self { room_id }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    /// The amount of extremities removed, all but the most recent ones.
+    pub deleted: u64,
+}
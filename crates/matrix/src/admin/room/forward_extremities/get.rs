@@ -0,0 +1,45 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, MilliSecondsSinceUnixEpoch, OwnedEventId, OwnedRoomId,
+};
+use serde::Deserialize;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_synapse/admin/v1/rooms/:room_id/forward_extremities",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId) -> Self {
+        Self { room_id }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    pub count: u64,
+
+    pub results: Vec<Extremity>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Extremity {
+    pub event_id: OwnedEventId,
+
+    pub state_group: Option<u64>,
+
+    pub depth: u64,
+
+    pub received_ts: MilliSecondsSinceUnixEpoch,
+}
@@ -0,0 +1,46 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedEventId, OwnedRoomId,
+};
+use ruma_events::AnyTimelineEvent;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_synapse/admin/v1/rooms/:room_id/context/:event_id",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[ruma_api(path)]
+    pub event_id: OwnedEventId,
+
+    /// The amount of events around the event to include.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub limit: Option<u64>,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId, event_id: OwnedEventId) -> Self {
+        Self {
+            room_id,
+            event_id,
+            limit: None,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    pub event: Raw<AnyTimelineEvent>,
+}
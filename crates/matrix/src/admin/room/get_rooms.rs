@@ -29,6 +29,7 @@ pub struct Request {
     #[ruma_api(query)]
     pub order_by: OrderBy,
 
+    #[serde(rename = "dir")]
     #[ruma_api(query)]
     pub direction: Direction,
 
@@ -37,6 +38,18 @@ pub struct Request {
     pub search_term: String,
 }
 
+impl Request {
+    pub fn new(order_by: OrderBy, direction: Direction) -> Self {
+        Self {
+            from: 0,
+            limit: None,
+            order_by,
+            direction,
+            search_term: String::new(),
+        }
+    }
+}
+
 #[response(error = crate::Error)]
 pub struct Response {
     pub rooms: Vec<Room>,
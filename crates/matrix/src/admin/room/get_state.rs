@@ -1,7 +1,10 @@
 use ruma_common::{
     api::{request, response, Metadata},
-    metadata, OwnedRoomId,
+    metadata,
+    serde::Raw,
+    OwnedRoomId, OwnedUserId,
 };
+use ruma_events::AnyStateEventContent;
 use serde::Deserialize;
 
 #[allow(dead_code)]
@@ -20,6 +23,12 @@ pub struct Request {
     pub room_id: OwnedRoomId,
 }
 
+impl Request {
+    pub fn new(room_id: OwnedRoomId) -> Self {
+        Self { room_id }
+    }
+}
+
 #[response(error = crate::Error)]
 pub struct Response {
     pub state: Vec<State>,
@@ -32,5 +41,7 @@ pub struct State {
 
     pub state_key: String,
 
-    pub etc: bool,
+    pub sender: OwnedUserId,
+
+    pub content: Raw<AnyStateEventContent>,
 }
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 pub mod get_user;
 pub mod get_user_by_3pid;
 pub mod get_users;
+pub mod login_as;
 pub mod set_user;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -21,6 +21,12 @@ pub struct Request {
     pub user_id: OwnedUserId,
 }
 
+impl Request {
+    pub fn new(user_id: OwnedUserId) -> Self {
+        Self { user_id }
+    }
+}
+
 #[response(error = crate::Error)]
 pub struct Response {
     #[ruma_api(body)]
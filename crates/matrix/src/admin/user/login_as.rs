@@ -0,0 +1,38 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, MilliSecondsSinceUnixEpoch, OwnedUserId,
+};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: POST,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_synapse/admin/v1/users/:user_id/login",
+    }
+};
+
+/// Gets an access token acting as the user, without a new device.
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub user_id: OwnedUserId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_until_ms: Option<MilliSecondsSinceUnixEpoch>,
+}
+
+impl Request {
+    pub fn new(user_id: OwnedUserId) -> Self {
+        Self {
+            user_id,
+            valid_until_ms: None,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    pub access_token: String,
+}
@@ -3,8 +3,23 @@
 //! reference: https://spec.matrix.org/unstable/client-server-api
 
 pub mod account;
+pub mod account_data;
+pub mod alias;
+pub mod create_room;
+pub mod event;
+pub mod keys;
 pub mod login;
 pub mod logout;
+pub mod membership;
+pub mod messages;
+pub mod notifications;
 pub mod profile;
+pub mod read_markers;
 pub mod register;
+pub mod relations;
+pub mod search;
+pub mod space;
+pub mod state;
+pub mod sync;
+pub mod to_device;
 pub mod uiaa;
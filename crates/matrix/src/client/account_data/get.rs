@@ -0,0 +1,41 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedUserId,
+};
+use ruma_events::{AnyGlobalAccountDataEventContent, GlobalAccountDataEventType};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/user/:user_id/account_data/:event_type",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub user_id: OwnedUserId,
+
+    #[ruma_api(path)]
+    pub event_type: GlobalAccountDataEventType,
+}
+
+impl Request {
+    pub fn new(user_id: OwnedUserId, event_type: GlobalAccountDataEventType) -> Self {
+        Self {
+            user_id,
+            event_type,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    #[ruma_api(body)]
+    pub account_data: Raw<AnyGlobalAccountDataEventContent>,
+}
@@ -0,0 +1,48 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedUserId,
+};
+use ruma_events::{AnyGlobalAccountDataEventContent, GlobalAccountDataEventType};
+use serde::Serialize;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: PUT,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/user/:user_id/account_data/:event_type",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub user_id: OwnedUserId,
+
+    #[ruma_api(path)]
+    pub event_type: GlobalAccountDataEventType,
+
+    #[ruma_api(body)]
+    pub data: Raw<AnyGlobalAccountDataEventContent>,
+}
+
+impl Request {
+    pub fn new(
+        user_id: OwnedUserId,
+        event_type: GlobalAccountDataEventType,
+        data: Raw<AnyGlobalAccountDataEventContent>,
+    ) -> Self {
+        Self {
+            user_id,
+            event_type,
+            data,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+#[derive(Serialize)]
+pub struct Response {}
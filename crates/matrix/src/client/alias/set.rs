@@ -0,0 +1,34 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedRoomAliasId, OwnedRoomId,
+};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: PUT,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/directory/room/:room_alias",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_alias: OwnedRoomAliasId,
+
+    pub room_id: OwnedRoomId,
+}
+
+impl Request {
+    pub fn new(room_alias: OwnedRoomAliasId, room_id: OwnedRoomId) -> Self {
+        Self {
+            room_alias,
+            room_id,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {}
@@ -0,0 +1,96 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    room::RoomType,
+    serde::Raw,
+    OwnedRoomAliasId, OwnedRoomId, OwnedUserId,
+};
+use ruma_events::{room::power_levels::RoomPowerLevelsEventContent, AnyInitialStateEvent};
+use serde::{Deserialize, Serialize};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: POST,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/createRoom",
+    }
+};
+
+#[request(error = crate::Error)]
+#[derive(Default)]
+pub struct Request {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creation_content: Option<CreationContent>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub initial_state: Vec<Raw<AnyInitialStateEvent>>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub invite: Vec<OwnedUserId>,
+
+    #[serde(skip_serializing_if = "ruma_common::serde::is_default")]
+    pub is_direct: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub power_level_content_override: Option<Raw<RoomPowerLevelsEventContent>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<RoomPreset>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub room_alias_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+
+    #[serde(skip_serializing_if = "ruma_common::serde::is_default")]
+    pub visibility: Visibility,
+}
+
+impl Request {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[response(error = crate::Error)]
+#[derive(Deserialize, Serialize)]
+pub struct Response {
+    pub room_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub room_alias: Option<OwnedRoomAliasId>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CreationContent {
+    #[serde(rename = "m.federate", default = "ruma_common::serde::default_true")]
+    pub federate: bool,
+
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub room_type: Option<RoomType>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoomPreset {
+    PrivateChat,
+
+    PublicChat,
+
+    TrustedPrivateChat,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    Public,
+
+    #[default]
+    Private,
+}
@@ -0,0 +1,38 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedEventId, OwnedRoomId,
+};
+use ruma_events::AnyTimelineEvent;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/rooms/:room_id/event/:event_id",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[ruma_api(path)]
+    pub event_id: OwnedEventId,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId, event_id: OwnedEventId) -> Self {
+        Self { room_id, event_id }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    #[ruma_api(body)]
+    pub event: Raw<AnyTimelineEvent>,
+}
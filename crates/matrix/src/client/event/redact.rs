@@ -0,0 +1,52 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedEventId, OwnedRoomId, OwnedTransactionId,
+};
+use serde::{Deserialize, Serialize};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: PUT,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/rooms/:room_id/redact/:event_id/:txn_id",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[ruma_api(path)]
+    pub event_id: OwnedEventId,
+
+    #[ruma_api(path)]
+    pub txn_id: OwnedTransactionId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Request {
+    pub fn new(
+        room_id: OwnedRoomId,
+        event_id: OwnedEventId,
+        txn_id: OwnedTransactionId,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            room_id,
+            event_id,
+            txn_id,
+            reason,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+#[derive(Deserialize, Serialize)]
+pub struct Response {
+    pub event_id: OwnedEventId,
+}
@@ -0,0 +1,72 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedEventId, OwnedRoomId, OwnedTransactionId,
+};
+use ruma_events::{AnyMessageLikeEventContent, MessageLikeEventContent, MessageLikeEventType};
+use serde::{Deserialize, Serialize};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: PUT,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/rooms/:room_id/send/:event_type/:txn_id",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[ruma_api(path)]
+    pub event_type: MessageLikeEventType,
+
+    /// The homeserver uses this to deduplicate requests that are retried with
+    /// the same access token.
+    #[ruma_api(path)]
+    pub txn_id: OwnedTransactionId,
+
+    #[ruma_api(body)]
+    pub body: Raw<AnyMessageLikeEventContent>,
+}
+
+impl Request {
+    pub fn new<T: MessageLikeEventContent>(
+        room_id: OwnedRoomId,
+        txn_id: OwnedTransactionId,
+        content: &T,
+    ) -> serde_json::Result<Self> {
+        Ok(Self {
+            room_id,
+            event_type: content.event_type(),
+            txn_id,
+            body: Raw::new(content)?.cast(),
+        })
+    }
+
+    /// Used for message-like events that carry fields unknown to `ruma_events`,
+    /// such as the ones namespaced by Commune.
+    pub fn new_raw(
+        room_id: OwnedRoomId,
+        event_type: MessageLikeEventType,
+        txn_id: OwnedTransactionId,
+        body: Raw<AnyMessageLikeEventContent>,
+    ) -> Self {
+        Self {
+            room_id,
+            event_type,
+            txn_id,
+            body,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+#[derive(Deserialize, Serialize)]
+pub struct Response {
+    pub event_id: OwnedEventId,
+}
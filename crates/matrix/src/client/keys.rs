@@ -0,0 +1,41 @@
+//! Device and one-time keys for end-to-end encryption. Key IDs are kept as
+//! `algorithm:id` strings, the way they appear on the wire.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#end-to-end-encryption
+
+use std::collections::BTreeMap;
+
+use ruma_common::{OwnedDeviceId, OwnedUserId};
+use serde::{Deserialize, Serialize};
+
+pub mod claim;
+pub mod query;
+pub mod upload;
+
+/// Signatures by user, then by `algorithm:key_id`.
+pub type Signatures = BTreeMap<OwnedUserId, BTreeMap<String, String>>;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DeviceKeys {
+    pub user_id: OwnedUserId,
+
+    pub device_id: OwnedDeviceId,
+
+    pub algorithms: Vec<String>,
+
+    pub keys: BTreeMap<String, String>,
+
+    #[serde(default)]
+    pub signatures: Signatures,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SignedKey {
+    pub key: String,
+
+    #[serde(default, skip_serializing_if = "ruma_common::serde::is_default")]
+    pub fallback: bool,
+
+    #[serde(default)]
+    pub signatures: Signatures,
+}
@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedDeviceId, OwnedUserId,
+};
+
+use super::SignedKey;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: POST,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/keys/claim",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    /// The algorithm of the key to claim for each device.
+    pub one_time_keys: BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, String>>,
+}
+
+impl Request {
+    pub fn new(one_time_keys: BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, String>>) -> Self {
+        Self { one_time_keys }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    /// Keyed by user, device and then `algorithm:key_id`.
+    #[serde(default)]
+    pub one_time_keys: BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, BTreeMap<String, SignedKey>>>,
+}
@@ -0,0 +1,36 @@
+use std::collections::BTreeMap;
+
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedDeviceId, OwnedUserId,
+};
+
+use super::DeviceKeys;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: POST,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/keys/query",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    /// An empty list of devices asks for all devices of the user.
+    pub device_keys: BTreeMap<OwnedUserId, Vec<OwnedDeviceId>>,
+}
+
+impl Request {
+    pub fn new(device_keys: BTreeMap<OwnedUserId, Vec<OwnedDeviceId>>) -> Self {
+        Self { device_keys }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    #[serde(default)]
+    pub device_keys: BTreeMap<OwnedUserId, BTreeMap<OwnedDeviceId, DeviceKeys>>,
+}
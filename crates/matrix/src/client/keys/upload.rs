@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+};
+
+use super::{DeviceKeys, SignedKey};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: POST,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/keys/upload",
+    }
+};
+
+#[request(error = crate::Error)]
+#[derive(Default)]
+pub struct Request {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_keys: Option<DeviceKeys>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub one_time_keys: BTreeMap<String, SignedKey>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fallback_keys: BTreeMap<String, SignedKey>,
+}
+
+impl Request {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    /// The amount of unclaimed one-time keys by algorithm.
+    #[serde(default)]
+    pub one_time_key_counts: BTreeMap<String, u64>,
+}
@@ -0,0 +1,8 @@
+pub mod ban;
+pub mod invite;
+pub mod join;
+pub mod joined_rooms;
+pub mod kick;
+pub mod knock;
+pub mod leave;
+pub mod unban;
@@ -0,0 +1,29 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedRoomId,
+};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/joined_rooms",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {}
+
+impl Request {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    pub joined_rooms: Vec<OwnedRoomId>,
+}
@@ -0,0 +1,38 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedRoomId, OwnedUserId,
+};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: POST,
+    rate_limited: true,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/rooms/:room_id/kick",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    pub user_id: OwnedUserId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId, user_id: OwnedUserId, reason: Option<String>) -> Self {
+        Self {
+            room_id,
+            user_id,
+            reason,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {}
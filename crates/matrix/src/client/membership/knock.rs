@@ -0,0 +1,46 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedRoomId, OwnedRoomOrAliasId, OwnedServerName,
+};
+use serde::{Deserialize, Serialize};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: POST,
+    rate_limited: true,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/knock/:room_id_or_alias",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id_or_alias: OwnedRoomOrAliasId,
+
+    /// Servers to attempt to knock through, required for rooms the homeserver
+    /// is not part of yet.
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    #[ruma_api(query)]
+    pub server_name: Vec<OwnedServerName>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Request {
+    pub fn new(room_id_or_alias: OwnedRoomOrAliasId) -> Self {
+        Self {
+            room_id_or_alias,
+            server_name: Vec::new(),
+            reason: None,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+#[derive(Deserialize, Serialize)]
+pub struct Response {
+    pub room_id: OwnedRoomId,
+}
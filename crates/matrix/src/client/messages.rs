@@ -0,0 +1,70 @@
+use ruma_common::{
+    api::{request, response, Direction, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedRoomId,
+};
+use ruma_events::{AnyStateEvent, AnyTimelineEvent};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/rooms/:room_id/messages",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub from: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub to: Option<String>,
+
+    #[ruma_api(query)]
+    pub dir: Direction,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub limit: Option<u64>,
+
+    /// A JSON encoded `RoomEventFilter`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    #[ruma_api(query)]
+    pub filter: String,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId, dir: Direction) -> Self {
+        Self {
+            room_id,
+            from: None,
+            to: None,
+            dir,
+            limit: None,
+            filter: String::new(),
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    pub start: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+
+    #[serde(default)]
+    pub chunk: Vec<Raw<AnyTimelineEvent>>,
+
+    #[serde(default)]
+    pub state: Vec<Raw<AnyStateEvent>>,
+}
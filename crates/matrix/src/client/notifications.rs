@@ -0,0 +1,70 @@
+//! The events the push rules of the user notified them of, newest first.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#get_matrixclientv3notifications
+
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    push::Action,
+    serde::Raw,
+    MilliSecondsSinceUnixEpoch, OwnedRoomId,
+};
+use ruma_events::AnyTimelineEvent;
+use serde::Deserialize;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/notifications",
+    }
+};
+
+#[request(error = crate::Error)]
+#[derive(Default)]
+pub struct Request {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub from: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub limit: Option<u64>,
+
+    /// `highlight` keeps only the notifications that highlight.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub only: Option<String>,
+}
+
+impl Request {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    pub next_token: Option<String>,
+
+    pub notifications: Vec<Notification>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Notification {
+    #[serde(default)]
+    pub actions: Vec<Action>,
+
+    pub event: Raw<AnyTimelineEvent>,
+
+    pub profile_tag: Option<String>,
+
+    /// Whether the read receipt of the user is past the event.
+    pub read: bool,
+
+    pub room_id: OwnedRoomId,
+
+    pub ts: MilliSecondsSinceUnixEpoch,
+}
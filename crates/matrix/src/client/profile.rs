@@ -1,2 +1,3 @@
 pub mod avatar_url;
 pub mod display_name;
+pub mod root;
@@ -0,0 +1,35 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedMxcUri, OwnedUserId,
+};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: None,
+    history: {
+        unstable => "/_matrix/client/v3/profile/:user_id",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub user_id: OwnedUserId,
+}
+
+impl Request {
+    pub fn new(user_id: OwnedUserId) -> Self {
+        Self { user_id }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    #[serde(rename = "displayname", skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<OwnedMxcUri>,
+}
@@ -0,0 +1,47 @@
+//! Moves the fully read marker and the read receipt of a room at once.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#post_matrixclientv3roomsroomidread_markers
+
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedEventId, OwnedRoomId,
+};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: POST,
+    rate_limited: true,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/rooms/:room_id/read_markers",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[serde(rename = "m.fully_read", skip_serializing_if = "Option::is_none")]
+    pub fully_read: Option<OwnedEventId>,
+
+    #[serde(rename = "m.read", skip_serializing_if = "Option::is_none")]
+    pub read: Option<OwnedEventId>,
+
+    #[serde(rename = "m.read.private", skip_serializing_if = "Option::is_none")]
+    pub private_read: Option<OwnedEventId>,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId) -> Self {
+        Self {
+            room_id,
+            fully_read: None,
+            read: None,
+            private_read: None,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {}
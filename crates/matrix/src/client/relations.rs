@@ -0,0 +1,70 @@
+use ruma_common::{
+    api::{request, response, Direction, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedEventId, OwnedRoomId,
+};
+use ruma_events::{relation::RelationType, AnyMessageLikeEvent};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v1/rooms/:room_id/relations/:event_id/:rel_type",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[ruma_api(path)]
+    pub event_id: OwnedEventId,
+
+    #[ruma_api(path)]
+    pub rel_type: RelationType,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub from: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub to: Option<String>,
+
+    #[serde(skip_serializing_if = "ruma_common::serde::is_default")]
+    #[ruma_api(query)]
+    pub dir: Direction,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub limit: Option<u64>,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId, event_id: OwnedEventId, rel_type: RelationType) -> Self {
+        Self {
+            room_id,
+            event_id,
+            rel_type,
+            from: None,
+            to: None,
+            dir: Direction::default(),
+            limit: None,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    pub chunk: Vec<Raw<AnyMessageLikeEvent>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_batch: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev_batch: Option<String>,
+}
@@ -0,0 +1,98 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    serde::Raw,
+};
+use ruma_events::AnyTimelineEvent;
+use serde::{Deserialize, Serialize};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: POST,
+    rate_limited: true,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/search",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub next_batch: Option<String>,
+
+    pub search_categories: Categories,
+}
+
+impl Request {
+    pub fn new(room_events: Criteria) -> Self {
+        Self {
+            next_batch: None,
+            search_categories: Categories {
+                room_events: Some(room_events),
+            },
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    pub search_categories: ResultCategories,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Categories {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub room_events: Option<Criteria>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Criteria {
+    pub search_term: String,
+
+    /// Any of `content.body`, `content.name` and `content.topic`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keys: Vec<String>,
+
+    /// A `RoomEventFilter`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<serde_json::Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_by: Option<OrderBy>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderBy {
+    Rank,
+    Recent,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ResultCategories {
+    #[serde(default)]
+    pub room_events: ResultRoomEvents,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ResultRoomEvents {
+    pub count: Option<u64>,
+
+    /// Words to highlight, as stemmed or otherwise normalized by the server.
+    #[serde(default)]
+    pub highlights: Vec<String>,
+
+    pub next_batch: Option<String>,
+
+    #[serde(default)]
+    pub results: Vec<SearchResult>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SearchResult {
+    pub rank: Option<f64>,
+
+    pub result: Option<Raw<AnyTimelineEvent>>,
+}
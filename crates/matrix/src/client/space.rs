@@ -0,0 +1,5 @@
+//! This module contains handlers for spaces.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#spaces
+
+pub mod hierarchy;
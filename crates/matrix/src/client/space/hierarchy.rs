@@ -0,0 +1,93 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    room::RoomType,
+    serde::Raw,
+    space::SpaceRoomJoinRule,
+    OwnedMxcUri, OwnedRoomAliasId, OwnedRoomId,
+};
+use ruma_events::space::child::HierarchySpaceChildEvent;
+use serde::{Deserialize, Serialize};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: true,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v1/rooms/:room_id/hierarchy",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub from: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub limit: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub max_depth: Option<u64>,
+
+    #[serde(skip_serializing_if = "ruma_common::serde::is_default")]
+    #[ruma_api(query)]
+    pub suggested_only: bool,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId) -> Self {
+        Self {
+            room_id,
+            from: None,
+            limit: None,
+            max_depth: None,
+            suggested_only: false,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_batch: Option<String>,
+
+    pub rooms: Vec<Chunk>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Chunk {
+    pub room_id: OwnedRoomId,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical_alias: Option<OwnedRoomAliasId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<OwnedMxcUri>,
+
+    pub num_joined_members: u64,
+
+    pub world_readable: bool,
+
+    pub guest_can_join: bool,
+
+    #[serde(default)]
+    pub join_rule: SpaceRoomJoinRule,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub room_type: Option<RoomType>,
+
+    pub children_state: Vec<Raw<HierarchySpaceChildEvent>>,
+}
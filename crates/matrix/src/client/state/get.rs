@@ -0,0 +1,45 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedRoomId,
+};
+use ruma_events::{AnyStateEventContent, StateEventType};
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/rooms/:room_id/state/:event_type/:state_key",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[ruma_api(path)]
+    pub event_type: StateEventType,
+
+    #[ruma_api(path)]
+    pub state_key: String,
+}
+
+impl Request {
+    pub fn new(room_id: OwnedRoomId, event_type: StateEventType, state_key: String) -> Self {
+        Self {
+            room_id,
+            event_type,
+            state_key,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    #[ruma_api(body)]
+    pub content: Raw<AnyStateEventContent>,
+}
@@ -0,0 +1,70 @@
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedEventId, OwnedRoomId,
+};
+use ruma_events::{AnyStateEventContent, StateEventContent, StateEventType};
+use serde::Serialize;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: PUT,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/rooms/:room_id/state/:event_type/:state_key",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub room_id: OwnedRoomId,
+
+    #[ruma_api(path)]
+    pub event_type: StateEventType,
+
+    #[ruma_api(path)]
+    pub state_key: String,
+
+    #[ruma_api(body)]
+    pub body: Raw<AnyStateEventContent>,
+}
+
+impl Request {
+    pub fn new<T: StateEventContent>(
+        room_id: OwnedRoomId,
+        state_key: impl Into<String>,
+        content: &T,
+    ) -> serde_json::Result<Self> {
+        Ok(Self {
+            room_id,
+            event_type: content.event_type(),
+            state_key: state_key.into(),
+            body: Raw::new(content)?.cast(),
+        })
+    }
+
+    /// Used for state events that have no typed content in `ruma_events`,
+    /// such as the ones namespaced by Commune.
+    pub fn new_raw(
+        room_id: OwnedRoomId,
+        event_type: StateEventType,
+        state_key: impl Into<String>,
+        body: Raw<AnyStateEventContent>,
+    ) -> Self {
+        Self {
+            room_id,
+            event_type,
+            state_key: state_key.into(),
+            body,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+#[derive(Serialize)]
+pub struct Response {
+    pub event_id: OwnedEventId,
+}
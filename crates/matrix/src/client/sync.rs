@@ -7,7 +7,7 @@ use std::{collections::BTreeMap, time::Duration};
 use ruma_common::{
     api::{request, response, Metadata},
     metadata,
-    serde::{deserialize_cow_str, duration::opt_ms, Raw},
+    serde::{deserialize_cow_str, Raw},
     DeviceKeyAlgorithm, MilliSecondsSinceUnixEpoch, OwnedMxcUri, OwnedRoomId, OwnedUserId, RoomId,
 };
 use ruma_events::{
@@ -17,6 +17,9 @@ use ruma_events::{
 };
 use serde::{self, de::Error as _, Deserialize, Serialize};
 
+pub mod v3;
+
+#[allow(dead_code)]
 const METADATA: Metadata = metadata! {
     method: POST,
     rate_limited: false,
@@ -43,7 +46,11 @@ pub struct Request {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub txn_id: Option<String>,
 
-    #[serde(with = "opt_ms", default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        with = "ruma_common::serde::duration::opt_ms",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
     #[ruma_api(query)]
     pub timeout: Option<Duration>,
 
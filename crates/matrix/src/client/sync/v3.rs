@@ -0,0 +1,157 @@
+//! The regular `/sync`, as opposed to the sliding sync of the parent module.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#get_matrixclientv3sync
+
+use std::{collections::BTreeMap, time::Duration};
+
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata,
+    serde::Raw,
+    OwnedRoomId,
+};
+use ruma_events::{
+    AnyGlobalAccountDataEvent, AnyRoomAccountDataEvent, AnyStrippedStateEvent,
+    AnySyncEphemeralRoomEvent, AnySyncStateEvent, AnySyncTimelineEvent, AnyToDeviceEvent,
+};
+use serde::Deserialize;
+
+use super::UnreadNotificationsCount;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: GET,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/sync",
+    }
+};
+
+#[request(error = crate::Error)]
+#[derive(Default)]
+pub struct Request {
+    /// A JSON encoded `Filter`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    #[ruma_api(query)]
+    pub filter: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ruma_api(query)]
+    pub since: Option<String>,
+
+    #[serde(
+        with = "ruma_common::serde::duration::opt_ms",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[ruma_api(query)]
+    pub timeout: Option<Duration>,
+}
+
+impl Request {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {
+    pub next_batch: String,
+
+    #[serde(default)]
+    pub rooms: Rooms,
+
+    #[serde(default)]
+    pub account_data: GlobalAccountData,
+
+    #[serde(default)]
+    pub to_device: ToDevice,
+
+    /// The amount of unclaimed one-time keys of the device by algorithm.
+    #[serde(default)]
+    pub device_one_time_keys_count: BTreeMap<String, u64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ToDevice {
+    #[serde(default)]
+    pub events: Vec<Raw<AnyToDeviceEvent>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct GlobalAccountData {
+    #[serde(default)]
+    pub events: Vec<Raw<AnyGlobalAccountDataEvent>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Rooms {
+    #[serde(default)]
+    pub join: BTreeMap<OwnedRoomId, JoinedRoom>,
+
+    #[serde(default)]
+    pub invite: BTreeMap<OwnedRoomId, InvitedRoom>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct JoinedRoom {
+    /// The state up to the start of the timeline.
+    #[serde(default)]
+    pub state: State,
+
+    #[serde(default)]
+    pub timeline: Timeline,
+
+    /// Counted from the read receipt of the user.
+    #[serde(default)]
+    pub unread_notifications: UnreadNotificationsCount,
+
+    #[serde(default)]
+    pub ephemeral: Ephemeral,
+
+    #[serde(default)]
+    pub account_data: RoomAccountData,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Timeline {
+    #[serde(default)]
+    pub events: Vec<Raw<AnySyncTimelineEvent>>,
+
+    #[serde(default)]
+    pub limited: bool,
+
+    pub prev_batch: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Ephemeral {
+    #[serde(default)]
+    pub events: Vec<Raw<AnySyncEphemeralRoomEvent>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RoomAccountData {
+    #[serde(default)]
+    pub events: Vec<Raw<AnyRoomAccountDataEvent>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    pub events: Vec<Raw<AnySyncStateEvent>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct InvitedRoom {
+    #[serde(default)]
+    pub invite_state: InviteState,
+}
+
+/// A subset of the room state, including the invite itself.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct InviteState {
+    #[serde(default)]
+    pub events: Vec<Raw<AnyStrippedStateEvent>>,
+}
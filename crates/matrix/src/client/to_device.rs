@@ -0,0 +1,50 @@
+//! Sends events straight to devices, outside of any room.
+//!
+//! reference: https://spec.matrix.org/unstable/client-server-api/#put_matrixclientv3sendtodeviceeventtypetxnid
+
+use std::collections::BTreeMap;
+
+use ruma_common::{
+    api::{request, response, Metadata},
+    metadata, OwnedTransactionId, OwnedUserId,
+};
+use serde_json::Value;
+
+#[allow(dead_code)]
+const METADATA: Metadata = metadata! {
+    method: PUT,
+    rate_limited: false,
+    authentication: AccessToken,
+    history: {
+        unstable => "/_matrix/client/v3/sendToDevice/:event_type/:txn_id",
+    }
+};
+
+#[request(error = crate::Error)]
+pub struct Request {
+    #[ruma_api(path)]
+    pub event_type: String,
+
+    #[ruma_api(path)]
+    pub txn_id: OwnedTransactionId,
+
+    /// Contents by user and then device ID, `*` meaning all their devices.
+    pub messages: BTreeMap<OwnedUserId, BTreeMap<String, Value>>,
+}
+
+impl Request {
+    pub fn new(
+        event_type: String,
+        txn_id: OwnedTransactionId,
+        messages: BTreeMap<OwnedUserId, BTreeMap<String, Value>>,
+    ) -> Self {
+        Self {
+            event_type,
+            txn_id,
+            messages,
+        }
+    }
+}
+
+#[response(error = crate::Error)]
+pub struct Response {}
@@ -18,6 +18,7 @@ use ruma_client::HttpClient;
 pub use ruma_client;
 pub use ruma_common;
 pub use ruma_events;
+pub use ruma_html;
 pub use ruma_identifiers_validation;
 
 pub type Error = ruma_common::api::error::MatrixError;
@@ -3,5 +3,19 @@
 //! reference: https://spec.matrix.org/unstable/client-server-api
 
 pub mod account;
+pub mod alias;
+pub mod crypto;
+pub mod direct;
+pub mod directory;
+pub mod extremities;
+pub mod membership;
+pub mod moderation;
+pub mod notification;
+pub mod post;
+pub mod read;
 pub mod relative;
+pub mod settings;
+pub mod space;
+pub mod stream;
+pub mod sync;
 // pub mod session;
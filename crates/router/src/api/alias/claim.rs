@@ -0,0 +1,40 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use commune::alias::Slug;
+use matrix::ruma_common::OwnedRoomId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    /// Either `space` or `space/board`.
+    pub slug: String,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::alias::claim::service;
+
+    let slug = match Slug::parse(&payload.slug) {
+        Ok(slug) => slug,
+        Err(e) => return e.into_response(),
+    };
+
+    match service(access_token.token(), room_id, slug).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to claim alias");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+use commune::alias::Slug;
+use serde::Deserialize;
+
+/// Serves both `/c/:space` and `/c/:space/:board`.
+#[derive(Debug, Deserialize)]
+pub struct Params {
+    pub space: String,
+
+    pub board: Option<String>,
+}
+
+pub async fn handler(Path(params): Path<Params>) -> Response {
+    use commune::alias::resolve::service;
+
+    let slug = match Slug::new(params.space, params.board) {
+        Ok(slug) => slug,
+        Err(e) => return e.into_response(),
+    };
+
+    match service(slug).await {
+        Ok(resp) => match resp.canonical {
+            Some(canonical) => {
+                Redirect::permanent(&format!("/_commune/client/r0/c/{canonical}")).into_response()
+            }
+            None => Json(resp).into_response(),
+        },
+        Err(e) => {
+            tracing::warn!(?e, "failed to resolve alias");
+
+            e.into_response()
+        }
+    }
+}
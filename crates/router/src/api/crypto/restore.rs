@@ -0,0 +1,31 @@
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use commune::util::secret::Secret;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    pub passphrase: Secret,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::crypto::restore::service;
+
+    match service(access_token.token(), payload.passphrase.inner()).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to restore room keys");
+
+            e.into_response()
+        }
+    }
+}
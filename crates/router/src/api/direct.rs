@@ -0,0 +1,6 @@
+pub mod accept;
+pub mod invites;
+pub mod list;
+pub mod messages;
+pub mod send;
+pub mod start;
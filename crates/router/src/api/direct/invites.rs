@@ -0,0 +1,21 @@
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+
+pub async fn handler(TypedHeader(access_token): TypedHeader<Authorization<Bearer>>) -> Response {
+    use commune::direct::invites::service;
+
+    match service(access_token.token()).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to list conversation invites");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use axum::{
+    extract::{Path, Query},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::OwnedRoomId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    /// The `next_batch` of the previous page.
+    pub from: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+    Query(params): Query<Params>,
+) -> Response {
+    use commune::direct::messages::service;
+
+    let limit = params.limit.map(|limit| limit.clamp(1, 100));
+
+    match service(access_token.token(), room_id, params.from, limit).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to read direct messages");
+
+            e.into_response()
+        }
+    }
+}
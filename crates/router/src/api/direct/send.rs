@@ -0,0 +1,35 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedRoomId, OwnedTransactionId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    #[serde(default)]
+    pub txn_id: Option<OwnedTransactionId>,
+    pub body: String,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::direct::send::service;
+
+    match service(access_token.token(), room_id, payload.txn_id, payload.body).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to send direct message");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::OwnedUserId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    pub user_id: OwnedUserId,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::direct::start::service;
+
+    match service(access_token.token(), payload.user_id).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to start conversation");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::OwnedRoomId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    #[serde(default)]
+    pub featured: bool,
+
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::directory::curate::service;
+
+    match service(
+        access_token.token(),
+        room_id,
+        payload.featured,
+        payload.hidden,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to curate directory entry");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use axum::{
+    extract::Query,
+    response::{IntoResponse, Response},
+    Json,
+};
+use commune::directory::list::Sort;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    pub search: Option<String>,
+
+    #[serde(default)]
+    pub sort: Sort,
+
+    pub from: Option<u64>,
+
+    pub limit: Option<u64>,
+}
+
+pub async fn handler(Query(params): Query<Params>) -> Response {
+    use commune::directory::list::service;
+
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+    match service(params.search, params.sort, params.from, limit).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to list public spaces");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::OwnedRoomId;
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+) -> Response {
+    use commune::extremities::clear::service;
+
+    match service(access_token.token(), room_id).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to clear forward extremities");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use axum::{
+    extract::Query,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    /// The least amount of extremities a room needs to be listed.
+    pub min: Option<u64>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Query(params): Query<Params>,
+) -> Response {
+    use commune::extremities::list::{service, DEFAULT_MIN};
+
+    match service(access_token.token(), params.min.unwrap_or(DEFAULT_MIN)).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to list rooms with forward extremities");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod invite;
+pub mod join;
+pub mod knock;
+pub mod leave;
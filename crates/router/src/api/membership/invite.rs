@@ -0,0 +1,43 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedRoomId, OwnedUserId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    pub user_id: OwnedUserId,
+
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::membership::invite::service;
+
+    match service(
+        access_token.token(),
+        room_id,
+        payload.user_id,
+        payload.reason,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to invite user");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::OwnedRoomId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Payload {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+    payload: Option<Json<Payload>>,
+) -> Response {
+    use commune::membership::join::service;
+
+    let Payload { reason } = payload.map(|Json(payload)| payload).unwrap_or_default();
+
+    match service(access_token.token(), room_id, reason).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to join room");
+
+            e.into_response()
+        }
+    }
+}
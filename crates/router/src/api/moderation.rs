@@ -0,0 +1,5 @@
+pub mod ban;
+pub mod kick;
+pub mod mute;
+pub mod unban;
+pub mod unmute;
@@ -0,0 +1,49 @@
+use std::time::Duration;
+
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedRoomId, OwnedUserId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    pub user_id: OwnedUserId,
+
+    pub reason: String,
+
+    /// In seconds, the mute lasts until lifted when omitted.
+    #[serde(default)]
+    pub duration: Option<u64>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::moderation::mute::service;
+
+    match service(
+        access_token.token(),
+        room_id,
+        payload.user_id,
+        payload.reason,
+        payload.duration.map(Duration::from_secs),
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to mute user");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use axum::{
+    extract::Query,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    /// The `next_batch` of the previous page.
+    pub from: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Query(params): Query<Params>,
+) -> Response {
+    use commune::notification::list::service;
+
+    let limit = params.limit.map(|limit| limit.clamp(1, 100));
+
+    match service(access_token.token(), params.from, limit).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to list notifications");
+
+            e.into_response()
+        }
+    }
+}
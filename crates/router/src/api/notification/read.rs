@@ -0,0 +1,35 @@
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    pub board_id: OwnedRoomId,
+
+    /// The notification to mark as read, along with everything before it in
+    /// the board.
+    pub event_id: OwnedEventId,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::notification::read::service;
+
+    match service(access_token.token(), payload.board_id, payload.event_id).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to mark notification as read");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,8 @@
+pub mod comment;
+pub mod create;
+pub mod delete;
+pub mod edit;
+pub mod get;
+pub mod list;
+pub mod revisions;
+pub mod vote;
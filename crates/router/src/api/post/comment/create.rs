@@ -0,0 +1,49 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId, OwnedTransactionId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    #[serde(default)]
+    pub txn_id: Option<OwnedTransactionId>,
+
+    /// The comment being replied to, omitted when replying to the post.
+    #[serde(default)]
+    pub parent: Option<OwnedEventId>,
+
+    pub body: String,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path((board_id, event_id)): Path<(OwnedRoomId, OwnedEventId)>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::post::comment::create::service;
+
+    match service(
+        access_token.token(),
+        board_id,
+        event_id,
+        payload.parent,
+        payload.txn_id,
+        payload.body,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to create comment");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use axum::{
+    extract::{Path, Query},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    /// Loads the replies to this comment instead of the whole thread.
+    pub parent: Option<OwnedEventId>,
+    pub from: Option<String>,
+    pub depth: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+pub async fn handler(
+    access_token: Option<TypedHeader<Authorization<Bearer>>>,
+    Path((board_id, event_id)): Path<(OwnedRoomId, OwnedEventId)>,
+    Query(params): Query<Params>,
+) -> Response {
+    use commune::post::comment::list::service;
+
+    let access_token = access_token.as_ref().map(|TypedHeader(at)| at.token());
+    let depth = params.depth.unwrap_or(5).clamp(1, 10);
+    let limit = params.limit.unwrap_or(20).clamp(1, 100);
+
+    match service(
+        access_token,
+        board_id,
+        event_id,
+        params.parent,
+        params.from,
+        depth,
+        limit,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to list comments");
+
+            e.into_response()
+        }
+    }
+}
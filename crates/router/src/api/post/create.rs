@@ -0,0 +1,57 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedMxcUri, OwnedRoomId, OwnedTransactionId};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    /// Retrying a request with the same transaction ID will not create
+    /// duplicate posts.
+    #[serde(default)]
+    pub txn_id: Option<OwnedTransactionId>,
+
+    pub title: String,
+
+    pub body: String,
+
+    #[serde(default)]
+    pub link: Option<Url>,
+
+    #[serde(default)]
+    pub media: Option<OwnedMxcUri>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(board_id): Path<OwnedRoomId>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::post::create::service;
+
+    match service(
+        access_token.token(),
+        board_id,
+        payload.txn_id,
+        payload.title,
+        payload.body,
+        payload.link,
+        payload.media,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to create post");
+
+            e.into_response()
+        }
+    }
+}
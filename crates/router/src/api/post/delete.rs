@@ -0,0 +1,34 @@
+use axum::{
+    extract::{Path, Query},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    /// Shown to the author when a moderator removes their post.
+    pub reason: Option<String>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path((board_id, event_id)): Path<(OwnedRoomId, OwnedEventId)>,
+    Query(params): Query<Params>,
+) -> Response {
+    use commune::post::delete::service;
+
+    match service(access_token.token(), board_id, event_id, params.reason).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to delete post");
+
+            e.into_response()
+        }
+    }
+}
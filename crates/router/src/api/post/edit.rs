@@ -0,0 +1,49 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId, OwnedTransactionId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    #[serde(default)]
+    pub txn_id: Option<OwnedTransactionId>,
+
+    /// Keeps the current title when omitted.
+    #[serde(default)]
+    pub title: Option<String>,
+
+    pub body: String,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path((board_id, event_id)): Path<(OwnedRoomId, OwnedEventId)>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::post::edit::service;
+
+    match service(
+        access_token.token(),
+        board_id,
+        event_id,
+        payload.txn_id,
+        payload.title,
+        payload.body,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to edit post");
+
+            e.into_response()
+        }
+    }
+}
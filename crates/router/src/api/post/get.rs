@@ -0,0 +1,28 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId};
+
+pub async fn handler(
+    access_token: Option<TypedHeader<Authorization<Bearer>>>,
+    Path((board_id, event_id)): Path<(OwnedRoomId, OwnedEventId)>,
+) -> Response {
+    use commune::post::get::service;
+
+    let access_token = access_token.as_ref().map(|TypedHeader(at)| at.token());
+
+    match service(access_token, board_id, event_id).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to retrieve post");
+
+            e.into_response()
+        }
+    }
+}
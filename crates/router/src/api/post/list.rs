@@ -0,0 +1,59 @@
+use axum::{
+    extract::{Path, Query},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use commune::post::list::{Sort, Window};
+use matrix::ruma_common::OwnedRoomId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortParam {
+    #[default]
+    New,
+    Hot,
+    Top,
+    Controversial,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    pub sort: Option<SortParam>,
+    /// The time window of the `top` sort.
+    pub t: Option<Window>,
+    /// The opaque `next_batch` of the previous page.
+    pub from: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub async fn handler(
+    access_token: Option<TypedHeader<Authorization<Bearer>>>,
+    Path(board_id): Path<OwnedRoomId>,
+    Query(params): Query<Params>,
+) -> Response {
+    use commune::post::list::service;
+
+    let access_token = access_token.as_ref().map(|TypedHeader(at)| at.token());
+    let sort = match params.sort.unwrap_or_default() {
+        SortParam::New => Sort::New,
+        SortParam::Hot => Sort::Hot,
+        SortParam::Top => Sort::Top(params.t.unwrap_or_default()),
+        SortParam::Controversial => Sort::Controversial,
+    };
+
+    let limit = params.limit.map(|limit| limit.clamp(1, 100));
+
+    match service(access_token, board_id, sort, params.from, limit).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to list posts");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId};
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path((board_id, event_id)): Path<(OwnedRoomId, OwnedEventId)>,
+) -> Response {
+    use commune::post::revisions::service;
+
+    match service(access_token.token(), board_id, event_id).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to list revisions");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use commune::post::vote::Vote;
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    /// Retracts the current vote when omitted.
+    #[serde(default)]
+    pub vote: Option<Vote>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path((board_id, event_id)): Path<(OwnedRoomId, OwnedEventId)>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::post::vote::service;
+
+    match service(access_token.token(), board_id, event_id, payload.vote).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to vote on post");
+
+            e.into_response()
+        }
+    }
+}
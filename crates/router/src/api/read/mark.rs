@@ -0,0 +1,47 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{OwnedEventId, OwnedRoomId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Payload {
+    #[serde(default)]
+    pub fully_read: Option<OwnedEventId>,
+
+    #[serde(default)]
+    pub read: Option<OwnedEventId>,
+
+    /// Keeps the read receipt from other members.
+    #[serde(default)]
+    pub private: bool,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::read::mark::service;
+
+    let Payload {
+        fully_read,
+        read,
+        private,
+    } = payload;
+
+    match service(access_token.token(), room_id, fully_read, read, private).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to set read markers");
+
+            e.into_response()
+        }
+    }
+}
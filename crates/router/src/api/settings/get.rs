@@ -0,0 +1,28 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::OwnedRoomId;
+
+pub async fn handler(
+    access_token: Option<TypedHeader<Authorization<Bearer>>>,
+    Path(room_id): Path<OwnedRoomId>,
+) -> Response {
+    use commune::settings::get::service;
+
+    let access_token = access_token.as_ref().map(|TypedHeader(at)| at.token());
+
+    match service(access_token, room_id).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to retrieve room settings");
+
+            e.into_response()
+        }
+    }
+}
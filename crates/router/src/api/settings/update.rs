@@ -0,0 +1,65 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use commune::settings::update::Changes;
+use matrix::{
+    ruma_common::{OwnedMxcUri, OwnedRoomId},
+    ruma_events::room::{
+        guest_access::GuestAccess, history_visibility::HistoryVisibility,
+        join_rules::RoomJoinRulesEventContent,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Payload {
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub topic: Option<String>,
+
+    #[serde(default)]
+    pub avatar: Option<OwnedMxcUri>,
+
+    #[serde(default)]
+    pub join_rules: Option<RoomJoinRulesEventContent>,
+
+    #[serde(default)]
+    pub history_visibility: Option<HistoryVisibility>,
+
+    #[serde(default)]
+    pub guest_access: Option<GuestAccess>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(room_id): Path<OwnedRoomId>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::settings::update::service;
+
+    let changes = Changes {
+        name: payload.name,
+        topic: payload.topic,
+        avatar: payload.avatar,
+        join_rules: payload.join_rules,
+        history_visibility: payload.history_visibility,
+        guest_access: payload.guest_access,
+    };
+
+    match service(access_token.token(), room_id, changes).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to update room settings");
+
+            e.into_response()
+        }
+    }
+}
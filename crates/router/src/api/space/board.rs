@@ -0,0 +1,6 @@
+pub mod archive;
+pub mod create;
+pub mod delete;
+pub mod list;
+pub mod relink;
+pub mod update;
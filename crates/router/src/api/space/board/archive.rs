@@ -0,0 +1,26 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::OwnedRoomId;
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path((space_id, board_id)): Path<(OwnedRoomId, OwnedRoomId)>,
+) -> Response {
+    use commune::space::board::archive::service;
+
+    match service(access_token.token(), space_id, board_id).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to archive board");
+
+            e.into_response()
+        }
+    }
+}
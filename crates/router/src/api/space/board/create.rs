@@ -0,0 +1,57 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use commune::space::board::Access;
+use matrix::ruma_common::OwnedRoomId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    pub name: String,
+
+    #[serde(default)]
+    pub topic: Option<String>,
+
+    #[serde(default)]
+    pub order: Option<String>,
+
+    /// Default boards are joined along with the space.
+    #[serde(default)]
+    pub default: bool,
+
+    #[serde(default)]
+    pub access: Access,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path(space_id): Path<OwnedRoomId>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::space::board::create::service;
+
+    match service(
+        access_token.token(),
+        space_id,
+        payload.name,
+        payload.topic,
+        payload.order,
+        payload.default,
+        payload.access,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to create board");
+
+            e.into_response()
+        }
+    }
+}
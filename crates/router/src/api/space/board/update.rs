@@ -0,0 +1,49 @@
+use axum::{
+    extract::Path,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::OwnedRoomId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub order: Option<String>,
+
+    #[serde(default)]
+    pub default: Option<bool>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Path((space_id, board_id)): Path<(OwnedRoomId, OwnedRoomId)>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::space::board::update::service;
+
+    match service(
+        access_token.token(),
+        space_id,
+        board_id,
+        payload.name,
+        payload.order,
+        payload.default,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to update board");
+
+            e.into_response()
+        }
+    }
+}
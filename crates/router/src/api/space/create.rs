@@ -0,0 +1,43 @@
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Payload {
+    pub name: String,
+
+    #[serde(default)]
+    pub topic: Option<String>,
+
+    #[serde(default)]
+    pub public: bool,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Json(payload): Json<Payload>,
+) -> Response {
+    use commune::space::create::service;
+
+    match service(
+        access_token.token(),
+        payload.name,
+        payload.topic,
+        payload.public,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to create space");
+
+            e.into_response()
+        }
+    }
+}
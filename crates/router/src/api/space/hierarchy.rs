@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Path, Query},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::OwnedRoomId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    pub from: Option<String>,
+    pub limit: Option<u64>,
+    pub max_depth: Option<u64>,
+}
+
+pub async fn handler(
+    access_token: Option<TypedHeader<Authorization<Bearer>>>,
+    Path(space_id): Path<OwnedRoomId>,
+    Query(params): Query<Params>,
+) -> Response {
+    use commune::space::hierarchy::service;
+
+    let access_token = access_token.as_ref().map(|TypedHeader(at)| at.token());
+
+    match service(
+        access_token,
+        space_id,
+        params.from,
+        params.limit,
+        params.max_depth,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to retrieve space hierarchy");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use axum::{
+    extract::{Path, Query},
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use commune::search::Order;
+use matrix::ruma_common::{OwnedRoomId, OwnedUserId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    pub q: String,
+    pub board: Option<OwnedRoomId>,
+    pub author: Option<OwnedUserId>,
+    pub order: Option<Order>,
+    pub next_batch: Option<String>,
+}
+
+pub async fn handler(
+    access_token: Option<TypedHeader<Authorization<Bearer>>>,
+    Path(space_id): Path<OwnedRoomId>,
+    Query(params): Query<Params>,
+) -> Response {
+    use commune::search::service;
+
+    let access_token = access_token.as_ref().map(|TypedHeader(at)| at.token());
+    let order = params.order.unwrap_or_default();
+
+    match service(
+        access_token,
+        space_id,
+        params.q,
+        params.board,
+        params.author,
+        order,
+        params.next_batch,
+    )
+    .await
+    {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to search space");
+
+            e.into_response()
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::{convert::Infallible, time::Duration};
+
+use axum::{
+    extract::Query,
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use matrix::ruma_common::{EventId, OwnedEventId, OwnedRoomId, RoomId};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    /// Comma-separated board IDs.
+    pub boards: Option<String>,
+    /// Comma-separated threads, each as `board_id/post_id`.
+    pub threads: Option<String>,
+    /// For clients that cannot set the `Last-Event-ID` header.
+    pub last_event_id: Option<String>,
+}
+
+pub async fn handler(
+    access_token: Option<TypedHeader<Authorization<Bearer>>>,
+    headers: HeaderMap,
+    Query(params): Query<Params>,
+) -> Response {
+    use commune::stream::service;
+
+    let access_token = access_token.as_ref().map(|TypedHeader(at)| at.token());
+
+    let (boards, threads) = match parse(&params) {
+        Ok(subscriptions) => subscriptions,
+        Err(e) => return e.into_response(),
+    };
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .map(ToOwned::to_owned)
+        .or(params.last_event_id);
+
+    let subscription = match service(access_token, boards, threads, last_event_id).await {
+        Ok(subscription) => subscription,
+        Err(e) => {
+            tracing::warn!(?e, "failed to subscribe to stream");
+
+            return e.into_response();
+        }
+    };
+
+    let stream = futures::stream::unfold(subscription, |mut subscription| async move {
+        let item = subscription.next().await?;
+
+        let event = Event::default()
+            .id(item.id)
+            .json_data(item.event)
+            .unwrap_or_default();
+
+        Some((Ok::<_, Infallible>(event), subscription))
+    });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+        .into_response()
+}
+
+type Threads = Vec<(OwnedRoomId, OwnedEventId)>;
+
+fn parse(params: &Params) -> commune::error::Result<(Vec<OwnedRoomId>, Threads)> {
+    let split = |list: &Option<String>| {
+        list.iter()
+            .flat_map(|list| list.split(','))
+            .filter(|entry| !entry.is_empty())
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>()
+    };
+
+    let boards = split(&params.boards)
+        .into_iter()
+        .map(RoomId::parse)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let threads = split(&params.threads)
+        .into_iter()
+        .map(|thread| {
+            let (board_id, post_id) = thread.split_once('/').unwrap_or((&thread, ""));
+
+            Ok((RoomId::parse(board_id)?, EventId::parse(post_id)?))
+        })
+        .collect::<commune::error::Result<Vec<_>>>()?;
+
+    Ok((boards, threads))
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use axum::{
+    extract::Query,
+    response::{IntoResponse, Response},
+    Json,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use commune::sync::Window;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Params {
+    /// Separates the connections of one user, such as those of several tabs.
+    pub conn_id: Option<String>,
+    pub pos: Option<String>,
+    /// How long to wait for changes, in milliseconds.
+    pub timeout: Option<u64>,
+}
+
+pub async fn handler(
+    TypedHeader(access_token): TypedHeader<Authorization<Bearer>>,
+    Query(params): Query<Params>,
+    payload: Option<Json<Window>>,
+) -> Response {
+    use commune::sync::service;
+
+    let conn_id = params.conn_id.unwrap_or_else(|| "default".to_owned());
+    let window = payload.map(|Json(window)| window).unwrap_or_default();
+    let timeout = Duration::from_millis(params.timeout.unwrap_or_default());
+
+    match service(access_token.token(), conn_id, params.pos, window, timeout).await {
+        Ok(resp) => Json(resp).into_response(),
+        Err(e) => {
+            tracing::warn!(?e, "failed to sync");
+
+            e.into_response()
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::net::SocketAddr;
 
 use axum::{
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Router,
 };
 use tokio::net::TcpListener;
@@ -24,6 +24,113 @@ pub async fn routes() -> Router {
                 .route("/password", put(api::account::password::handler))
                 .route("/display_name", put(api::account::display_name::handler))
                 .route("/avatar", put(api::account::avatar::handler)),
+        )
+        .nest(
+            "/rooms",
+            Router::new()
+                .route("/:room_id/alias", put(api::alias::claim::handler))
+                .route(
+                    "/:room_id/settings",
+                    get(api::settings::get::handler).put(api::settings::update::handler),
+                )
+                .route("/:room_id/join", post(api::membership::join::handler))
+                .route("/:room_id/knock", post(api::membership::knock::handler))
+                .route("/:room_id/leave", post(api::membership::leave::handler))
+                .route("/:room_id/invite", post(api::membership::invite::handler))
+                .route("/:room_id/kick", post(api::moderation::kick::handler))
+                .route("/:room_id/ban", post(api::moderation::ban::handler))
+                .route("/:room_id/unban", post(api::moderation::unban::handler))
+                .route("/:room_id/mute", post(api::moderation::mute::handler))
+                .route("/:room_id/unmute", post(api::moderation::unmute::handler))
+                .route("/:room_id/read_markers", post(api::read::mark::handler)),
+        )
+        .route("/notifications", get(api::notification::list::handler))
+        .route(
+            "/notifications/read",
+            post(api::notification::read::handler),
+        )
+        .route("/sync", post(api::sync::handler))
+        .route("/stream", get(api::stream::handler))
+        .route("/c/:space", get(api::alias::resolve::handler))
+        .route("/c/:space/:board", get(api::alias::resolve::handler))
+        .nest(
+            "/direct",
+            Router::new()
+                .route(
+                    "/",
+                    get(api::direct::list::handler).post(api::direct::start::handler),
+                )
+                .route("/invites", get(api::direct::invites::handler))
+                .route("/:room_id/accept", post(api::direct::accept::handler))
+                .route(
+                    "/:room_id/messages",
+                    get(api::direct::messages::handler).post(api::direct::send::handler),
+                ),
+        )
+        .route("/keys/backup", post(api::crypto::backup::handler))
+        .route("/keys/restore", post(api::crypto::restore::handler))
+        .route("/directory", get(api::directory::list::handler))
+        .route("/directory/:room_id", put(api::directory::curate::handler))
+        .nest(
+            "/admin",
+            Router::new()
+                .route("/extremities", get(api::extremities::list::handler))
+                .route(
+                    "/extremities/:room_id",
+                    delete(api::extremities::clear::handler),
+                ),
+        )
+        .nest(
+            "/boards",
+            Router::new()
+                .route(
+                    "/:board_id/posts",
+                    get(api::post::list::handler).post(api::post::create::handler),
+                )
+                .route(
+                    "/:board_id/posts/:event_id",
+                    get(api::post::get::handler)
+                        .put(api::post::edit::handler)
+                        .delete(api::post::delete::handler),
+                )
+                .route(
+                    "/:board_id/posts/:event_id/revisions",
+                    get(api::post::revisions::handler),
+                )
+                .route(
+                    "/:board_id/posts/:event_id/vote",
+                    put(api::post::vote::handler),
+                )
+                .route(
+                    "/:board_id/posts/:event_id/comments",
+                    get(api::post::comment::list::handler)
+                        .post(api::post::comment::create::handler),
+                ),
+        )
+        .nest(
+            "/spaces",
+            Router::new()
+                .route("/", post(api::space::create::handler))
+                .route("/:space_id/hierarchy", get(api::space::hierarchy::handler))
+                .route("/:space_id/search", get(api::space::search::handler))
+                .route("/:space_id/unread", get(api::read::unread::handler))
+                .route(
+                    "/:space_id/boards",
+                    get(api::space::board::list::handler).post(api::space::board::create::handler),
+                )
+                .route(
+                    "/:space_id/boards/relink",
+                    post(api::space::board::relink::handler),
+                )
+                .route(
+                    "/:space_id/boards/:board_id",
+                    put(api::space::board::update::handler)
+                        .delete(api::space::board::delete::handler),
+                )
+                .route(
+                    "/:space_id/boards/:board_id/archive",
+                    post(api::space::board::archive::handler),
+                ),
         );
 
     Router::new().nest("/_commune/client/r0", router)
@@ -7,6 +7,16 @@ async fn main() -> Result<()> {
     commune::init().await;
     let config = &commune::commune().config;
 
+    if std::env::args().nth(1).as_deref() == Some("reindex") {
+        let count = commune::search::index::reindex().await?;
+        tracing::info!(count, "rebuilt search index");
+
+        return Ok(());
+    }
+
+    commune::search::index::start().await;
+    commune::moderation::mute::start().await;
+
     router::serve(config.public_loopback, config.port.unwrap()).await?;
 
     Ok(())
@@ -0,0 +1,79 @@
+use rand::seq::IteratorRandom;
+use reqwest::StatusCode;
+use router::api::alias::claim;
+use serde::Deserialize;
+
+use crate::{
+    api::{relative::login, space::create::create_space},
+    env::Env,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Resolved {
+    pub room_id: String,
+}
+
+fn slug() -> String {
+    ('a'..='z')
+        .choose_multiple(&mut rand::thread_rng(), 12)
+        .into_iter()
+        .collect()
+}
+
+#[tokio::test]
+async fn claim_alias_test() {
+    let client = Env::new().await;
+
+    let owner = login::login(&client).await.unwrap();
+    let space = create_space(&client, &owner.access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+
+    let (old, new) = (slug(), slug());
+
+    for slug in [&old, &new] {
+        let resp = client
+            .put(&format!("/_commune/client/r0/rooms/{space_id}/alias"))
+            .bearer_auth(&owner.access_token)
+            .json(&claim::Payload { slug: slug.clone() })
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    let resp = client
+        .get(&format!("/_commune/client/r0/c/{new}"))
+        .send()
+        .await
+        .unwrap()
+        .json::<Resolved>()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.room_id, space_id);
+
+    let resp = client
+        .get(&format!("/_commune/client/r0/c/{old}"))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        resp.headers()["location"],
+        format!("/_commune/client/r0/c/{new}").as_str()
+    );
+
+    let resp = client
+        .put(&format!("/_commune/client/r0/rooms/{space_id}/alias"))
+        .bearer_auth(&owner.access_token)
+        .json(&claim::Payload {
+            slug: "admin".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
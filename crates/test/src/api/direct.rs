@@ -0,0 +1,141 @@
+use matrix::client::event::send::Response;
+use router::api::direct::{send, start};
+use serde::Deserialize;
+
+use crate::{api::relative::login, env::Env};
+
+#[derive(Debug, Deserialize)]
+pub struct Started {
+    pub room_id: String,
+    pub created: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Message {
+    pub event_id: String,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Conversation {
+    pub room_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Messages {
+    pub messages: Vec<Message>,
+}
+
+async fn start(client: &Env, access_token: &str, user_id: &str) -> Started {
+    client
+        .post("/_commune/client/r0/direct")
+        .bearer_auth(access_token)
+        .json(&start::Payload {
+            user_id: user_id.try_into().unwrap(),
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<Started>()
+        .await
+        .unwrap()
+}
+
+async fn list(client: &Env, access_token: &str) -> Vec<Conversation> {
+    client
+        .get("/_commune/client/r0/direct")
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Vec<Conversation>>()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn direct_messages_test() {
+    let client = Env::new().await;
+
+    let alice = login::login(&client).await.unwrap();
+    let bob = login::login(&client).await.unwrap();
+
+    let started = start(&client, &alice.access_token, bob.user_id.as_str()).await;
+    assert!(started.created);
+
+    let again = start(&client, &alice.access_token, bob.user_id.as_str()).await;
+    assert!(!again.created);
+    assert_eq!(again.room_id, started.room_id);
+
+    let room_id = started.room_id.as_str();
+
+    let sent = client
+        .post(&format!("/_commune/client/r0/direct/{room_id}/messages"))
+        .bearer_auth(&alice.access_token)
+        .json(&send::Payload {
+            txn_id: None,
+            body: "hello bob".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<Response>()
+        .await
+        .unwrap();
+
+    // listing leaves the invite pending
+    assert!(list(&client, &bob.access_token).await.is_empty());
+
+    let invites = client
+        .get("/_commune/client/r0/direct/invites")
+        .bearer_auth(&bob.access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Vec<Conversation>>()
+        .await
+        .unwrap();
+
+    assert!(invites.iter().any(|invite| invite.room_id == room_id));
+
+    let resp = client
+        .post(&format!("/_commune/client/r0/direct/{room_id}/accept"))
+        .bearer_auth(&bob.access_token)
+        .send()
+        .await
+        .unwrap();
+
+    assert!(resp.status().is_success());
+
+    let conversations = list(&client, &bob.access_token).await;
+
+    tracing::info!(?conversations);
+
+    assert!(conversations
+        .iter()
+        .any(|conversation| conversation.room_id == room_id));
+
+    let resp = client
+        .get(&format!("/_commune/client/r0/direct/{room_id}/messages"))
+        .bearer_auth(&bob.access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Messages>()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.messages[0].event_id, sent.event_id.as_str());
+    assert_eq!(resp.messages[0].body, "hello bob");
+
+    let outsider = login::login(&client).await.unwrap();
+
+    let resp = client
+        .get(&format!("/_commune/client/r0/direct/{room_id}/messages"))
+        .bearer_auth(&outsider.access_token)
+        .send()
+        .await
+        .unwrap();
+
+    assert!(resp.status().is_client_error());
+}
@@ -0,0 +1,42 @@
+use serde::Deserialize;
+
+use crate::{
+    api::{relative::login, space::create::create_space},
+    env::Env,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Entry {
+    pub room_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Directory {
+    pub spaces: Vec<Entry>,
+}
+
+#[tokio::test]
+async fn list_directory_test() {
+    let client = Env::new().await;
+
+    let login_resp = login::login(&client).await.unwrap();
+    let space = create_space(&client, &login_resp.access_token)
+        .await
+        .unwrap();
+
+    let resp = client
+        .get("/_commune/client/r0/directory?search=rustaceans&limit=100")
+        .send()
+        .await
+        .unwrap()
+        .json::<Directory>()
+        .await
+        .unwrap();
+
+    tracing::info!(?resp);
+
+    assert!(resp
+        .spaces
+        .iter()
+        .any(|entry| entry.room_id == space.room_id.as_str()));
+}
@@ -0,0 +1,35 @@
+use reqwest::StatusCode;
+
+use crate::{
+    api::{relative::login, space::create::create_space},
+    env::Env,
+};
+
+#[tokio::test]
+async fn extremities_require_admin_test() {
+    let client = Env::new().await;
+
+    let login_resp = login::login(&client).await.unwrap();
+    let access_token = login_resp.access_token.as_str();
+
+    let space = create_space(&client, access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+
+    let resp = client
+        .get("/_commune/client/r0/admin/extremities?min=1")
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = client
+        .delete(&format!("/_commune/client/r0/admin/extremities/{space_id}"))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,64 @@
+use matrix::client::create_room;
+use router::api::{membership::join, space::board::create};
+use serde::Deserialize;
+
+use crate::{
+    api::{relative::login, space::create::create_space},
+    env::Env,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Joined {
+    pub room_id: String,
+    pub boards: Vec<String>,
+}
+
+#[tokio::test]
+async fn join_space_test() {
+    let client = Env::new().await;
+
+    let owner = login::login(&client).await.unwrap();
+    let space = create_space(&client, &owner.access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+
+    let mut boards = Vec::new();
+
+    for (name, default) in [("general", true), ("off-topic", false)] {
+        let board = client
+            .post(&format!("/_commune/client/r0/spaces/{space_id}/boards"))
+            .bearer_auth(&owner.access_token)
+            .json(&create::Payload {
+                name: name.to_owned(),
+                topic: None,
+                order: None,
+                default,
+                access: Default::default(),
+            })
+            .send()
+            .await
+            .unwrap()
+            .json::<create_room::Response>()
+            .await
+            .unwrap();
+
+        boards.push(board.room_id);
+    }
+
+    let member = login::login(&client).await.unwrap();
+
+    let resp = client
+        .post(&format!("/_commune/client/r0/rooms/{space_id}/join"))
+        .bearer_auth(&member.access_token)
+        .json(&join::Payload::default())
+        .send()
+        .await
+        .unwrap()
+        .json::<Joined>()
+        .await
+        .unwrap();
+
+    tracing::info!(?resp);
+
+    assert_eq!(resp.room_id, space_id);
+    assert_eq!(resp.boards, [boards[0].as_str()]);
+}
@@ -0,0 +1,49 @@
+use reqwest::StatusCode;
+use router::api::{membership::join, moderation::ban};
+
+use crate::{
+    api::{
+        relative::login,
+        space::{board::create_board, create::create_space},
+    },
+    env::Env,
+};
+
+#[tokio::test]
+async fn ban_from_space_test() {
+    let client = Env::new().await;
+
+    let owner = login::login(&client).await.unwrap();
+    let space = create_space(&client, &owner.access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+    let board = create_board(&client, &owner.access_token, space_id, "general", None)
+        .await
+        .unwrap();
+
+    let member = login::login(&client).await.unwrap();
+
+    let resp = client
+        .post(&format!("/_commune/client/r0/rooms/{space_id}/ban"))
+        .bearer_auth(&owner.access_token)
+        .json(&ban::Payload {
+            user_id: member.user_id.clone(),
+            reason: "spam".to_owned(),
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // the ban cascades to the boards of the space
+    let resp = client
+        .post(&format!("/_commune/client/r0/rooms/{}/join", board.room_id))
+        .bearer_auth(&member.access_token)
+        .json(&join::Payload::default())
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    assert_eq!(resp.text().await.unwrap(), "you are banned from this room");
+}
@@ -0,0 +1,158 @@
+use matrix::client::event::send::Response;
+use router::api::{membership::join, post::comment::create};
+use serde::Deserialize;
+
+use crate::{
+    api::{
+        post::create_post,
+        relative::login,
+        space::{board::create_board, create::create_space},
+    },
+    env::Env,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Notification {
+    pub kind: String,
+    pub event_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Notifications {
+    pub notifications: Vec<Notification>,
+}
+
+async fn comment(
+    client: &Env,
+    access_token: &str,
+    board_id: &str,
+    post_id: &str,
+    parent: Option<&str>,
+    body: &str,
+) -> Response {
+    client
+        .post(&format!(
+            "/_commune/client/r0/boards/{board_id}/posts/{post_id}/comments"
+        ))
+        .bearer_auth(access_token)
+        .json(&create::Payload {
+            txn_id: None,
+            parent: parent.map(|parent| parent.try_into().unwrap()),
+            body: body.to_owned(),
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<Response>()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn list_notifications_test() {
+    let client = Env::new().await;
+
+    let owner = login::login(&client).await.unwrap();
+    let access_token = owner.access_token.as_str();
+
+    let space = create_space(&client, access_token).await.unwrap();
+    let board = create_board(
+        &client,
+        access_token,
+        space.room_id.as_str(),
+        "general",
+        None,
+    )
+    .await
+    .unwrap();
+    let board_id = board.room_id.as_str();
+
+    let member = login::login(&client).await.unwrap();
+
+    let _ = client
+        .post(&format!("/_commune/client/r0/rooms/{board_id}/join"))
+        .bearer_auth(&member.access_token)
+        .json(&join::Payload::default())
+        .send()
+        .await
+        .unwrap();
+
+    let post = create_post(&client, access_token, board_id, "txn1", "owned")
+        .await
+        .unwrap();
+    let post_id = post.event_id.as_str();
+
+    let post_reply = comment(
+        &client,
+        &member.access_token,
+        board_id,
+        post_id,
+        None,
+        "nice post",
+    )
+    .await;
+
+    let parent = comment(&client, access_token, board_id, post_id, None, "thanks").await;
+
+    let comment_reply = comment(
+        &client,
+        &member.access_token,
+        board_id,
+        post_id,
+        Some(parent.event_id.as_str()),
+        "you are welcome",
+    )
+    .await;
+
+    let other = create_post(&client, &member.access_token, board_id, "txn2", "mine")
+        .await
+        .unwrap();
+
+    let mention = comment(
+        &client,
+        &member.access_token,
+        board_id,
+        other.event_id.as_str(),
+        None,
+        &format!("what do you think, {}?", owner.user_id),
+    )
+    .await;
+
+    // names a longer user ID the owner's is only the start of
+    let _ = comment(
+        &client,
+        &member.access_token,
+        board_id,
+        other.event_id.as_str(),
+        None,
+        &format!("ask {}x instead", owner.user_id),
+    )
+    .await;
+
+    let resp = client
+        .get("/_commune/client/r0/notifications")
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Notifications>()
+        .await
+        .unwrap();
+
+    tracing::info!(?resp);
+
+    let notifications: Vec<_> = resp
+        .notifications
+        .iter()
+        .map(|notification| (notification.kind.as_str(), notification.event_id.as_str()))
+        .collect();
+
+    assert_eq!(
+        notifications,
+        [
+            ("mention", mention.event_id.as_str()),
+            ("comment_reply", comment_reply.event_id.as_str()),
+            ("post_reply", post_reply.event_id.as_str()),
+        ]
+    );
+}
@@ -0,0 +1,155 @@
+use commune::post::vote::Vote;
+use matrix::client::event::send::Response;
+use router::api::post::{create, vote};
+use serde::Deserialize;
+
+use crate::{
+    api::{
+        relative::login,
+        space::{board::create_board, create::create_space},
+    },
+    env::Env,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Post {
+    pub event_id: String,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+pub struct Votes {
+    pub up: u64,
+    pub down: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Posts {
+    pub posts: Vec<Post>,
+}
+
+pub async fn create_post(
+    client: &Env,
+    access_token: &str,
+    board_id: &str,
+    txn_id: &str,
+    title: &str,
+) -> Result<Response, reqwest::Error> {
+    let resp = client
+        .post(&format!("/_commune/client/r0/boards/{board_id}/posts"))
+        .bearer_auth(access_token)
+        .json(&create::Payload {
+            txn_id: Some(txn_id.into()),
+            title: title.to_owned(),
+            body: "hello **world**".to_owned(),
+            link: None,
+            media: None,
+        })
+        .send()
+        .await
+        .unwrap();
+
+    resp.json::<Response>().await
+}
+
+#[tokio::test]
+async fn list_posts_test() {
+    let client = Env::new().await;
+
+    let login_resp = login::login(&client).await.unwrap();
+    let access_token = login_resp.access_token.as_str();
+
+    let space = create_space(&client, access_token).await.unwrap();
+    let board = create_board(
+        &client,
+        access_token,
+        space.room_id.as_str(),
+        "general",
+        None,
+    )
+    .await
+    .unwrap();
+    let board_id = board.room_id.as_str();
+
+    let first = create_post(&client, access_token, board_id, "txn1", "first")
+        .await
+        .unwrap();
+    let retried = create_post(&client, access_token, board_id, "txn1", "first")
+        .await
+        .unwrap();
+    let _ = create_post(&client, access_token, board_id, "txn2", "second")
+        .await
+        .unwrap();
+
+    assert_eq!(first.event_id, retried.event_id);
+
+    let resp = client
+        .get(&format!("/_commune/client/r0/boards/{board_id}/posts"))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Posts>()
+        .await
+        .unwrap();
+
+    tracing::info!(?resp);
+
+    let titles: Vec<_> = resp.posts.iter().map(|post| post.title.as_str()).collect();
+
+    assert_eq!(titles, ["second", "first"]);
+}
+
+#[tokio::test]
+async fn vote_post_test() {
+    let client = Env::new().await;
+
+    let login_resp = login::login(&client).await.unwrap();
+    let access_token = login_resp.access_token.as_str();
+
+    let space = create_space(&client, access_token).await.unwrap();
+    let board = create_board(
+        &client,
+        access_token,
+        space.room_id.as_str(),
+        "general",
+        None,
+    )
+    .await
+    .unwrap();
+    let board_id = board.room_id.as_str();
+
+    let post = create_post(&client, access_token, board_id, "txn1", "first")
+        .await
+        .unwrap();
+    let post_id = post.event_id.as_str();
+
+    let mut votes = Vec::new();
+
+    for vote in [Some(Vote::Up), Some(Vote::Down), None] {
+        let resp = client
+            .put(&format!(
+                "/_commune/client/r0/boards/{board_id}/posts/{post_id}/vote"
+            ))
+            .bearer_auth(access_token)
+            .json(&vote::Payload { vote })
+            .send()
+            .await
+            .unwrap()
+            .json::<Votes>()
+            .await
+            .unwrap();
+
+        votes.push(resp);
+    }
+
+    assert_eq!(
+        votes,
+        [
+            Votes { up: 1, down: 0 },
+            Votes { up: 0, down: 1 },
+            Votes { up: 0, down: 0 },
+        ]
+    );
+}
@@ -5,7 +5,7 @@ use router::api::relative::login;
 use crate::{api::relative::register, env::Env};
 
 pub async fn login(client: &Env) -> Result<Response, reqwest::Error> {
-    let register_resp = register::register(&client).await.unwrap();
+    let register_resp = register::register(client).await.unwrap();
 
     tracing::info!(?register_resp);
 
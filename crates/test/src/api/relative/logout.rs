@@ -3,7 +3,7 @@ use matrix::client::logout::root::*;
 use crate::{api::relative::login, env::Env};
 
 pub async fn logout(client: &Env) -> Result<Response, reqwest::Error> {
-    let login_resp = login::login(&client).await.unwrap();
+    let login_resp = login::login(client).await.unwrap();
 
     tracing::info!(?login_resp);
 
@@ -0,0 +1,61 @@
+use matrix::ruma_events::room::history_visibility::HistoryVisibility;
+use reqwest::StatusCode;
+use router::api::settings::update;
+use serde::Deserialize;
+
+use crate::{
+    api::{relative::login, space::create::create_space},
+    env::Env,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Settings {
+    pub name: Option<String>,
+    pub topic: Option<String>,
+    pub history_visibility: HistoryVisibility,
+}
+
+#[tokio::test]
+async fn update_settings_test() {
+    let client = Env::new().await;
+
+    let owner = login::login(&client).await.unwrap();
+    let space = create_space(&client, &owner.access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+
+    let resp = client
+        .put(&format!("/_commune/client/r0/rooms/{space_id}/settings"))
+        .bearer_auth(&owner.access_token)
+        .json(&update::Payload {
+            topic: Some("all things rust".to_owned()),
+            history_visibility: Some(HistoryVisibility::WorldReadable),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<Settings>()
+        .await
+        .unwrap();
+
+    tracing::info!(?resp);
+
+    assert_eq!(resp.name.as_deref(), Some("rustaceans"));
+    assert_eq!(resp.topic.as_deref(), Some("all things rust"));
+    assert_eq!(resp.history_visibility, HistoryVisibility::WorldReadable);
+
+    let member = login::login(&client).await.unwrap();
+
+    let resp = client
+        .put(&format!("/_commune/client/r0/rooms/{space_id}/settings"))
+        .bearer_auth(&member.access_token)
+        .json(&update::Payload {
+            name: Some("crustaceans".to_owned()),
+            ..Default::default()
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,4 @@
+pub mod board;
+pub mod create;
+pub mod search;
+pub mod unread;
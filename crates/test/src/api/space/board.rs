@@ -0,0 +1,193 @@
+use commune::space::board::Access;
+use matrix::client::create_room::Response;
+use reqwest::StatusCode;
+use router::api::{membership::join, space::board::create};
+use serde::Deserialize;
+
+use crate::{
+    api::{post::create_post, relative::login, space::create::create_space},
+    env::Env,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Board {
+    pub room_id: String,
+    pub name: Option<String>,
+    pub order: Option<String>,
+    pub archived: bool,
+    pub joined_members: u64,
+}
+
+pub async fn create_board(
+    client: &Env,
+    access_token: &str,
+    space_id: &str,
+    name: &str,
+    order: Option<&str>,
+) -> Result<Response, reqwest::Error> {
+    let resp = client
+        .post(&format!("/_commune/client/r0/spaces/{space_id}/boards"))
+        .bearer_auth(access_token)
+        .json(&create::Payload {
+            name: name.to_owned(),
+            topic: None,
+            order: order.map(ToOwned::to_owned),
+            default: false,
+            access: Access::Public,
+        })
+        .send()
+        .await
+        .unwrap();
+
+    resp.json::<Response>().await
+}
+
+#[tokio::test]
+async fn list_boards_test() {
+    let client = Env::new().await;
+
+    let login_resp = login::login(&client).await.unwrap();
+    let access_token = login_resp.access_token.as_str();
+
+    let space = create_space(&client, access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+
+    let _ = create_board(&client, access_token, space_id, "general", Some("b"))
+        .await
+        .unwrap();
+    let _ = create_board(&client, access_token, space_id, "announcements", Some("a"))
+        .await
+        .unwrap();
+
+    let resp = client
+        .get(&format!("/_commune/client/r0/spaces/{space_id}/boards"))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Vec<Board>>()
+        .await
+        .unwrap();
+
+    tracing::info!(?resp);
+
+    let names: Vec<_> = resp.iter().filter_map(|b| b.name.as_deref()).collect();
+
+    assert_eq!(names, ["announcements", "general"]);
+    assert!(resp.iter().all(|b| !b.archived && b.joined_members == 1));
+}
+
+#[tokio::test]
+async fn private_board_test() {
+    let client = Env::new().await;
+
+    let owner = login::login(&client).await.unwrap();
+    let space = create_space(&client, &owner.access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+
+    let board = client
+        .post(&format!("/_commune/client/r0/spaces/{space_id}/boards"))
+        .bearer_auth(&owner.access_token)
+        .json(&create::Payload {
+            name: "moderators".to_owned(),
+            topic: None,
+            order: None,
+            default: false,
+            access: Access::Invite,
+        })
+        .send()
+        .await
+        .unwrap()
+        .json::<Response>()
+        .await
+        .unwrap();
+    let board_id = board.room_id.as_str();
+
+    let _ = create_post(&client, &owner.access_token, board_id, "txn1", "secret")
+        .await
+        .unwrap();
+
+    let member = login::login(&client).await.unwrap();
+
+    let _ = client
+        .post(&format!("/_commune/client/r0/rooms/{space_id}/join"))
+        .bearer_auth(&member.access_token)
+        .json(&join::Payload::default())
+        .send()
+        .await
+        .unwrap();
+
+    let boards = client
+        .get(&format!("/_commune/client/r0/spaces/{space_id}/boards"))
+        .bearer_auth(&member.access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Vec<Board>>()
+        .await
+        .unwrap();
+
+    assert!(boards.iter().all(|b| b.room_id != board_id));
+
+    let posts = format!("/_commune/client/r0/boards/{board_id}/posts");
+
+    let resp = client
+        .get(&posts)
+        .bearer_auth(&member.access_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = client.get(&posts).send().await.unwrap();
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let resp = client
+        .get(&posts)
+        .bearer_auth(&owner.access_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn delete_board_of_another_space_test() {
+    let client = Env::new().await;
+
+    let owner = login::login(&client).await.unwrap();
+    let space = create_space(&client, &owner.access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+    let board = create_board(&client, &owner.access_token, space_id, "general", None)
+        .await
+        .unwrap();
+    let board_id = board.room_id.as_str();
+
+    // a space of their own does not let anyone purge boards of others
+    let mallory = login::login(&client).await.unwrap();
+    let own_space = create_space(&client, &mallory.access_token).await.unwrap();
+    let own_space_id = own_space.room_id.as_str();
+
+    let resp = client
+        .delete(&format!(
+            "/_commune/client/r0/spaces/{own_space_id}/boards/{board_id}"
+        ))
+        .bearer_auth(&mallory.access_token)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+    let boards = client
+        .get(&format!("/_commune/client/r0/spaces/{space_id}/boards"))
+        .bearer_auth(&owner.access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Vec<Board>>()
+        .await
+        .unwrap();
+
+    assert!(boards.iter().any(|b| b.room_id == board_id));
+}
@@ -0,0 +1,34 @@
+use matrix::client::create_room::*;
+use router::api::space::create;
+
+use crate::{api::relative::login, env::Env};
+
+pub async fn create_space(client: &Env, access_token: &str) -> Result<Response, reqwest::Error> {
+    let resp = client
+        .post("/_commune/client/r0/spaces")
+        .bearer_auth(access_token)
+        .json(&create::Payload {
+            name: "rustaceans".to_owned(),
+            topic: Some("a space for rustaceans".to_owned()),
+            public: true,
+        })
+        .send()
+        .await
+        .unwrap();
+
+    resp.json::<Response>().await
+}
+
+#[tokio::test]
+async fn create_space_test() {
+    let client = Env::new().await;
+
+    let login_resp = login::login(&client).await.unwrap();
+    let resp = create_space(&client, &login_resp.access_token)
+        .await
+        .unwrap();
+
+    tracing::info!(?resp);
+
+    assert!(resp.room_id.as_str().starts_with('!'));
+}
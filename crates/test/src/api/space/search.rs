@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+use crate::{
+    api::{
+        post::create_post,
+        relative::login,
+        space::{board::create_board, create::create_space},
+    },
+    env::Env,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Hit {
+    pub board_id: String,
+    pub post_id: String,
+    pub title: Option<String>,
+    pub snippet: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Results {
+    pub results: Vec<Hit>,
+}
+
+#[tokio::test]
+async fn search_space_test() {
+    let client = Env::new().await;
+
+    let owner = login::login(&client).await.unwrap();
+    let access_token = owner.access_token.as_str();
+
+    let space = create_space(&client, access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+    let board = create_board(&client, access_token, space_id, "general", None)
+        .await
+        .unwrap();
+    let post = create_post(&client, access_token, board.room_id.as_str(), "1", "ferris")
+        .await
+        .unwrap();
+
+    let resp = client
+        .get(&format!(
+            "/_commune/client/r0/spaces/{space_id}/search?q=world&order=recent"
+        ))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Results>()
+        .await
+        .unwrap();
+
+    tracing::info!(?resp);
+
+    let hit = resp
+        .results
+        .iter()
+        .find(|hit| hit.post_id == post.event_id.as_str())
+        .unwrap();
+
+    assert_eq!(hit.board_id, board.room_id.as_str());
+    assert_eq!(hit.title.as_deref(), Some("ferris"));
+    assert!(hit.snippet.contains("world"));
+}
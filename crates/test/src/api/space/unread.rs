@@ -0,0 +1,95 @@
+use router::api::{membership::join, read::mark};
+use serde::Deserialize;
+
+use crate::{
+    api::{
+        post::create_post,
+        relative::login,
+        space::{board::create_board, create::create_space},
+    },
+    env::Env,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Board {
+    pub board_id: String,
+    pub notification_count: usize,
+    pub fully_read: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Unread {
+    pub notification_count: usize,
+    pub boards: Vec<Board>,
+}
+
+async fn unread(client: &Env, access_token: &str, space_id: &str) -> Unread {
+    client
+        .get(&format!("/_commune/client/r0/spaces/{space_id}/unread"))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .unwrap()
+        .json::<Unread>()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn read_markers_test() {
+    let client = Env::new().await;
+
+    let owner = login::login(&client).await.unwrap();
+    let space = create_space(&client, &owner.access_token).await.unwrap();
+    let space_id = space.room_id.as_str();
+    let board = create_board(&client, &owner.access_token, space_id, "general", None)
+        .await
+        .unwrap();
+    let board_id = board.room_id.as_str();
+
+    let member = login::login(&client).await.unwrap();
+
+    for room_id in [space_id, board_id] {
+        client
+            .post(&format!("/_commune/client/r0/rooms/{room_id}/join"))
+            .bearer_auth(&member.access_token)
+            .json(&join::Payload::default())
+            .send()
+            .await
+            .unwrap();
+    }
+
+    let post = create_post(&client, &owner.access_token, board_id, "1", "unread")
+        .await
+        .unwrap();
+
+    let resp = client
+        .post(&format!(
+            "/_commune/client/r0/rooms/{board_id}/read_markers"
+        ))
+        .bearer_auth(&member.access_token)
+        .json(&mark::Payload {
+            fully_read: Some(post.event_id.clone()),
+            read: Some(post.event_id.clone()),
+            private: false,
+        })
+        .send()
+        .await
+        .unwrap();
+
+    assert!(resp.status().is_success());
+
+    let resp = unread(&client, &member.access_token, space_id).await;
+
+    tracing::info!(?resp);
+
+    assert_eq!(resp.notification_count, 0);
+
+    let board = resp
+        .boards
+        .iter()
+        .find(|board| board.board_id == board_id)
+        .unwrap();
+    assert_eq!(board.notification_count, 0);
+    assert_eq!(board.fully_read.as_deref(), Some(post.event_id.as_str()));
+}
@@ -63,4 +63,16 @@ impl Env {
 
         self.client.post(self.path(url))
     }
+
+    pub(crate) fn put(&self, url: &str) -> reqwest::RequestBuilder {
+        tracing::info!("PUT {}", self.path(url));
+
+        self.client.put(self.path(url))
+    }
+
+    pub(crate) fn delete(&self, url: &str) -> reqwest::RequestBuilder {
+        tracing::info!("DELETE {}", self.path(url));
+
+        self.client.delete(self.path(url))
+    }
 }